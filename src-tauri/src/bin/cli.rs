@@ -3,7 +3,17 @@
 
 use clap::{Parser, Subcommand};
 use colored::*;
-use rusqlite::{params, Connection};
+use geekfit_lib::data_io::{build_export_json, import_json};
+use geekfit_lib::levels::{
+    clamp_max_level, decayed_totals, get_title_for_level, xp_for_level, DEFAULT_MAX_LEVEL,
+};
+use geekfit_lib::streaks::{
+    active_dates, compute_streaks, effective_date, effective_today, local_now, local_timestamp,
+    log_day_sql, record_daily_goal_change, streak_runs,
+};
+use geekfit_lib::{log_exercise_at_time, FALLBACK_MARKER};
+use rusqlite::{params, Connection, ErrorCode, Transaction, TransactionBehavior};
+use std::io::Read;
 use std::path::PathBuf;
 
 /// GeekFit CLI - Gamified fitness tracker for your terminal
@@ -45,26 +55,46 @@ enum Commands {
     Today,
     /// Show achievements
     Achievements,
+    /// Log many entries from stdin, one `exercise,reps[,timestamp]` per line
+    Batch,
+    /// Export all data as JSON to a file, or stdout without a path
+    Export {
+        /// File to write the backup to
+        path: Option<PathBuf>,
+    },
+    /// Import a JSON backup, replacing current data unless --merge is given
+    Import {
+        /// Backup file made by `geekfit export` or the app
+        path: PathBuf,
+        /// Keep whichever side has more XP per exercise instead of replacing
+        #[arg(long)]
+        merge: bool,
+    },
+    /// Show current and longest streak with a recent activity strip
+    Streak {
+        /// Number of days in the activity strip (default: 7)
+        #[arg(short, long, default_value = "7")]
+        days: i64,
+    },
+    /// Show the daily XP goal, or set it (e.g., geekfit goal 800)
+    Goal {
+        /// New daily goal in XP
+        value: Option<i32>,
+    },
 }
 
-// XP calculation (same as main app)
-fn xp_for_level(level: i32) -> i64 {
-    if level <= 1 {
-        return 0;
-    }
-    let mut total: f64 = 0.0;
-    for i in 1..level {
-        total += (i as f64) + 300.0 * 2.0_f64.powf((i as f64) / 7.0);
-    }
-    (total / 4.0).floor() as i64
-}
-
-fn level_from_xp(xp: i64) -> i32 {
-    let mut level = 1;
-    while xp_for_level(level + 1) <= xp && level < 99 {
-        level += 1;
-    }
-    level
+// Level cap configured in the app's settings (same as main app)
+fn get_max_level(conn: &Connection) -> i32 {
+    let max_level = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = 'max_level'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_LEVEL);
+    clamp_max_level(max_level)
 }
 
 fn get_db_path() -> PathBuf {
@@ -84,7 +114,14 @@ fn get_db_path() -> PathBuf {
             .join("com.geekfit.app")
     };
 
-    app_dir.join("geekfit.db")
+    // The app moves to a marked fallback folder when its data directory is read-only
+    let adopted = dirs::document_dir()
+        .map(|dir| dir.join("GeekFit"))
+        .into_iter()
+        .chain(std::iter::once(std::env::temp_dir().join("GeekFit")))
+        .find(|dir| dir.join(FALLBACK_MARKER).exists());
+
+    adopted.unwrap_or(app_dir).join("geekfit.db")
 }
 
 fn open_database() -> Result<Connection, String> {
@@ -97,7 +134,103 @@ fn open_database() -> Result<Connection, String> {
         ));
     }
 
-    Connection::open(&db_path).map_err(|e| format!("Failed to open database: {}", e))
+    let mut conn =
+        Connection::open(&db_path).map_err(|e| format!("Failed to open database: {}", e))?;
+
+    // Follow the app's active profile; profiles other than the default have their own file
+    let profile_id: i64 = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = 'active_profile_id'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1);
+    if profile_id != 1 {
+        let profile_path = db_path
+            .with_file_name("profiles")
+            .join(format!("profile-{}.db", profile_id));
+        if !profile_path.exists() {
+            return Err(format!(
+                "Database for the active profile {} not found at {:?}\nSwitch profiles in the GeekFit app to recreate it.",
+                profile_id, profile_path
+            ));
+        }
+        conn = Connection::open(&profile_path)
+            .map_err(|e| format!("Failed to open profile database: {}", e))?;
+    }
+
+    // The GUI may be writing at the same time; wait for its lock instead of failing
+    conn.busy_timeout(std::time::Duration::from_millis(3000))
+        .map_err(|e| format!("Failed to configure database: {}", e))?;
+    Ok(conn)
+}
+
+const MAX_BUSY_RETRIES: u32 = 4;
+
+/// A failed CLI write. SQLite's busy and locked errors are told apart by their error
+/// code before they become a message, so only those are retried.
+#[derive(Debug, PartialEq)]
+enum WriteError {
+    Busy(String),
+    Failed(String),
+}
+
+impl From<rusqlite::Error> for WriteError {
+    fn from(e: rusqlite::Error) -> Self {
+        match &e {
+            rusqlite::Error::SqliteFailure(failure, _)
+                if matches!(
+                    failure.code,
+                    ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked
+                ) =>
+            {
+                WriteError::Busy(e.to_string())
+            }
+            _ => WriteError::Failed(e.to_string()),
+        }
+    }
+}
+
+impl From<String> for WriteError {
+    fn from(message: String) -> Self {
+        WriteError::Failed(message)
+    }
+}
+
+impl std::fmt::Display for WriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WriteError::Busy(message) | WriteError::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+/// Starts an IMMEDIATE transaction, taking the write lock up front. A busy database then
+/// fails here or at commit, where the error still carries its SQLite code, and never
+/// halfway through `f`'s statements.
+fn begin_write(conn: &Connection) -> Result<Transaction<'_>, WriteError> {
+    Ok(Transaction::new_unchecked(
+        conn,
+        TransactionBehavior::Immediate,
+    )?)
+}
+
+/// Runs `f` again with exponential backoff (100ms, 200ms, ...) while SQLite reports
+/// the database as busy or locked. `f` must be safe to repeat, i.e. run inside a
+/// `begin_write` transaction.
+fn with_retry<T>(mut f: impl FnMut() -> Result<T, WriteError>) -> Result<T, String> {
+    let mut attempt = 0;
+    loop {
+        match f() {
+            Err(WriteError::Busy(_)) if attempt < MAX_BUSY_RETRIES => {
+                std::thread::sleep(std::time::Duration::from_millis(100 << attempt));
+                attempt += 1;
+            }
+            result => return result.map_err(|e| e.to_string()),
+        }
+    }
 }
 
 fn find_exercise(conn: &Connection, search: &str) -> Result<(i64, String, i32), String> {
@@ -133,84 +266,38 @@ fn log_exercise(
     exercise_id: i64,
     reps: i32,
 ) -> Result<(i32, i32, bool), String> {
-    // Get current exercise stats
-    let (xp_per_rep, old_xp, old_level): (i32, i64, i32) = conn
-        .query_row(
-            "SELECT xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1) FROM exercises WHERE id = ?",
-            params![exercise_id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        )
-        .map_err(|e| e.to_string())?;
-
-    let xp_earned = xp_per_rep * reps;
-    let new_xp = old_xp + xp_earned as i64;
-    let new_level = level_from_xp(new_xp);
-    let leveled_up = new_level > old_level;
-
-    // Log the exercise
-    conn.execute(
-        "INSERT INTO exercise_logs (exercise_id, reps, xp_earned, logged_at) VALUES (?, ?, ?, datetime('now', 'localtime'))",
-        params![exercise_id, reps, xp_earned],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Update exercise XP and level
-    conn.execute(
-        "UPDATE exercises SET total_xp = ?, current_level = ? WHERE id = ?",
-        params![new_xp, new_level, exercise_id],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Update streak
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let last_date: Option<String> = conn
-        .query_row(
-            "SELECT last_exercise_date FROM user_stats WHERE id = 1",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(None);
-
-    let (current_streak, longest_streak): (i32, i32) = conn
-        .query_row(
-            "SELECT current_streak, longest_streak FROM user_stats WHERE id = 1",
-            [],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
-        .unwrap_or((0, 0));
-
-    let new_streak = match &last_date {
-        Some(date) => {
-            if date == &today {
-                current_streak
-            } else {
-                let yesterday = (chrono::Local::now() - chrono::Duration::days(1))
-                    .format("%Y-%m-%d")
-                    .to_string();
-                if date == &yesterday {
-                    current_streak + 1
-                } else {
-                    1
-                }
-            }
-        }
-        None => 1,
-    };
-    let new_longest = std::cmp::max(new_streak, longest_streak);
-
-    conn.execute(
-        "UPDATE user_stats SET current_streak = ?, longest_streak = ?, last_exercise_date = ? WHERE id = 1",
-        params![new_streak, new_longest, today],
-    )
-    .map_err(|e| e.to_string())?;
+    with_retry(|| {
+        let tx = begin_write(conn)?;
+        let result = log_exercise_at(&tx, exercise_id, reps, None)?;
+        tx.commit()?;
+        Ok(result)
+    })
+}
 
-    Ok((xp_earned, new_level, leveled_up))
+/// Logs through the app's own path, so a CLI log is validated and judged for streaks,
+/// achievements and challenges exactly like one made in the app. Defaults to now in
+/// the home timezone.
+fn log_exercise_at(
+    conn: &Connection,
+    exercise_id: i64,
+    reps: i32,
+    logged_at: Option<&str>,
+) -> Result<(i32, i32, bool), String> {
+    let logged_at = logged_at
+        .map(str::to_string)
+        .unwrap_or_else(|| local_timestamp(conn));
+    let result = log_exercise_at_time(conn, exercise_id, reps, &logged_at)?;
+    Ok((
+        result.xp_earned,
+        result.new_exercise_level,
+        result.leveled_up,
+    ))
 }
 
-fn print_level_bar(level: i32, xp: i64) -> String {
+fn print_level_bar(level: i32, xp: i64, max_level: i32) -> String {
     let xp_for_current = xp_for_level(level);
     let xp_for_next = xp_for_level(level + 1);
-    let progress = if level >= 99 {
+    let progress = if level >= max_level {
         1.0
     } else {
         (xp - xp_for_current) as f64 / (xp_for_next - xp_for_current) as f64
@@ -238,18 +325,6 @@ fn format_xp(xp: i64) -> String {
     }
 }
 
-fn get_title_for_level(level: i32) -> &'static str {
-    match level {
-        0..=4 => "Novice Geek",
-        5..=9 => "Fitness Apprentice",
-        10..=19 => "Gym Initiate",
-        20..=29 => "Strength Seeker",
-        30..=39 => "Endurance Elite",
-        40..=49 => "Fitness Warrior",
-        _ => "Legendary Geek",
-    }
-}
-
 fn cmd_log(exercise: &str, reps: i32) {
     let conn = match open_database() {
         Ok(c) => c,
@@ -314,14 +389,26 @@ fn cmd_stats() {
         }
     };
 
-    // Get totals
-    let (total_xp, total_level, exercise_count): (i64, i32, i32) = conn
-        .query_row(
-            "SELECT COALESCE(SUM(total_xp), 0), COALESCE(SUM(current_level), 0), COUNT(*) FROM exercises",
-            [],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        )
-        .unwrap_or((0, 0, 0));
+    // Get totals, with any XP decay applied as in the app
+    let today = effective_today(&conn);
+    let mut stmt = conn
+        .prepare("SELECT id, COALESCE(total_xp, 0), COALESCE(current_level, 1) FROM exercises")
+        .expect("Failed to prepare statement");
+    let totals: Vec<(i64, i32)> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i32>(2)?,
+            ))
+        })
+        .expect("Failed to query exercises")
+        .filter_map(|r| r.ok())
+        .map(|(id, xp, level)| decayed_totals(&conn, id, xp, level, today))
+        .collect();
+    let total_xp: i64 = totals.iter().map(|(xp, _)| xp).sum();
+    let total_level: i32 = totals.iter().map(|(_, level)| level).sum();
+    let exercise_count = totals.len() as i32;
 
     let (current_streak, longest_streak): (i32, i32) = conn
         .query_row(
@@ -332,10 +419,20 @@ fn cmd_stats() {
         .unwrap_or((0, 0));
 
     let title = get_title_for_level(total_level / exercise_count.max(1));
+    let user_name: String = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = 'user_name'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or_default();
 
     println!();
     println!("{}", " GEEKFIT STATS ".on_blue().white().bold());
     println!();
+    if !user_name.is_empty() {
+        println!("  {}  {}", "Name:".dimmed(), user_name.bold());
+    }
     println!("  {}  {}", "Title:".dimmed(), title.cyan().bold());
     println!(
         "  {}  {}",
@@ -377,18 +474,31 @@ fn cmd_list() {
 
     let mut stmt = conn
         .prepare(
-            "SELECT name, xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1)
+            "SELECT id, name, xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1)
              FROM exercises ORDER BY current_level DESC, total_xp DESC",
         )
         .expect("Failed to prepare statement");
 
+    // With any XP decay applied as in the app
+    let today = effective_today(&conn);
     let exercises: Vec<(String, i32, i64, i32)> = stmt
         .query_map([], |row| {
-            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i32>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i32>(4)?,
+            ))
         })
         .expect("Failed to query exercises")
         .filter_map(|r| r.ok())
+        .map(|(id, name, xp_per_rep, xp, level)| {
+            let (xp, level) = decayed_totals(&conn, id, xp, level, today);
+            (name, xp_per_rep, xp, level)
+        })
         .collect();
+    let max_level = get_max_level(&conn);
 
     println!();
     println!("{}", " EXERCISES ".on_green().black().bold());
@@ -421,7 +531,7 @@ fn cmd_list() {
             level_colored,
             xp_per_rep.to_string().dimmed(),
             format_xp(total_xp).yellow(),
-            print_level_bar(level, total_xp)
+            print_level_bar(level, total_xp, max_level)
         );
     }
     println!();
@@ -442,14 +552,14 @@ fn cmd_history(days: i32) {
             "SELECT e.name, el.reps, el.xp_earned, el.logged_at
              FROM exercise_logs el
              JOIN exercises e ON el.exercise_id = e.id
-             WHERE el.logged_at >= datetime('now', 'localtime', ? || ' days')
+             WHERE el.logged_at >= datetime(?, ? || ' days')
              ORDER BY el.logged_at DESC
              LIMIT 50",
         )
         .expect("Failed to prepare statement");
 
     let logs: Vec<(String, i32, i32, String)> = stmt
-        .query_map([days_param], |row| {
+        .query_map([local_timestamp(&conn), days_param], |row| {
             Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
         })
         .expect("Failed to query logs")
@@ -488,7 +598,7 @@ fn cmd_history(days: i32) {
             let date_str = if let Ok(parsed) =
                 chrono::NaiveDateTime::parse_from_str(&logged_at, "%Y-%m-%d %H:%M:%S")
             {
-                let now = chrono::Local::now().naive_local();
+                let now = local_now(&conn);
                 let diff = now.date() - parsed.date();
 
                 if diff.num_days() == 0 {
@@ -514,7 +624,55 @@ fn cmd_history(days: i32) {
     println!();
 }
 
-fn cmd_today() {
+fn get_today_xp(conn: &Connection, today: &str) -> i64 {
+    conn.query_row(
+        &format!(
+            "SELECT COALESCE(SUM(xp_earned), 0) FROM exercise_logs WHERE {} = ?",
+            log_day_sql(conn, "logged_at")
+        ),
+        params![today],
+        |row| row.get(0),
+    )
+    .unwrap_or(0)
+}
+
+fn get_daily_goal(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT COALESCE(value, '500') FROM settings WHERE key = 'daily_goal_xp'",
+        [],
+        |row| {
+            let val: String = row.get(0)?;
+            Ok(val.parse::<i64>().unwrap_or(500))
+        },
+    )
+    .unwrap_or(500)
+}
+
+/// Saves a new daily goal, recording the change so the goal streak judges past days fairly.
+fn set_daily_goal(conn: &Connection, goal: i32) -> Result<(), WriteError> {
+    if goal <= 0 {
+        return Err("Daily goal must be positive".to_string().into());
+    }
+    let previous_goal = get_daily_goal(conn);
+    if goal as i64 == previous_goal {
+        return Ok(());
+    }
+
+    let tx = begin_write(conn)?;
+    record_daily_goal_change(
+        &tx,
+        previous_goal,
+        goal as i64,
+        &effective_today(&tx).format("%Y-%m-%d").to_string(),
+    )?;
+    tx.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('daily_goal_xp', ?)",
+        params![goal.to_string()],
+    )?;
+    Ok(tx.commit()?)
+}
+
+fn cmd_goal(value: Option<i32>) {
     let conn = match open_database() {
         Ok(c) => c,
         Err(e) => {
@@ -523,39 +681,213 @@ fn cmd_today() {
         }
     };
 
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    if let Some(goal) = value {
+        if let Err(e) = with_retry(|| set_daily_goal(&conn, goal)) {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+        println!(
+            "{} Daily goal set to {} XP",
+            "+".green().bold(),
+            format_xp(goal as i64).yellow().bold()
+        );
+        return;
+    }
 
-    // Get today's XP
-    let today_xp: i64 = conn
+    let today = effective_today(&conn).format("%Y-%m-%d").to_string();
+    let today_xp = get_today_xp(&conn, &today);
+    let daily_goal = get_daily_goal(&conn);
+    println!();
+    println!(
+        "  {}  {} XP",
+        "Daily goal:".dimmed(),
+        format_xp(daily_goal).yellow().bold()
+    );
+    println!(
+        "  {}  {} / {} XP ({}%)",
+        "Today:".dimmed(),
+        format_xp(today_xp).cyan(),
+        format_xp(daily_goal),
+        (today_xp * 100 / daily_goal.max(1)).min(100)
+    );
+    println!();
+}
+
+fn cmd_export(path: Option<PathBuf>) {
+    let conn = match open_database() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let json = match build_export_json(&conn) {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("{} Export failed: {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    match path {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, json) {
+                eprintln!(
+                    "{} Failed to write {:?}: {}",
+                    "Error:".red().bold(),
+                    path,
+                    e
+                );
+                std::process::exit(1);
+            }
+            println!("{} Exported to {}", "+".green().bold(), path.display());
+        }
+        None => println!("{}", json),
+    }
+}
+
+fn cmd_import(path: PathBuf, merge: bool) {
+    let conn = match open_database() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let json = match std::fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("{} Failed to read {:?}: {}", "Error:".red().bold(), path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let mode = if merge { "keep_higher" } else { "replace" };
+    let imported = with_retry(|| {
+        let tx = begin_write(&conn)?;
+        import_json(&tx, &json, Some(mode))?;
+        tx.commit()?;
+        Ok(())
+    });
+    if let Err(e) = imported {
+        eprintln!("{} Import failed: {}", "Error:".red().bold(), e);
+        std::process::exit(1);
+    }
+    println!(
+        "{} Imported {} ({})",
+        "+".green().bold(),
+        path.display(),
+        if merge { "merged" } else { "replaced" }
+    );
+}
+
+const ACTIVE_DAY: char = '■';
+const REST_DAY: char = '·';
+
+/// One block per day for the `days` ending `today`, oldest first.
+fn activity_strip(active: &[chrono::NaiveDate], today: chrono::NaiveDate, days: i64) -> String {
+    (0..days.max(1))
+        .rev()
+        .map(|ago| {
+            if active.contains(&(today - chrono::Duration::days(ago))) {
+                ACTIVE_DAY
+            } else {
+                REST_DAY
+            }
+        })
+        .collect()
+}
+
+fn cmd_streak(days: i64) {
+    let conn = match open_database() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let dates = match active_dates(&conn) {
+        Ok(dates) => dates,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+    let today = effective_today(&conn);
+    let (current, longest) = compute_streaks(&dates, today);
+    // Imported records can beat what the logs alone show
+    let stored_longest: i32 = conn
         .query_row(
-            "SELECT COALESCE(SUM(xp_earned), 0) FROM exercise_logs WHERE DATE(logged_at) = ?",
-            params![today],
+            "SELECT longest_streak FROM user_stats WHERE id = 1",
+            [],
             |row| row.get(0),
         )
         .unwrap_or(0);
 
-    // Get daily goal
-    let daily_goal: i64 = conn
-        .query_row(
-            "SELECT COALESCE(value, '500') FROM settings WHERE key = 'daily_goal_xp'",
-            [],
-            |row| {
-                let val: String = row.get(0)?;
-                Ok(val.parse::<i64>().unwrap_or(500))
-            },
-        )
-        .unwrap_or(500);
+    println!();
+    println!("{}", " STREAK ".on_red().white().bold());
+    println!();
+    println!(
+        "  {}  {} days",
+        "Current:".dimmed(),
+        current.to_string().red().bold()
+    );
+    if let Some((start, _)) = streak_runs(&dates).last().filter(|_| current > 0) {
+        println!("  {}  {}", "Since:".dimmed(), start.format("%b %d"));
+    }
+    println!(
+        "  {}  {} days",
+        "Longest:".dimmed(),
+        longest.max(stored_longest).to_string().yellow()
+    );
+    println!();
+
+    let strip: String = activity_strip(&dates, today, days)
+        .chars()
+        .map(|day| {
+            if day == ACTIVE_DAY {
+                day.to_string().green().to_string()
+            } else {
+                day.to_string().dimmed().to_string()
+            }
+        })
+        .collect();
+    println!(
+        "  {}  {}",
+        format!("Last {} days:", days.max(1)).dimmed(),
+        strip
+    );
+    println!();
+}
+
+fn cmd_today() {
+    let conn = match open_database() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let today = effective_today(&conn).format("%Y-%m-%d").to_string();
+
+    let today_xp = get_today_xp(&conn, &today);
+    let daily_goal = get_daily_goal(&conn);
 
     // Get today's exercises
     let mut stmt = conn
-        .prepare(
+        .prepare(&format!(
             "SELECT e.name, SUM(el.reps), SUM(el.xp_earned)
              FROM exercise_logs el
              JOIN exercises e ON el.exercise_id = e.id
-             WHERE DATE(el.logged_at) = ?
+             WHERE {} = ?
              GROUP BY e.name
              ORDER BY SUM(el.xp_earned) DESC",
-        )
+            log_day_sql(&conn, "el.logged_at")
+        ))
         .expect("Failed to prepare statement");
 
     let exercises: Vec<(String, i32, i32)> = stmt
@@ -728,6 +1060,185 @@ fn cmd_achievements() {
     println!();
 }
 
+#[derive(Debug, PartialEq)]
+struct BatchEntry {
+    line: usize,
+    exercise: String,
+    reps: i32,
+    timestamp: Option<String>,
+}
+
+/// Parses `exercise,reps[,timestamp]` lines, returning the entries and (line, reason) for
+/// lines that couldn't be parsed. Blank lines and `#` comments are skipped.
+fn parse_batch_input(input: &str) -> (Vec<BatchEntry>, Vec<(usize, String)>) {
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, raw) in input.lines().enumerate() {
+        let line = index + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = trimmed.split(',').map(|f| f.trim()).collect();
+        if fields.len() < 2 || fields.len() > 3 || fields[0].is_empty() {
+            errors.push((
+                line,
+                format!("expected exercise,reps[,timestamp]: '{}'", trimmed),
+            ));
+            continue;
+        }
+
+        let reps = match fields[1].parse::<i32>() {
+            Ok(r) => r,
+            Err(_) => {
+                errors.push((line, format!("invalid rep count '{}'", fields[1])));
+                continue;
+            }
+        };
+
+        let timestamp = match fields.get(2) {
+            Some(ts) => {
+                if chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S").is_err() {
+                    errors.push((
+                        line,
+                        format!("invalid timestamp '{}' (use YYYY-MM-DD HH:MM:SS)", ts),
+                    ));
+                    continue;
+                }
+                Some(ts.to_string())
+            }
+            None => None,
+        };
+
+        entries.push(BatchEntry {
+            line,
+            exercise: fields[0].to_string(),
+            reps,
+            timestamp,
+        });
+    }
+
+    (entries, errors)
+}
+
+#[derive(Default)]
+struct BatchSummary {
+    logged: i32,
+    total_reps: i64,
+    total_xp: i64,
+    level_ups: i32,
+    errors: Vec<(usize, String)>,
+}
+
+/// Logs all entries in one transaction. The write lock is taken up front, so a busy
+/// database fails the whole batch before anything is logged and it can be retried.
+fn log_batch(conn: &Connection, entries: &[BatchEntry]) -> Result<BatchSummary, WriteError> {
+    let tx = begin_write(conn)?;
+    let mut summary = BatchSummary::default();
+
+    for entry in entries {
+        let (exercise_id, _name, _xp_per_rep) = match find_exercise(&tx, &entry.exercise) {
+            Ok(e) => e,
+            Err(e) => {
+                summary.errors.push((entry.line, e));
+                continue;
+            }
+        };
+
+        match log_exercise_at(&tx, exercise_id, entry.reps, entry.timestamp.as_deref()) {
+            Ok((xp_earned, _new_level, leveled_up)) => {
+                summary.logged += 1;
+                summary.total_reps += entry.reps as i64;
+                summary.total_xp += xp_earned as i64;
+                if leveled_up {
+                    summary.level_ups += 1;
+                }
+            }
+            Err(e) => summary.errors.push((entry.line, e)),
+        }
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
+
+fn cmd_batch() {
+    let conn = match open_database() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut input = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+        eprintln!("{} Failed to read stdin: {}", "Error:".red().bold(), e);
+        std::process::exit(1);
+    }
+
+    let (entries, mut errors) = parse_batch_input(&input);
+
+    let summary = match with_retry(|| log_batch(&conn, &entries)) {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!(
+                "{} Batch failed, nothing was logged: {}",
+                "Error:".red().bold(),
+                e
+            );
+            std::process::exit(1);
+        }
+    };
+    let BatchSummary {
+        logged,
+        total_reps,
+        total_xp,
+        level_ups,
+        errors: entry_errors,
+    } = summary;
+    errors.extend(entry_errors);
+
+    errors.sort_by_key(|(line, _)| *line);
+
+    println!();
+    println!("{}", " BATCH LOG ".on_blue().white().bold());
+    println!();
+    println!(
+        "  {} {} entries logged",
+        "+".green().bold(),
+        logged.to_string().white().bold()
+    );
+    println!(
+        "  {}  {}",
+        "Total reps:".dimmed(),
+        total_reps.to_string().cyan()
+    );
+    println!(
+        "  {}  {}",
+        "Total XP:".dimmed(),
+        format!("+{}", format_xp(total_xp)).yellow().bold()
+    );
+    if level_ups > 0 {
+        println!(
+            "  {}  {}",
+            "Level ups:".dimmed(),
+            level_ups.to_string().magenta().bold()
+        );
+    }
+
+    if !errors.is_empty() {
+        println!();
+        println!("  {} {} lines skipped:", "!".yellow(), errors.len());
+        for (line, reason) in &errors {
+            println!("    {} {}", format!("line {}:", line).dimmed(), reason);
+        }
+    }
+    println!();
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -739,5 +1250,167 @@ fn main() {
         Commands::Today => cmd_today(),
         Commands::Quick { search } => cmd_quick(&search),
         Commands::Achievements => cmd_achievements(),
+        Commands::Batch => cmd_batch(),
+        Commands::Export { path } => cmd_export(path),
+        Commands::Import { path, merge } => cmd_import(path, merge),
+        Commands::Streak { days } => cmd_streak(days),
+        Commands::Goal { value } => cmd_goal(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batch_input_reports_malformed_lines() {
+        let input = "pushups,20\n\
+                     # morning set\n\
+                     \n\
+                     squats, 15, 2024-03-01 07:30:00\n\
+                     burpees\n\
+                     lunges,ten\n\
+                     situps,10,yesterday\n\
+                     jumping jacks,50\n";
+
+        let (entries, errors) = parse_batch_input(input);
+
+        assert_eq!(
+            entries,
+            vec![
+                BatchEntry {
+                    line: 1,
+                    exercise: "pushups".to_string(),
+                    reps: 20,
+                    timestamp: None,
+                },
+                BatchEntry {
+                    line: 4,
+                    exercise: "squats".to_string(),
+                    reps: 15,
+                    timestamp: Some("2024-03-01 07:30:00".to_string()),
+                },
+                BatchEntry {
+                    line: 8,
+                    exercise: "jumping jacks".to_string(),
+                    reps: 50,
+                    timestamp: None,
+                },
+            ]
+        );
+
+        let error_lines: Vec<usize> = errors.iter().map(|(line, _)| *line).collect();
+        assert_eq!(error_lines, vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn test_activity_strip() {
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let active: Vec<chrono::NaiveDate> = [2, 4, 5, 7, 10]
+            .iter()
+            .map(|d| chrono::NaiveDate::from_ymd_opt(2024, 3, *d).unwrap())
+            .collect();
+
+        // Mar 4 through Mar 10
+        assert_eq!(activity_strip(&active, today, 7), "■■·■··■");
+        assert_eq!(activity_strip(&active, today, 1), "■");
+        assert_eq!(activity_strip(&[], today, 3), "···");
+        assert_eq!(activity_strip(&active, today, 30).chars().count(), 30);
+    }
+
+    fn goal_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT);
+             CREATE TABLE daily_goal_history (effective_date TEXT PRIMARY KEY, goal_xp INTEGER NOT NULL);
+             CREATE TABLE exercise_logs (id INTEGER PRIMARY KEY, exercise_id INTEGER, reps INTEGER, xp_earned INTEGER, logged_at TEXT);",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_goal_read_and_write() {
+        let conn = goal_db();
+        assert_eq!(get_daily_goal(&conn), 500);
+
+        let today = effective_today(&conn).format("%Y-%m-%d").to_string();
+        conn.execute(
+            "INSERT INTO exercise_logs (exercise_id, reps, xp_earned, logged_at) VALUES (1, 10, 120, ?)",
+            params![format!("{} 09:00:00", today)],
+        )
+        .unwrap();
+        assert_eq!(get_today_xp(&conn, &today), 120);
+
+        set_daily_goal(&conn, 800).unwrap();
+        assert_eq!(get_daily_goal(&conn), 800);
+        let history: i64 = conn
+            .query_row("SELECT COUNT(*) FROM daily_goal_history", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(history, 2); // Baseline plus the new goal
+
+        assert!(set_daily_goal(&conn, 0).is_err());
+        assert_eq!(get_daily_goal(&conn), 800);
+    }
+
+    fn sqlite_error(code: std::os::raw::c_int) -> WriteError {
+        rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(code),
+            Some("database is locked".to_string()),
+        )
+        .into()
+    }
+
+    fn busy_error() -> WriteError {
+        sqlite_error(rusqlite::ffi::SQLITE_BUSY)
+    }
+
+    #[test]
+    fn test_with_retry_recovers_from_busy() {
+        let mut calls = 0;
+        let result = with_retry(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(busy_error())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result, Ok(3));
+    }
+
+    #[test]
+    fn test_with_retry_gives_up_on_other_errors() {
+        let mut calls = 0;
+        let result: Result<(), String> = with_retry(|| {
+            calls += 1;
+            Err("no such table: exercises".to_string().into())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_busy_errors_classified_by_code() {
+        assert!(matches!(busy_error(), WriteError::Busy(_)));
+        assert!(matches!(
+            sqlite_error(rusqlite::ffi::SQLITE_LOCKED),
+            WriteError::Busy(_)
+        ));
+        // Same wording, different code: a constraint failure is not retried
+        assert!(matches!(
+            sqlite_error(rusqlite::ffi::SQLITE_CONSTRAINT),
+            WriteError::Failed(_)
+        ));
+
+        let mut calls = 0;
+        let result: Result<(), String> = with_retry(|| {
+            calls += 1;
+            Err("No exercise found matching 'busy bees'".to_string().into())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
     }
 }
@@ -0,0 +1,543 @@
+// Backup export and import, shared by the Tauri commands and the CLI so both read and
+// write exactly the same format.
+
+use crate::levels::level_from_xp_capped;
+use crate::streaks::{local_timestamp, recompute_streak};
+use crate::{
+    exercise_from_row, get_max_level, user_stats, Achievement, Exercise, ExerciseLog, Settings,
+    UserStats, EXERCISE_COLUMNS, EXERCISE_SOURCE,
+};
+use rusqlite::{params, Connection, Transaction};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportData {
+    pub version: String,
+    pub exported_at: String,
+    pub exercises: Vec<Exercise>,
+    pub exercise_logs: Vec<ExerciseLog>,
+    pub user_stats: UserStats,
+    pub achievements: Vec<Achievement>,
+    pub settings: Settings,
+    // SHA-256 of the payload without this field; absent in older exports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+}
+
+/// Hashes the export JSON with the checksum removed. Going through `serde_json::Value`
+/// sorts object keys, so formatting and key order in the file don't matter.
+pub(crate) fn export_checksum(value: &serde_json::Value) -> String {
+    let mut value = value.clone();
+    if let Some(object) = value.as_object_mut() {
+        object.remove("checksum");
+    }
+    format!("{:x}", Sha256::digest(value.to_string().as_bytes()))
+}
+
+/// Checks a backup against its checksum, if it has one. Runs on the JSON as read rather
+/// than a re-serialized `ExportData`, so fields added since the backup was written don't
+/// change its hash.
+pub(crate) fn verify_export_checksum(value: &serde_json::Value) -> Result<(), String> {
+    match value.get("checksum").and_then(|checksum| checksum.as_str()) {
+        Some(expected) if expected != export_checksum(value) => {
+            Err("Backup file is corrupted or was edited (checksum mismatch)".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Snapshot of the whole database, checksummed.
+pub fn build_export(conn: &Connection) -> Result<ExportData, String> {
+    // Get all exercises
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM {}",
+            EXERCISE_COLUMNS, EXERCISE_SOURCE
+        ))
+        .map_err(|e| e.to_string())?;
+    let exercises: Vec<Exercise> = stmt
+        .query_map([], exercise_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // Get all logs
+    let mut stmt = conn
+        .prepare("SELECT id, exercise_id, reps, xp_earned, logged_at, weight FROM exercise_logs")
+        .map_err(|e| e.to_string())?;
+    let exercise_logs: Vec<ExerciseLog> = stmt
+        .query_map([], |row| {
+            Ok(ExerciseLog {
+                id: row.get(0)?,
+                exercise_id: row.get(1)?,
+                reps: row.get(2)?,
+                xp_earned: row.get(3)?,
+                logged_at: row.get(4)?,
+                weight: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // Get stats, totalled from the stored exercise XP like the exercises themselves.
+    // Decay is left to the reader: it is derived from the logs, so exporting decayed
+    // totals would apply it again on import.
+    let mut user_stats = user_stats(conn)?;
+    user_stats.total_xp = exercises.iter().map(|e| e.total_xp).sum();
+    user_stats.total_level = exercises.iter().map(|e| e.current_level).sum();
+
+    // Get achievements
+    let mut stmt = conn
+        .prepare("SELECT id, key, name, description, icon, unlocked_at FROM achievements")
+        .map_err(|e| e.to_string())?;
+    let achievements: Vec<Achievement> = stmt
+        .query_map([], |row| {
+            Ok(Achievement {
+                id: row.get(0)?,
+                key: row.get(1)?,
+                name: row.get(2)?,
+                description: row.get(3)?,
+                icon: row.get(4)?,
+                unlocked_at: row.get(5)?,
+                progress: None,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // Get settings
+    let get_setting = |key: &str, default: &str| -> String {
+        conn.query_row(
+            "SELECT value FROM settings WHERE key = ?",
+            params![key],
+            |row| row.get(0),
+        )
+        .unwrap_or_else(|_| default.to_string())
+    };
+
+    let settings = Settings {
+        reminder_enabled: get_setting("reminder_enabled", "true") == "true",
+        reminder_interval_minutes: get_setting("reminder_interval_minutes", "120")
+            .parse()
+            .unwrap_or(120),
+        sound_enabled: get_setting("sound_enabled", "true") == "true",
+        daily_goal_xp: get_setting("daily_goal_xp", "500").parse().unwrap_or(500),
+        theme_mode: Some(get_setting("theme_mode", "dark")),
+        launch_on_startup: get_setting("launch_on_startup", "false") == "true",
+    };
+
+    let mut export_data = ExportData {
+        version: "1.0.0".to_string(),
+        exported_at: local_timestamp(conn),
+        exercises,
+        exercise_logs,
+        user_stats,
+        achievements,
+        settings,
+        checksum: None,
+    };
+    let value = serde_json::to_value(&export_data).map_err(|e| e.to_string())?;
+    export_data.checksum = Some(export_checksum(&value));
+    Ok(export_data)
+}
+
+/// `build_export` as pretty-printed JSON, the format of backup files.
+pub fn build_export_json(conn: &Connection) -> Result<String, String> {
+    serde_json::to_string_pretty(&build_export(conn)?).map_err(|e| e.to_string())
+}
+
+/// Imports an export already checked by `import_json`. `mode` is "replace" (the default)
+/// to swap in the exported data, or "keep_higher" to keep whichever side has more XP for
+/// each exercise.
+pub fn apply_import(
+    conn: &Connection,
+    data: &ExportData,
+    mode: Option<&str>,
+) -> Result<(), String> {
+    match mode.unwrap_or("replace") {
+        "replace" => replace_with_export(conn, data),
+        "keep_higher" => import_keep_higher(conn, data),
+        other => Err(format!(
+            "Unknown import mode '{}', expected 'replace' or 'keep_higher'",
+            other
+        )),
+    }
+}
+
+/// Parses a backup file's contents, verifies its checksum and hands it to `apply_import`.
+pub fn import_json(conn: &Connection, json_data: &str, mode: Option<&str>) -> Result<(), String> {
+    let value: serde_json::Value =
+        serde_json::from_str(json_data).map_err(|e| format!("Invalid data format: {}", e))?;
+    verify_export_checksum(&value)?;
+    let data: ExportData =
+        serde_json::from_value(value).map_err(|e| format!("Invalid data format: {}", e))?;
+    apply_import(conn, &data, mode)
+}
+
+/// Starts a transaction unless the caller already holds one, as the CLI does so it can
+/// retry a busy database. `None` leaves commit or rollback to the caller.
+fn begin_unless_nested(conn: &Connection) -> Result<Option<Transaction<'_>>, String> {
+    if conn.is_autocommit() {
+        conn.unchecked_transaction()
+            .map(Some)
+            .map_err(|e| e.to_string())
+    } else {
+        Ok(None)
+    }
+}
+
+fn commit_unless_nested(tx: Option<Transaction<'_>>) -> Result<(), String> {
+    match tx {
+        Some(tx) => tx.commit().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Wipes the database and loads `data` in its place. Runs in one transaction, so an
+/// export that fails partway leaves the existing data untouched.
+fn replace_with_export(conn: &Connection, data: &ExportData) -> Result<(), String> {
+    let tx = begin_unless_nested(conn)?;
+
+    // Clear existing data
+    conn.execute_batch(
+        "
+        DELETE FROM exercise_logs;
+        DELETE FROM level_history;
+        DELETE FROM exercises;
+        DELETE FROM daily_goal_history;
+        UPDATE user_stats SET current_streak = 0, longest_streak = 0, last_exercise_date = NULL WHERE id = 1;
+        UPDATE achievements SET unlocked_at = NULL;
+        ",
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Import exercises
+    for exercise in &data.exercises {
+        conn.execute(
+            "INSERT INTO exercises (id, name, xp_per_rep, total_xp, current_level, icon, created_at, difficulty, category, seconds_per_rep, color, remind) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                exercise.id,
+                exercise.name,
+                exercise.xp_per_rep,
+                exercise.total_xp,
+                exercise.current_level,
+                exercise.icon,
+                exercise.created_at,
+                exercise.difficulty,
+                exercise.category,
+                exercise.seconds_per_rep,
+                exercise.color,
+                exercise.remind
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Import exercise logs
+    for log in &data.exercise_logs {
+        conn.execute(
+            "INSERT INTO exercise_logs (id, exercise_id, reps, xp_earned, logged_at, weight) VALUES (?, ?, ?, ?, ?, ?)",
+            params![log.id, log.exercise_id, log.reps, log.xp_earned, log.logged_at, log.weight],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Update user stats
+    conn.execute(
+        "UPDATE user_stats SET current_streak = ?, longest_streak = ?, last_exercise_date = ? WHERE id = 1",
+        params![
+            data.user_stats.current_streak,
+            data.user_stats.longest_streak,
+            data.user_stats.last_exercise_date
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Update achievements
+    for achievement in &data.achievements {
+        if achievement.unlocked_at.is_some() {
+            conn.execute(
+                "UPDATE achievements SET unlocked_at = ? WHERE key = ?",
+                params![achievement.unlocked_at, achievement.key],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    // Update settings
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('reminder_enabled', ?)",
+        params![data.settings.reminder_enabled.to_string()],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('reminder_interval_minutes', ?)",
+        params![data.settings.reminder_interval_minutes.to_string()],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('sound_enabled', ?)",
+        params![data.settings.sound_enabled.to_string()],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('daily_goal_xp', ?)",
+        params![data.settings.daily_goal_xp.to_string()],
+    )
+    .map_err(|e| e.to_string())?;
+    if let Some(theme_mode) = &data.settings.theme_mode {
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('theme_mode', ?)",
+            params![theme_mode],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    commit_unless_nested(tx)
+}
+
+/// Merges an export exercise by exercise (matched by name): the side with more
+/// `total_xp` wins and brings its log history along. Exercises only in the export are
+/// added; local settings are kept and unlocked achievements are combined.
+pub(crate) fn import_keep_higher(conn: &Connection, data: &ExportData) -> Result<(), String> {
+    let tx = begin_unless_nested(conn)?;
+    let max_level = get_max_level(conn);
+
+    for exercise in &data.exercises {
+        let local: Option<(i64, i64)> = conn
+            .query_row(
+                "SELECT id, COALESCE(total_xp, 0) FROM exercises WHERE LOWER(name) = LOWER(?)",
+                params![exercise.name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+        let level = level_from_xp_capped(exercise.total_xp, max_level);
+
+        let target_id = match local {
+            Some((_, local_xp)) if local_xp >= exercise.total_xp => continue,
+            Some((id, _)) => {
+                conn.execute(
+                    "DELETE FROM exercise_logs WHERE exercise_id = ?",
+                    params![id],
+                )
+                .map_err(|e| e.to_string())?;
+                conn.execute(
+                    "DELETE FROM level_history WHERE exercise_id = ?",
+                    params![id],
+                )
+                .map_err(|e| e.to_string())?;
+                conn.execute(
+                    "UPDATE exercises SET total_xp = ?, current_level = ? WHERE id = ?",
+                    params![exercise.total_xp, level, id],
+                )
+                .map_err(|e| e.to_string())?;
+                id
+            }
+            None => {
+                conn.execute(
+                    "INSERT INTO exercises (name, xp_per_rep, total_xp, current_level, icon, created_at, difficulty, category, seconds_per_rep, color, remind) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    params![
+                        exercise.name,
+                        exercise.xp_per_rep,
+                        exercise.total_xp,
+                        level,
+                        exercise.icon,
+                        exercise.created_at,
+                        exercise.difficulty,
+                        exercise.category,
+                        exercise.seconds_per_rep,
+                        exercise.color,
+                        exercise.remind
+                    ],
+                )
+                .map_err(|e| e.to_string())?;
+                conn.last_insert_rowid()
+            }
+        };
+
+        for log in data
+            .exercise_logs
+            .iter()
+            .filter(|log| log.exercise_id == exercise.id)
+        {
+            conn.execute(
+                "INSERT INTO exercise_logs (exercise_id, reps, xp_earned, logged_at, weight) VALUES (?, ?, ?, ?, ?)",
+                params![target_id, log.reps, log.xp_earned, log.logged_at, log.weight],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    for achievement in &data.achievements {
+        if achievement.unlocked_at.is_some() {
+            conn.execute(
+                "UPDATE achievements SET unlocked_at = COALESCE(unlocked_at, ?) WHERE key = ?",
+                params![achievement.unlocked_at, achievement.key],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    recompute_streak(conn)?;
+    commit_unless_nested(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{init_database, log_exercise_at_time};
+
+    fn seeded() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO exercises (name, xp_per_rep, total_xp, current_level) VALUES ('Pushups', 10, 0, 1)",
+            [],
+        )
+        .unwrap();
+        log_exercise_at_time(&conn, 1, 20, "2024-03-01 08:00:00").unwrap();
+        log_exercise_at_time(&conn, 1, 10, "2024-03-02 08:00:00").unwrap();
+        conn
+    }
+
+    fn fresh() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_build_export_snapshots_the_database() {
+        let data = build_export(&seeded()).unwrap();
+        assert_eq!(data.exercises.len(), 1);
+        assert_eq!(data.exercises[0].total_xp, 300);
+        assert_eq!(data.exercise_logs.len(), 2);
+        assert_eq!(data.user_stats.total_xp, 300);
+        assert!(verify_export_checksum(&serde_json::to_value(&data).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_apply_import_replaces_or_merges() {
+        let data = build_export(&seeded()).unwrap();
+
+        let conn = fresh();
+        conn.execute(
+            "INSERT INTO exercises (name, xp_per_rep, total_xp, current_level) VALUES ('Squats', 8, 80, 1)",
+            [],
+        )
+        .unwrap();
+        apply_import(&conn, &data, Some("keep_higher")).unwrap();
+        let merged = build_export(&conn).unwrap();
+        assert_eq!(merged.exercises.len(), 2);
+        assert_eq!(merged.exercise_logs.len(), 2);
+
+        apply_import(&conn, &data, None).unwrap();
+        let replaced = build_export(&conn).unwrap();
+        assert_eq!(replaced.exercises.len(), 1);
+        assert_eq!(replaced.exercises[0].name, "Pushups");
+        assert_eq!(replaced.user_stats.total_xp, 300);
+
+        let mut tampered = serde_json::to_value(&data).unwrap();
+        tampered["exercises"][0]["total_xp"] = serde_json::json!(1);
+        assert!(import_json(&conn, &tampered.to_string(), None).is_err());
+        assert_eq!(build_export(&conn).unwrap().user_stats.total_xp, 300);
+    }
+
+    #[test]
+    fn test_failed_replace_keeps_existing_data() {
+        let conn = seeded();
+        let mut data = build_export(&conn).unwrap();
+        // Duplicate log ids make the import fail after the wipe
+        data.exercise_logs.push(ExerciseLog {
+            id: data.exercise_logs[0].id,
+            exercise_id: 1,
+            reps: 5,
+            xp_earned: 50,
+            logged_at: "2024-03-03 08:00:00".to_string(),
+            weight: None,
+        });
+
+        assert!(apply_import(&conn, &data, None).is_err());
+        let kept = build_export(&conn).unwrap();
+        assert_eq!(kept.exercises.len(), 1);
+        assert_eq!(kept.exercise_logs.len(), 2);
+        assert_eq!(kept.user_stats.total_xp, 300);
+    }
+
+    // A checksummed backup written before color, remind, last_logged, weight, progress,
+    // goal_streak, consistency_score and launch_on_startup existed
+    const OLDER_SCHEMA_EXPORT: &str = r#"{
+  "achievements": [
+    {
+      "description": "Log your first exercise",
+      "icon": null,
+      "id": 1,
+      "key": "first_exercise",
+      "name": "First Steps",
+      "unlocked_at": "2024-03-01 08:00:00"
+    }
+  ],
+  "checksum": "aa0cf4651a7166b4e7d260be67a66dd0a0b91caebf36c180df154caed002b4a2",
+  "exercise_logs": [
+    {
+      "exercise_id": 1,
+      "id": 1,
+      "logged_at": "2024-03-01 08:00:00",
+      "reps": 20,
+      "xp_earned": 200
+    }
+  ],
+  "exercises": [
+    {
+      "category": "Upper Body",
+      "created_at": "2024-03-01 07:00:00",
+      "current_level": 3,
+      "difficulty": 1.0,
+      "icon": null,
+      "id": 1,
+      "name": "Pushups",
+      "total_xp": 200,
+      "xp_per_rep": 10
+    }
+  ],
+  "exported_at": "2024-03-02 09:00:00",
+  "settings": {
+    "daily_goal_xp": 500,
+    "reminder_enabled": true,
+    "reminder_interval_minutes": 120,
+    "sound_enabled": true,
+    "theme_mode": "dark"
+  },
+  "user_stats": {
+    "current_streak": 1,
+    "exercise_count": 1,
+    "last_exercise_date": "2024-03-01",
+    "longest_streak": 1,
+    "total_level": 3,
+    "total_xp": 200
+  },
+  "version": "1.0.0"
+}"#;
+
+    #[test]
+    fn test_imports_checksummed_backup_from_older_schema() {
+        let conn = fresh();
+        import_json(&conn, OLDER_SCHEMA_EXPORT, None).unwrap();
+
+        let data = build_export(&conn).unwrap();
+        assert_eq!(data.exercises.len(), 1);
+        assert_eq!(data.exercises[0].name, "Pushups");
+        assert_eq!(data.exercises[0].total_xp, 200);
+        assert!(data.exercises[0].remind);
+        assert_eq!(data.exercise_logs.len(), 1);
+        assert_eq!(data.exercise_logs[0].weight, None);
+
+        // The checksum still guards the older file
+        let edited = OLDER_SCHEMA_EXPORT.replace("\"reps\": 20", "\"reps\": 200");
+        let err = import_json(&fresh(), &edited, None).unwrap_err();
+        assert!(err.contains("checksum mismatch"));
+    }
+}
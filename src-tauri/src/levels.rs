@@ -0,0 +1,413 @@
+// Level helpers shared by the Tauri app and the CLI
+
+use crate::streaks::log_day_sql;
+use rusqlite::{params, Connection};
+use std::sync::OnceLock;
+
+/// Default level cap, matching RuneScape.
+pub const DEFAULT_MAX_LEVEL: i32 = 99;
+/// Highest cap users can opt into for "prestige" levels.
+pub const MAX_LEVEL_LIMIT: i32 = 120;
+/// Largest rep count accepted for a single log; anything above is almost certainly a typo.
+pub const MAX_REPS: i32 = 10_000;
+/// Distinct exercises in one day that earn the combo bonus
+pub const COMBO_EXERCISE_COUNT: i64 = 3;
+
+// ============ XP Calculations (RuneScape-style) ============
+
+pub fn xp_for_level(level: i32) -> i64 {
+    if level <= 1 {
+        return 0;
+    }
+    let mut total: f64 = 0.0;
+    for i in 1..level {
+        total += (i as f64) + 300.0 * 2.0_f64.powf((i as f64) / 7.0);
+    }
+    (total / 4.0).floor() as i64
+}
+
+/// XP thresholds indexed by level, sized to the highest allowed cap.
+fn xp_thresholds() -> &'static [i64] {
+    static THRESHOLDS: OnceLock<Vec<i64>> = OnceLock::new();
+    THRESHOLDS.get_or_init(|| (0..=MAX_LEVEL_LIMIT).map(xp_for_level).collect())
+}
+
+pub fn level_from_xp(xp: i64) -> i32 {
+    level_from_xp_capped(xp, DEFAULT_MAX_LEVEL)
+}
+
+/// Level for `xp`, allowed to climb up to `max_level` (clamped to the supported range).
+pub fn level_from_xp_capped(xp: i64, max_level: i32) -> i32 {
+    let max_level = clamp_max_level(max_level);
+    let thresholds = xp_thresholds();
+    let mut level = 1;
+    while level < max_level && thresholds[(level + 1) as usize] <= xp {
+        level += 1;
+    }
+    level
+}
+
+/// Keeps a configured cap between the default 99 and the prestige limit.
+pub fn clamp_max_level(max_level: i32) -> i32 {
+    max_level.clamp(DEFAULT_MAX_LEVEL, MAX_LEVEL_LIMIT)
+}
+
+/// Returns a fun title for a level, used by the CLI stats header and the GUI.
+pub fn get_title_for_level(level: i32) -> &'static str {
+    match level {
+        i32::MIN..=4 => "Novice Geek",
+        5..=9 => "Fitness Apprentice",
+        10..=19 => "Gym Initiate",
+        20..=29 => "Strength Seeker",
+        30..=39 => "Endurance Elite",
+        40..=49 => "Fitness Warrior",
+        _ => "Legendary Geek",
+    }
+}
+
+/// Settings for the optional "use it or lose it" XP decay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XpDecay {
+    pub grace_days: i64,      // Idle days before decay starts
+    pub percent_per_day: f64, // Share of XP lost per idle day after the grace period
+    pub allow_delevel: bool,  // Otherwise XP stops at the current level's threshold
+}
+
+/// XP left after `idle_days` without a log. Decay compounds daily once the grace
+/// period is over and is computed from scratch on every read, never stored. Without
+/// delevelling it stops at the threshold of the level reached under `max_level`.
+pub fn decayed_xp(total_xp: i64, idle_days: i64, decay: &XpDecay, max_level: i32) -> i64 {
+    let decay_days = idle_days - decay.grace_days.max(0);
+    if decay_days <= 0 || total_xp <= 0 || decay.percent_per_day <= 0.0 {
+        return total_xp;
+    }
+
+    let rate = (1.0 - decay.percent_per_day / 100.0).clamp(0.0, 1.0);
+    let decayed =
+        (total_xp as f64 * rate.powi(decay_days.min(i32::MAX as i64) as i32)).floor() as i64;
+    if decay.allow_delevel {
+        decayed
+    } else {
+        decayed.max(xp_for_level(level_from_xp_capped(total_xp, max_level)))
+    }
+}
+
+fn setting_value(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?",
+        params![key],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// The `xp_decay_*` settings, or None while the mode is off (the default).
+pub fn xp_decay_config(conn: &Connection) -> Option<XpDecay> {
+    if setting_value(conn, "xp_decay_enabled").as_deref() != Some("true") {
+        return None;
+    }
+    Some(XpDecay {
+        grace_days: setting_value(conn, "xp_decay_grace_days")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(7),
+        percent_per_day: setting_value(conn, "xp_decay_percent_per_day")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1.0),
+        allow_delevel: setting_value(conn, "xp_decay_allow_delevel").as_deref() == Some("true"),
+    })
+}
+
+/// An exercise's XP and level on `day`, with decay applied to its stored totals. Idle
+/// time runs from the exercise's last log in effective days, so the stored values come
+/// back unchanged while decay is off, for exercises never logged and for backdated days.
+/// Every read of exercise totals goes through here, and logging builds on the result.
+pub fn decayed_totals(
+    conn: &Connection,
+    exercise_id: i64,
+    stored_xp: i64,
+    stored_level: i32,
+    day: chrono::NaiveDate,
+) -> (i64, i32) {
+    let Some(decay) = xp_decay_config(conn) else {
+        return (stored_xp, stored_level);
+    };
+    let last_logged = conn
+        .query_row(
+            &format!(
+                "SELECT MAX({}) FROM exercise_logs WHERE exercise_id = ?",
+                log_day_sql(conn, "logged_at")
+            ),
+            params![exercise_id],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .ok()
+        .flatten()
+        .and_then(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok());
+    let Some(last_logged) = last_logged else {
+        return (stored_xp, stored_level);
+    };
+
+    let max_level = crate::get_max_level(conn);
+    let xp = decayed_xp(stored_xp, (day - last_logged).num_days(), &decay, max_level);
+    if xp == stored_xp {
+        return (stored_xp, stored_level);
+    }
+    (xp, level_from_xp_capped(xp, max_level))
+}
+
+/// An exercise's XP rebuilt from its log history, losing decay over each idle gap
+/// between logs just as logging does. The plain sum of `xp_earned` while decay is off.
+pub fn replayed_xp(conn: &Connection, exercise_id: i64) -> rusqlite::Result<i64> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, xp_earned FROM exercise_logs WHERE exercise_id = ? ORDER BY logged_at, id",
+        log_day_sql(conn, "logged_at")
+    ))?;
+    let logs = stmt
+        .query_map(params![exercise_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let decay = xp_decay_config(conn);
+    let max_level = crate::get_max_level(conn);
+    let mut xp = 0;
+    let mut last_day: Option<chrono::NaiveDate> = None;
+    for (day, xp_earned) in logs {
+        let day = chrono::NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok();
+        if let (Some(decay), Some(last), Some(day)) = (&decay, last_day, day) {
+            xp = decayed_xp(xp, (day - last).num_days(), decay, max_level);
+        }
+        xp += xp_earned;
+        last_day = day.or(last_day);
+    }
+    Ok(xp)
+}
+
+/// Smallest rep count a log may have, from the `min_reps` setting (at least 1).
+pub fn min_reps(conn: &Connection) -> i32 {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'min_reps'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|v| v.parse().ok())
+    .unwrap_or(1)
+    .clamp(1, MAX_REPS)
+}
+
+/// Rejects zero, negative and absurd rep counts before they reach the logs.
+pub fn validate_reps(reps: i32, min_reps: i32) -> Result<(), String> {
+    if reps < min_reps {
+        return Err(format!("Reps must be at least {} (got {})", min_reps, reps));
+    }
+    if reps > MAX_REPS {
+        return Err(format!("Reps must be at most {} (got {})", MAX_REPS, reps));
+    }
+    Ok(())
+}
+
+/// Bonus XP from the `combo_bonus` setting (0, the default, turns it off) when logging `exercise_id`
+/// brings `day` to COMBO_EXERCISE_COUNT distinct exercises. Only the log that crosses
+/// the threshold earns it, so each day pays out once.
+pub fn combo_bonus_for(conn: &Connection, exercise_id: i64, day: &str) -> i32 {
+    let bonus: i32 = setting_value(conn, "combo_bonus")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if bonus <= 0 {
+        return 0;
+    }
+    let (distinct, already_logged): (i64, bool) = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(DISTINCT exercise_id), COALESCE(MAX(exercise_id = ?1), 0)
+                 FROM exercise_logs WHERE {} = ?2",
+                log_day_sql(conn, "logged_at")
+            ),
+            params![exercise_id, day],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap_or((0, true));
+    if !already_logged && distinct == COMBO_EXERCISE_COUNT - 1 {
+        bonus
+    } else {
+        0
+    }
+}
+
+/// XP multiplier from the `xp_events` active at `at` for an exercise: events can target
+/// one exercise, one category or everything. Overlapping events don't stack; the
+/// largest multiplier applies. 1.0 when nothing is running.
+pub fn xp_multiplier_at(conn: &Connection, exercise_id: i64, at: &str) -> f64 {
+    conn.query_row(
+        "SELECT MAX(multiplier) FROM xp_events
+         WHERE ?1 >= starts_at AND ?1 < ends_at
+           AND (exercise_id IS NULL OR exercise_id = ?2)
+           AND (category IS NULL OR category = (SELECT category FROM exercises WHERE id = ?2))",
+        params![at, exercise_id],
+        |row| row.get::<_, Option<f64>>(0),
+    )
+    .ok()
+    .flatten()
+    .unwrap_or(1.0)
+}
+
+/// XP credited for a set: `xp_per_rep * reps`, scaled by the exercise's difficulty and
+/// any XP event multiplier. Rounded once at the end, so the app, the tray and the CLI
+/// always agree on what a set is worth.
+pub fn compute_xp(xp_per_rep: i32, reps: i32, difficulty: f64, multiplier: f64) -> i32 {
+    (xp_per_rep as f64 * reps as f64 * difficulty * multiplier).round() as i32
+}
+
+/// Records one `level_history` row per level gained, so level-ups stay auditable.
+/// `reached_at` defaults to the current time in the home timezone.
+pub fn record_level_ups(
+    conn: &Connection,
+    exercise_id: i64,
+    old_level: i32,
+    new_level: i32,
+    reached_at: Option<&str>,
+) -> rusqlite::Result<()> {
+    if new_level <= old_level {
+        return Ok(());
+    }
+    let now = crate::streaks::local_timestamp(conn);
+    let reached_at = reached_at.unwrap_or(&now);
+    for level in (old_level + 1)..=new_level {
+        conn.execute(
+            "INSERT INTO level_history (exercise_id, level, reached_at) VALUES (?, ?, ?)",
+            params![exercise_id, level, reached_at],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_capped_at_99_by_default() {
+        assert_eq!(level_from_xp(xp_for_level(110)), 99);
+        assert_eq!(
+            level_from_xp_capped(xp_for_level(110), DEFAULT_MAX_LEVEL),
+            99
+        );
+    }
+
+    #[test]
+    fn test_level_120_reachable_with_raised_cap() {
+        assert_eq!(level_from_xp_capped(xp_for_level(120), 120), 120);
+        assert_eq!(level_from_xp_capped(xp_for_level(105), 120), 105);
+        assert_eq!(level_from_xp_capped(xp_for_level(105) - 1, 120), 104);
+        assert_eq!(level_from_xp_capped(i64::MAX / 2, 120), 120);
+    }
+
+    #[test]
+    fn test_max_level_clamped_to_supported_range() {
+        assert_eq!(clamp_max_level(50), 99);
+        assert_eq!(clamp_max_level(110), 110);
+        assert_eq!(clamp_max_level(500), 120);
+    }
+
+    #[test]
+    fn test_decayed_xp_over_idle_days() {
+        let decay = XpDecay {
+            grace_days: 3,
+            percent_per_day: 10.0,
+            allow_delevel: true,
+        };
+        let xp = xp_for_level(20);
+
+        // Nothing is lost during the grace period
+        assert_eq!(decayed_xp(xp, 0, &decay, DEFAULT_MAX_LEVEL), xp);
+        assert_eq!(decayed_xp(xp, 3, &decay, DEFAULT_MAX_LEVEL), xp);
+        // Then 10% compounds per idle day
+        assert_eq!(
+            decayed_xp(xp, 4, &decay, DEFAULT_MAX_LEVEL),
+            (xp as f64 * 0.9).floor() as i64
+        );
+        assert_eq!(
+            decayed_xp(xp, 6, &decay, DEFAULT_MAX_LEVEL),
+            (xp as f64 * 0.729).floor() as i64
+        );
+        assert!(level_from_xp(decayed_xp(xp, 6, &decay, DEFAULT_MAX_LEVEL)) < 20);
+
+        // Without delevelling, XP bottoms out at the current level's threshold
+        let floored = XpDecay {
+            allow_delevel: false,
+            ..decay
+        };
+        assert_eq!(decayed_xp(xp + 100, 30, &floored, DEFAULT_MAX_LEVEL), xp);
+        assert_eq!(
+            level_from_xp(decayed_xp(xp + 100, 30, &floored, DEFAULT_MAX_LEVEL)),
+            20
+        );
+        assert_eq!(decayed_xp(0, 30, &floored, DEFAULT_MAX_LEVEL), 0);
+        // The floor is the level under the configured cap, not the prestige limit
+        assert_eq!(
+            decayed_xp(xp_for_level(105), 30, &floored, DEFAULT_MAX_LEVEL),
+            xp_for_level(DEFAULT_MAX_LEVEL)
+        );
+        assert_eq!(
+            decayed_xp(xp_for_level(105), 30, &floored, MAX_LEVEL_LIMIT),
+            xp_for_level(105)
+        );
+    }
+
+    #[test]
+    fn test_title_boundaries() {
+        assert_eq!(get_title_for_level(1), "Novice Geek");
+        assert_eq!(get_title_for_level(4), "Novice Geek");
+        assert_eq!(get_title_for_level(5), "Fitness Apprentice");
+        assert_eq!(get_title_for_level(9), "Fitness Apprentice");
+        assert_eq!(get_title_for_level(10), "Gym Initiate");
+        assert_eq!(get_title_for_level(19), "Gym Initiate");
+        assert_eq!(get_title_for_level(20), "Strength Seeker");
+        assert_eq!(get_title_for_level(29), "Strength Seeker");
+        assert_eq!(get_title_for_level(30), "Endurance Elite");
+        assert_eq!(get_title_for_level(39), "Endurance Elite");
+        assert_eq!(get_title_for_level(40), "Fitness Warrior");
+        assert_eq!(get_title_for_level(49), "Fitness Warrior");
+        assert_eq!(get_title_for_level(50), "Legendary Geek");
+        assert_eq!(get_title_for_level(99), "Legendary Geek");
+    }
+
+    #[test]
+    fn test_title_for_out_of_range_levels() {
+        assert_eq!(get_title_for_level(0), "Novice Geek");
+        assert_eq!(get_title_for_level(-5), "Novice Geek");
+    }
+
+    #[test]
+    fn test_validate_reps() {
+        assert!(validate_reps(1, 1).is_ok());
+        assert!(validate_reps(MAX_REPS, 1).is_ok());
+        assert!(validate_reps(0, 1).is_err());
+        assert!(validate_reps(-5, 1).is_err());
+        assert!(validate_reps(MAX_REPS + 1, 1).is_err());
+        assert!(validate_reps(4, 5).is_err());
+    }
+
+    #[test]
+    fn test_compute_xp_with_difficulty() {
+        assert_eq!(compute_xp(10, 5, 1.0, 1.0), 50);
+        assert_eq!(compute_xp(10, 5, 1.5, 1.0), 75);
+        // 3 * 5 * 1.25 = 18.75 rounds up, 3 * 3 * 1.05 = 9.45 rounds down
+        assert_eq!(compute_xp(3, 5, 1.25, 1.0), 19);
+        assert_eq!(compute_xp(3, 3, 1.05, 1.0), 9);
+        // 7 * 1 * 0.5 = 3.5 rounds half away from zero
+        assert_eq!(compute_xp(7, 1, 0.5, 1.0), 4);
+    }
+
+    #[test]
+    fn test_compute_xp_with_event_multiplier() {
+        assert_eq!(compute_xp(10, 5, 1.0, 2.0), 100);
+        assert_eq!(compute_xp(10, 5, 1.5, 2.0), 150);
+        assert_eq!(compute_xp(3, 3, 1.05, 1.5), 14);
+        // Rounded once: 3 * 5 * 1.25 * 1.5 = 28.125, where rounding the difficulty
+        // first would give 19 * 1.5 = 28.5 -> 29
+        assert_eq!(compute_xp(3, 5, 1.25, 1.5), 28);
+        assert_eq!(compute_xp(7, 1, 0.5, 3.0), 11);
+    }
+}
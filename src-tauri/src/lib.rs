@@ -1,11 +1,29 @@
-use chrono::Timelike;
+pub mod data_io;
+pub mod levels;
+#[cfg(feature = "local-api")]
+mod local_api;
+mod share_card;
+#[cfg(feature = "sound")]
+mod sound;
+pub mod streaks;
+
+use chrono::{Datelike, Timelike};
+use levels::{
+    combo_bonus_for, compute_xp, decayed_totals, level_from_xp_capped, min_reps, record_level_ups,
+    replayed_xp, validate_reps, xp_multiplier_at,
+};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
+use streaks::{
+    active_dates, effective_today, local_now, local_timestamp, log_day_sql, longest_run,
+    recompute_streak, record_daily_goal_change, streak_runs,
+};
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::TrayIconBuilder,
     AppHandle, Emitter, Manager, State,
 };
@@ -13,13 +31,94 @@ use tauri::{
 // Database state
 struct DbState(Mutex<Connection>);
 
+// Result of the startup integrity check, reported via get_version_info
+struct DbHealthState(Mutex<DbHealth>);
+
+// Where profile databases live
+struct ProfileState {
+    app_dir: PathBuf,
+}
+
+// Effective data directory, and the unwritable one it replaced (if any)
+struct DataDirState {
+    path: PathBuf,
+    fallback_from: Option<PathBuf>,
+}
+
 // Reminder state for background scheduling
 struct ReminderState {
     last_eye_care: Mutex<Instant>,
     last_hydration: Mutex<Instant>,
     last_posture: Mutex<Instant>,
     last_exercise: Mutex<Instant>,
+    exercise_escalation: Mutex<ReminderEscalation>,
+    next_exercise_at: Mutex<Option<chrono::NaiveDateTime>>, // Published by the loop, None while off
     running: AtomicBool,
+    fullscreen_detector: Box<dyn FullscreenDetector>,
+}
+
+// Last logged exercise, repeated by the tray's "log again" item
+#[derive(Default)]
+struct LastLogState {
+    last: Mutex<Option<LastLog>>,
+    menu_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+}
+
+// Logs removed by undo this session, newest last, so they can be redone
+#[derive(Default)]
+struct UndoState {
+    undone: Mutex<Vec<UndoneLog>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct LastLog {
+    exercise_id: i64,
+    exercise_name: String,
+    reps: i32,
+}
+
+impl UndoState {
+    fn clear(&self) {
+        if let Ok(mut undone) = self.undone.lock() {
+            undone.clear();
+        }
+    }
+}
+
+impl LastLogState {
+    fn record(&self, log: LastLog) {
+        let label = log_again_label(Some(&log));
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some(log);
+        }
+        self.set_log_again_label(&label);
+    }
+
+    fn get(&self) -> Option<LastLog> {
+        self.last.lock().ok().and_then(|last| last.clone())
+    }
+
+    fn clear(&self) {
+        if let Ok(mut last) = self.last.lock() {
+            *last = None;
+        }
+    }
+
+    fn set_log_again_label(&self, label: &str) {
+        if let Ok(item) = self.menu_item.lock() {
+            if let Some(item) = item.as_ref() {
+                let _ = item.set_text(label);
+                let _ = item.set_enabled(true);
+            }
+        }
+    }
+}
+
+fn log_again_label(last: Option<&LastLog>) -> String {
+    match last {
+        Some(log) => format!("Log {} x{} again", log.exercise_name, log.reps),
+        None => "Log last exercise again".to_string(),
+    }
 }
 
 // ============ Data Structures ============
@@ -30,9 +129,54 @@ pub struct Exercise {
     pub name: String,
     pub xp_per_rep: i32,
     pub total_xp: i64,      // XP earned for this specific exercise
-    pub current_level: i32, // Level for this exercise (1-99)
+    pub current_level: i32, // Level for this exercise (1-99, or up to max_level)
     pub icon: Option<String>,
     pub created_at: String,
+    #[serde(default = "default_difficulty")]
+    pub difficulty: f64, // XP multiplier for harder movements
+    #[serde(default)]
+    pub category: Option<String>, // e.g. "Upper Body", "Cardio"
+    #[serde(default)]
+    pub seconds_per_rep: Option<f64>, // None = estimate from name/category
+    #[serde(default)]
+    pub color: Option<String>, // "#RRGGBB" accent for cards and charts
+    #[serde(default = "default_remind")]
+    pub remind: bool, // Whether exercise reminders may suggest it
+    #[serde(default)]
+    pub last_logged: Option<String>, // Most recent log, None if never logged
+}
+
+fn default_difficulty() -> f64 {
+    1.0
+}
+
+fn default_remind() -> bool {
+    true
+}
+
+const EXERCISE_COLUMNS: &str = "id, name, xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1), icon, created_at, COALESCE(difficulty, 1.0), category, seconds_per_rep, color, COALESCE(remind, 1), latest.last_logged";
+
+/// Exercises joined with their most recent log, for use with EXERCISE_COLUMNS
+const EXERCISE_SOURCE: &str = "exercises LEFT JOIN (
+    SELECT exercise_id, MAX(logged_at) AS last_logged FROM exercise_logs GROUP BY exercise_id
+) latest ON latest.exercise_id = exercises.id";
+
+fn exercise_from_row(row: &rusqlite::Row) -> rusqlite::Result<Exercise> {
+    Ok(Exercise {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        xp_per_rep: row.get(2)?,
+        total_xp: row.get(3)?,
+        current_level: row.get(4)?,
+        icon: row.get(5)?,
+        created_at: row.get(6)?,
+        difficulty: row.get(7)?,
+        category: row.get(8)?,
+        seconds_per_rep: row.get(9)?,
+        color: row.get(10)?,
+        remind: row.get(11)?,
+        last_logged: row.get(12)?,
+    })
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -42,6 +186,8 @@ pub struct ExerciseLog {
     pub reps: i32,
     pub xp_earned: i32,
     pub logged_at: String,
+    #[serde(default)]
+    pub weight: Option<f64>, // Load for weighted sets (kg or lb, as the user logs it)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,6 +198,10 @@ pub struct UserStats {
     pub longest_streak: i32,
     pub last_exercise_date: Option<String>,
     pub exercise_count: i32, // Number of exercises (skills)
+    #[serde(default)]
+    pub goal_streak: i32, // Consecutive days that met the daily XP goal
+    #[serde(default)]
+    pub consistency_score: i32, // 0-100 habit strength over the last CONSISTENCY_WINDOW_DAYS
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,6 +212,8 @@ pub struct Achievement {
     pub description: Option<String>,
     pub icon: Option<String>,
     pub unlocked_at: Option<String>,
+    #[serde(default)]
+    pub progress: Option<(i64, i64)>, // (current, target) while locked, None once unlocked
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -71,6 +223,8 @@ pub struct Settings {
     pub sound_enabled: bool,
     pub daily_goal_xp: i32,
     pub theme_mode: Option<String>,
+    #[serde(default)]
+    pub launch_on_startup: bool, // Registered with the OS, not just the stored setting
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -78,27 +232,47 @@ pub struct LogExerciseResult {
     pub xp_earned: i32,
     pub new_exercise_level: i32,
     pub leveled_up: bool,
+    #[serde(default)]
+    pub comeback: bool, // First log after a lapse of more than COMEBACK_GAP_DAYS
+    #[serde(default)]
+    pub combo_awarded: bool, // This log completed the day's combo; xp_earned includes the bonus
+    #[serde(default = "default_xp_multiplier")]
+    pub xp_multiplier: f64, // From an active XP event, 1.0 otherwise
+}
+
+fn default_xp_multiplier() -> f64 {
+    1.0
+}
+
+/// Days without a log after which the next one counts as a comeback
+const COMEBACK_GAP_DAYS: i64 = 7;
+const COMEBACK_MESSAGE: &str = "Welcome back! Let's rebuild that streak";
+
+/// Whether logging on `day` ends a lapse. Never true for the first-ever log.
+fn is_comeback(last_exercise_date: Option<&str>, day: chrono::NaiveDate) -> bool {
+    last_exercise_date
+        .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .is_some_and(|last| (day - last).num_days() > COMEBACK_GAP_DAYS)
 }
 
 // ============ XP Calculations (RuneScape-style) ============
 
-fn xp_for_level(level: i32) -> i64 {
-    if level <= 1 {
-        return 0;
-    }
-    let mut total: f64 = 0.0;
-    for i in 1..level {
-        total += (i as f64) + 300.0 * 2.0_f64.powf((i as f64) / 7.0);
-    }
-    (total / 4.0).floor() as i64
+/// Level cap from the `max_level` setting (99 unless the user opted into prestige levels).
+fn get_max_level(conn: &Connection) -> i32 {
+    let max_level = get_setting_value(conn, "max_level", "99")
+        .parse()
+        .unwrap_or(levels::DEFAULT_MAX_LEVEL);
+    levels::clamp_max_level(max_level)
 }
 
-fn level_from_xp(xp: i64) -> i32 {
-    let mut level = 1;
-    while xp_for_level(level + 1) <= xp && level < 99 {
-        level += 1;
+/// XP for a weighted set: +1% per unit of load when the `scale_xp_by_weight` setting is on.
+fn weighted_xp(xp: i32, weight: Option<f64>, scale_by_weight: bool) -> i32 {
+    match weight {
+        Some(weight) if scale_by_weight && weight > 0.0 => {
+            (xp as f64 * (1.0 + weight / 100.0)).round() as i32
+        }
+        _ => xp,
     }
-    level
 }
 
 // ============ Default Exercises ============
@@ -142,6 +316,98 @@ fn get_default_exercises_list() -> Vec<(&'static str, i32, &'static str, &'stati
     ]
 }
 
+/// Curated packs users can add in one go, as (pack, [(name, xp_per_rep, icon, category)])
+const EXERCISE_PACKS: &[(&str, &[(&str, i32, &str, &str)])] = &[
+    (
+        "Yoga Pack",
+        &[
+            ("Sun Salutation", 12, "self_improvement", "Stretches"),
+            ("Downward Dog (10 sec)", 4, "self_improvement", "Stretches"),
+            ("Warrior Pose (10 sec)", 5, "self_improvement", "Stretches"),
+            ("Tree Pose (10 sec)", 4, "self_improvement", "Stretches"),
+            ("Child's Pose (10 sec)", 2, "self_improvement", "Stretches"),
+            ("Cobra Stretch", 3, "self_improvement", "Stretches"),
+        ],
+    ),
+    (
+        "Strength Pack",
+        &[
+            ("Pushups", 10, "fitness_center", "Upper Body"),
+            ("Diamond Pushups", 14, "fitness_center", "Upper Body"),
+            ("Tricep Dips", 12, "fitness_center", "Upper Body"),
+            ("Pike Pushups", 14, "fitness_center", "Upper Body"),
+            ("Squats", 8, "fitness_center", "Lower Body"),
+            ("Glute Bridges", 7, "fitness_center", "Lower Body"),
+            ("Bulgarian Split Squats", 14, "fitness_center", "Lower Body"),
+        ],
+    ),
+    (
+        "Cardio Pack",
+        &[
+            ("Jumping Jacks", 6, "directions_run", "Cardio"),
+            ("Skater Jumps", 10, "directions_run", "Cardio"),
+            ("Jump Squats", 12, "directions_run", "Cardio"),
+            ("Butt Kicks", 6, "directions_run", "Cardio"),
+            ("Shadow Boxing (10 sec)", 5, "directions_run", "Cardio"),
+        ],
+    ),
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkAddResult {
+    pub added: i32,
+    pub skipped: i32, // Already present (matched by name, ignoring case)
+}
+
+#[tauri::command]
+fn get_exercise_packs() -> Vec<String> {
+    EXERCISE_PACKS
+        .iter()
+        .map(|(pack, _)| pack.to_string())
+        .collect()
+}
+
+#[tauri::command]
+fn bulk_add_exercises(state: State<DbState>, pack: String) -> Result<BulkAddResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    add_exercise_pack(&conn, &pack)
+}
+
+/// Inserts every exercise from `pack` that isn't already present.
+fn add_exercise_pack(conn: &Connection, pack: &str) -> Result<BulkAddResult, String> {
+    let (_, exercises) = EXERCISE_PACKS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(pack.trim()))
+        .ok_or_else(|| format!("Unknown exercise pack '{}'", pack))?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut result = BulkAddResult {
+        added: 0,
+        skipped: 0,
+    };
+    for (name, xp, icon, category) in exercises.iter() {
+        let exists: bool = tx
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM exercises WHERE LOWER(name) = LOWER(?)",
+                params![name],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if exists {
+            result.skipped += 1;
+            continue;
+        }
+        tx.execute(
+            "INSERT INTO exercises (name, xp_per_rep, icon, category, total_xp, current_level) VALUES (?, ?, ?, ?, 0, 1)",
+            params![name, xp, icon, category],
+        )
+        .map_err(|e| e.to_string())?;
+        result.added += 1;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
 // ============ Database Initialization ============
 
 fn init_database(conn: &Connection) -> Result<(), rusqlite::Error> {
@@ -155,7 +421,12 @@ fn init_database(conn: &Connection) -> Result<(), rusqlite::Error> {
             total_xp INTEGER DEFAULT 0,
             current_level INTEGER DEFAULT 1,
             icon TEXT,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            difficulty REAL DEFAULT 1.0,
+            category TEXT,
+            seconds_per_rep REAL,
+            color TEXT,
+            remind INTEGER DEFAULT 1
         );
 
         -- Exercise logs
@@ -165,9 +436,65 @@ fn init_database(conn: &Connection) -> Result<(), rusqlite::Error> {
             reps INTEGER NOT NULL,
             xp_earned INTEGER NOT NULL,
             logged_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            weight REAL,
+            FOREIGN KEY (exercise_id) REFERENCES exercises(id)
+        );
+
+        -- When each exercise reached each level
+        CREATE TABLE IF NOT EXISTS level_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            exercise_id INTEGER NOT NULL,
+            level INTEGER NOT NULL,
+            reached_at DATETIME NOT NULL,
+            FOREIGN KEY (exercise_id) REFERENCES exercises(id)
+        );
+
+        -- Time-boxed rep goals (exercise_id NULL = any exercise)
+        CREATE TABLE IF NOT EXISTS challenges (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            exercise_id INTEGER,
+            target_reps INTEGER NOT NULL,
+            start_date DATE NOT NULL,
+            end_date DATE NOT NULL,
+            completed_at DATETIME,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Time-boxed XP multipliers, optionally limited to one exercise or category
+        CREATE TABLE IF NOT EXISTS xp_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            starts_at DATETIME NOT NULL,
+            ends_at DATETIME NOT NULL,
+            multiplier REAL NOT NULL,
+            exercise_id INTEGER,
+            category TEXT
+        );
+
+        -- Named sequences of sets logged together
+        CREATE TABLE IF NOT EXISTS routines (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS routine_steps (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            routine_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            exercise_id INTEGER NOT NULL,
+            reps INTEGER NOT NULL,
+            FOREIGN KEY (routine_id) REFERENCES routines(id),
             FOREIGN KEY (exercise_id) REFERENCES exercises(id)
         );
 
+        -- Daily XP goal in effect from each date on, for judging past days
+        CREATE TABLE IF NOT EXISTS daily_goal_history (
+            effective_date DATE PRIMARY KEY,
+            goal_xp INTEGER NOT NULL
+        );
+
         -- User stats (streak tracking only, levels calculated from exercises)
         CREATE TABLE IF NOT EXISTS user_stats (
             id INTEGER PRIMARY KEY CHECK (id = 1),
@@ -191,6 +518,13 @@ fn init_database(conn: &Connection) -> Result<(), rusqlite::Error> {
             key TEXT PRIMARY KEY,
             value TEXT
         );
+
+        -- Profiles (only read from geekfit.db, see Profiles section)
+        CREATE TABLE IF NOT EXISTS profiles (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
         ",
     )?;
 
@@ -203,9 +537,35 @@ fn init_database(conn: &Connection) -> Result<(), rusqlite::Error> {
         "ALTER TABLE exercises ADD COLUMN current_level INTEGER DEFAULT 1",
         [],
     );
+    let _ = conn.execute(
+        "ALTER TABLE exercises ADD COLUMN difficulty REAL DEFAULT 1.0",
+        [],
+    );
+    let _ = conn.execute("ALTER TABLE exercises ADD COLUMN category TEXT", []);
+    let _ = conn.execute("ALTER TABLE exercises ADD COLUMN seconds_per_rep REAL", []);
+    let _ = conn.execute("ALTER TABLE exercises ADD COLUMN color TEXT", []);
+    let _ = conn.execute(
+        "ALTER TABLE exercises ADD COLUMN remind INTEGER DEFAULT 1",
+        [],
+    );
+    let _ = conn.execute("ALTER TABLE exercise_logs ADD COLUMN weight REAL", []);
+
+    // Backfill categories for exercises added from the default list
+    for (name, _xp, _icon, category) in get_default_exercises_list() {
+        conn.execute(
+            "UPDATE exercises SET category = ? WHERE name = ? AND category IS NULL",
+            params![category, name],
+        )?;
+    }
 
     // No default exercises - users add exercises through onboarding
 
+    // Existing data belongs to the default profile
+    conn.execute(
+        "INSERT OR IGNORE INTO profiles (id, name) VALUES (1, 'default')",
+        [],
+    )?;
+
     // Seed user stats
     conn.execute(
         "INSERT OR IGNORE INTO user_stats (id, current_streak, longest_streak) VALUES (1, 0, 0)",
@@ -283,1484 +643,9360 @@ fn init_database(conn: &Connection) -> Result<(), rusqlite::Error> {
     Ok(())
 }
 
-// ============ Tauri Commands ============
+// ============ Database Health ============
 
-#[tauri::command]
-fn get_exercises(state: State<DbState>) -> Result<Vec<Exercise>, String> {
-    let conn = state.0.lock().map_err(|e| e.to_string())?;
-    let mut stmt = conn
-        .prepare("SELECT id, name, xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1), icon, created_at FROM exercises ORDER BY current_level DESC, total_xp DESC")
-        .map_err(|e| e.to_string())?;
+/// How many daily snapshots of a healthy database to keep in `backups/`
+const DB_BACKUPS_TO_KEEP: usize = 3;
 
-    let exercises = stmt
-        .query_map([], |row| {
-            Ok(Exercise {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                xp_per_rep: row.get(2)?,
-                total_xp: row.get(3)?,
-                current_level: row.get(4)?,
-                icon: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbHealth {
+    pub ok: bool,
+    pub issues: Vec<String>,
+    pub restored_from_backup: Option<String>,
+    #[serde(default)]
+    pub repaired: Vec<String>, // Problems fixed in place at startup, no restore needed
+}
 
-    Ok(exercises)
+fn integrity_check_messages(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let mut messages = Vec::new();
+    for row in stmt.query_map([], |row| row.get(0))? {
+        messages.push(row?);
+    }
+    Ok(messages)
 }
 
-#[tauri::command]
-fn add_exercise(state: State<DbState>, name: String, xp_per_rep: i32) -> Result<(), String> {
-    let conn = state.0.lock().map_err(|e| e.to_string())?;
+/// Lightweight startup check: SQLite's integrity check plus logs pointing at missing exercises.
+fn check_database_health(conn: &Connection) -> DbHealth {
+    let mut issues = Vec::new();
+
+    match integrity_check_messages(conn) {
+        Ok(messages) => issues.extend(messages.into_iter().filter(|m| m != "ok")),
+        Err(e) => issues.push(format!("Integrity check failed: {}", e)),
+    }
+
+    match conn.query_row(
+        "SELECT COUNT(*) FROM exercise_logs WHERE exercise_id NOT IN (SELECT id FROM exercises)",
+        [],
+        |row| row.get::<_, i64>(0),
+    ) {
+        Ok(0) => {}
+        Ok(count) => issues.push(format!(
+            "{} exercise log(s) reference missing exercises",
+            count
+        )),
+        Err(e) => issues.push(format!("Log reference check failed: {}", e)),
+    }
+
+    DbHealth {
+        ok: issues.is_empty(),
+        issues,
+        restored_from_backup: None,
+        repaired: Vec::new(),
+    }
+}
+
+/// Deletes logs pointing at exercises that no longer exist. They can't be shown or
+/// credited anywhere, and restoring a whole backup over them would lose far more.
+fn repair_orphan_logs(conn: &Connection) -> rusqlite::Result<usize> {
     conn.execute(
-        "INSERT INTO exercises (name, xp_per_rep, total_xp, current_level) VALUES (?, ?, 0, 1)",
-        params![name, xp_per_rep],
+        "DELETE FROM exercise_logs WHERE exercise_id NOT IN (SELECT id FROM exercises)",
+        [],
     )
-    .map_err(|e| e.to_string())?;
-    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MaintenanceResult {
+    pub size_before: i64, // Bytes
+    pub size_after: i64,
 }
 
 #[tauri::command]
-fn delete_exercise(state: State<DbState>, id: i64) -> Result<(), String> {
+fn maintenance(state: State<DbState>) -> Result<MaintenanceResult, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    conn.execute(
-        "DELETE FROM exercise_logs WHERE exercise_id = ?",
-        params![id],
+    run_maintenance(&conn)
+}
+
+/// Size of the database as SQLite sees it, which matches the file on disk.
+fn database_size(conn: &Connection) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
     )
-    .map_err(|e| e.to_string())?;
-    conn.execute("DELETE FROM exercises WHERE id = ?", params![id])
+    .map_err(|e| e.to_string())
+}
+
+/// Reclaims the free pages left behind by deletes and refreshes the query planner's
+/// statistics. VACUUM can't run inside a transaction, so refuse rather than fail halfway.
+fn run_maintenance(conn: &Connection) -> Result<MaintenanceResult, String> {
+    if !conn.is_autocommit() {
+        return Err("Cannot run maintenance while a transaction is open".to_string());
+    }
+    let size_before = database_size(conn)?;
+    conn.execute_batch("VACUUM; ANALYZE;")
         .map_err(|e| e.to_string())?;
-    Ok(())
+    Ok(MaintenanceResult {
+        size_before,
+        size_after: database_size(conn)?,
+    })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct DefaultExercise {
-    pub name: String,
-    pub xp_per_rep: i32,
-    pub icon: String,
-    pub category: String,
+fn newest_backup(backup_dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(backup_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "db"))
+        .max_by_key(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
 }
 
-#[tauri::command]
-fn get_default_exercises() -> Vec<DefaultExercise> {
-    get_default_exercises_list()
-        .into_iter()
-        .map(|(name, xp, icon, category)| DefaultExercise {
-            name: name.to_string(),
-            xp_per_rep: xp,
-            icon: icon.to_string(),
-            category: category.to_string(),
+/// Writes today's snapshot of the database (once per day) and prunes older ones.
+fn snapshot_database(conn: &Connection, backup_dir: &Path) {
+    let path = backup_dir.join(format!("geekfit-{}.db", today_string(conn)));
+    if path.exists() {
+        return;
+    }
+    if let Err(e) = std::fs::create_dir_all(backup_dir) {
+        log::error!("Failed to create backup directory: {}", e);
+        return;
+    }
+    if let Err(e) = conn.execute("VACUUM INTO ?", params![path.to_string_lossy().to_string()]) {
+        log::error!("Failed to back up database: {}", e);
+        return;
+    }
+
+    let mut snapshots: Vec<PathBuf> = std::fs::read_dir(backup_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "db"))
+                .collect()
         })
-        .collect()
+        .unwrap_or_default();
+    // Names embed the date, so lexical order is chronological
+    snapshots.sort();
+    let excess = snapshots.len().saturating_sub(DB_BACKUPS_TO_KEEP);
+    for old in &snapshots[..excess] {
+        let _ = std::fs::remove_file(old);
+    }
 }
 
-#[tauri::command]
-fn complete_initial_setup(
-    state: State<DbState>,
-    selected_exercises: Vec<String>,
-) -> Result<(), String> {
-    let conn = state.0.lock().map_err(|e| e.to_string())?;
+/// True if `dir` exists (or can be created) and accepts new files.
+fn is_writable_dir(dir: &Path) -> bool {
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(".geekfit-write-test");
+    let writable = std::fs::write(&probe, b"ok").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
 
-    // Get default exercises with their details
-    let default_exercises = get_default_exercises_list();
+/// Marks a fallback directory as the one in use, so later launches keep using it even
+/// if the usual data directory becomes writable again.
+pub const FALLBACK_MARKER: &str = ".geekfit-fallback";
+
+/// A fallback adopted on an earlier launch if it is still writable, otherwise the first
+/// writable directory among `primary` and then `fallbacks`.
+fn select_data_dir(
+    primary: &Path,
+    fallbacks: &[PathBuf],
+    is_writable: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    if let Some(adopted) = fallbacks
+        .iter()
+        .find(|dir| dir.join(FALLBACK_MARKER).exists() && is_writable(dir))
+    {
+        return Some(adopted.clone());
+    }
+    std::iter::once(primary.to_path_buf())
+        .chain(fallbacks.iter().cloned())
+        .find(|dir| is_writable(dir))
+}
 
-    // Add only the selected exercises
-    for (name, xp, icon, _category) in default_exercises {
-        if selected_exercises.contains(&name.to_string()) {
-            conn.execute(
-                "INSERT OR IGNORE INTO exercises (name, xp_per_rep, icon, total_xp, current_level) VALUES (?, ?, ?, 0, 1)",
-                params![name, xp, icon],
-            )
-            .map_err(|e| e.to_string())?;
+/// Makes `fallback` the data directory: copies the databases from the read-only
+/// `primary` (geekfit.db and profiles, with their WAL side files) so existing data
+/// carries over instead of starting empty, then writes the marker. Files already in
+/// `fallback` are left alone. Returns how many databases were copied.
+fn adopt_fallback_dir(primary: &Path, fallback: &Path) -> std::io::Result<usize> {
+    let mut databases = vec![primary.join("geekfit.db")];
+    if let Ok(entries) = std::fs::read_dir(primary.join("profiles")) {
+        databases.extend(
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "db")),
+        );
+    }
+
+    let mut copied = 0;
+    for source in databases.iter().filter(|path| path.exists()) {
+        let Ok(relative) = source.strip_prefix(primary) else {
+            continue;
+        };
+        let dest = fallback.join(relative);
+        if dest.exists() {
+            continue;
         }
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(source, &dest)?;
+        for suffix in ["-wal", "-shm"] {
+            let side = with_file_suffix(source, suffix);
+            if side.exists() {
+                std::fs::copy(&side, with_file_suffix(&dest, suffix))?;
+            }
+        }
+        copied += 1;
     }
 
-    Ok(())
+    std::fs::write(
+        fallback.join(FALLBACK_MARKER),
+        primary.display().to_string(),
+    )?;
+    Ok(copied)
 }
 
-#[tauri::command]
-fn log_exercise(
-    state: State<DbState>,
-    exercise_id: i64,
-    reps: i32,
-) -> Result<LogExerciseResult, String> {
-    let conn = state.0.lock().map_err(|e| e.to_string())?;
+/// `path` with `suffix` appended to the file name, e.g. SQLite's `-wal` side file.
+fn with_file_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
 
-    // Get exercise info
-    let (xp_per_rep, old_xp, old_level): (i32, i64, i32) = conn
-        .query_row(
-            "SELECT xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1) FROM exercises WHERE id = ?",
-            params![exercise_id],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        )
-        .map_err(|e| e.to_string())?;
+/// Moves a database and its `-wal`/`-shm` side files to `dest`, so a restore never
+/// overwrites the only copy of recent data and a stale WAL can't replay onto the backup.
+fn set_aside_database(db_path: &Path, dest: &Path) -> std::io::Result<()> {
+    std::fs::rename(db_path, dest)?;
+    for suffix in ["-wal", "-shm"] {
+        let side = with_file_suffix(db_path, suffix);
+        if side.exists() {
+            std::fs::rename(&side, with_file_suffix(dest, suffix))?;
+        }
+    }
+    Ok(())
+}
 
-    let xp_earned = xp_per_rep * reps;
-    let new_xp = old_xp + xp_earned as i64;
-    let new_level = level_from_xp(new_xp);
-    let leveled_up = new_level > old_level;
+fn open_and_init(db_path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    init_database(&conn).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
 
-    // Log the exercise (use localtime for correct timezone)
-    conn.execute(
-        "INSERT INTO exercise_logs (exercise_id, reps, xp_earned, logged_at) VALUES (?, ?, ?, datetime('now', 'localtime'))",
-        params![exercise_id, reps, xp_earned],
-    )
-    .map_err(|e| e.to_string())?;
+/// Opens and initializes a database. Logs pointing at missing exercises are repaired in
+/// place; only a failed integrity check (or a file SQLite can't open) restores the newest
+/// backup from `backup_dir`, after moving the damaged file aside as
+/// `<name>.corrupt-<timestamp>.db` (e.g. `geekfit.corrupt-20240301-080000.db`).
+fn open_checked_database(
+    db_path: &Path,
+    backup_dir: &Path,
+) -> Result<(Connection, DbHealth), String> {
+    let mut health = match open_and_init(db_path) {
+        Ok(conn) => {
+            let mut health = check_database_health(&conn);
+            if integrity_check_messages(&conn)
+                .is_ok_and(|messages| messages.iter().all(|m| m == "ok"))
+            {
+                match repair_orphan_logs(&conn) {
+                    Ok(0) => {}
+                    Ok(count) => {
+                        log::warn!("Removed {} log(s) referencing missing exercises", count);
+                        health.repaired.push(format!(
+                            "Removed {} exercise log(s) referencing missing exercises",
+                            count
+                        ));
+                    }
+                    Err(e) => log::error!("Failed to repair exercise logs: {}", e),
+                }
+                // Keep the original issues so the UI can explain what was repaired
+                health.ok = check_database_health(&conn).ok;
+                if health.ok {
+                    snapshot_database(&conn, backup_dir);
+                }
+                return Ok((conn, health));
+            }
+            health
+        }
+        Err(e) => DbHealth {
+            ok: false,
+            issues: vec![format!("Database could not be opened: {}", e)],
+            restored_from_backup: None,
+            repaired: Vec::new(),
+        },
+    };
+    log::warn!("Database integrity check failed: {:?}", health.issues);
 
-    // Update exercise XP and level
-    conn.execute(
-        "UPDATE exercises SET total_xp = ?, current_level = ? WHERE id = ?",
-        params![new_xp, new_level, exercise_id],
-    )
-    .map_err(|e| e.to_string())?;
-
-    // Update streak
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let last_date: Option<String> = conn
-        .query_row(
-            "SELECT last_exercise_date FROM user_stats WHERE id = 1",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(None);
-
-    let (current_streak, longest_streak): (i32, i32) = conn
-        .query_row(
-            "SELECT current_streak, longest_streak FROM user_stats WHERE id = 1",
-            [],
-            |row| Ok((row.get(0)?, row.get(1)?)),
-        )
-        .unwrap_or((0, 0));
+    let Some(backup) = newest_backup(backup_dir) else {
+        return Ok((open_and_init(db_path)?, health));
+    };
 
-    let new_streak = match &last_date {
-        Some(date) => {
-            if date == &today {
-                current_streak
-            } else {
-                let yesterday = (chrono::Local::now() - chrono::Duration::days(1))
-                    .format("%Y-%m-%d")
-                    .to_string();
-                if date == &yesterday {
-                    current_streak + 1
-                } else {
-                    1
-                }
-            }
+    let stem = db_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "geekfit".to_string());
+    let corrupt_path = db_path.with_file_name(format!(
+        "{}.corrupt-{}.db",
+        stem,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    let restored =
+        set_aside_database(db_path, &corrupt_path).and_then(|_| std::fs::copy(&backup, db_path));
+    let conn = open_and_init(db_path)?;
+
+    match restored {
+        Ok(_) => {
+            log::info!(
+                "Restored database from backup {}, kept the damaged copy as {}",
+                backup.display(),
+                corrupt_path.display()
+            );
+            // Keep the original issues so the UI can explain why a restore happened
+            health.ok = check_database_health(&conn).ok;
+            health.restored_from_backup = Some(backup.display().to_string());
         }
-        None => 1,
-    };
-    let new_longest = std::cmp::max(new_streak, longest_streak);
+        Err(e) => log::error!("Failed to restore database from backup: {}", e),
+    }
 
-    conn.execute(
-        "UPDATE user_stats SET current_streak = ?, longest_streak = ?, last_exercise_date = ? WHERE id = 1",
-        params![new_streak, new_longest, today],
-    )
-    .map_err(|e| e.to_string())?;
+    Ok((conn, health))
+}
 
-    // Calculate total level for achievements
-    let total_level: i32 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(current_level), 0) FROM exercises",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(0);
+// ============ Profiles ============
 
-    // Check achievements
-    check_achievements(&conn, new_level, new_streak, total_level)?;
+// Profile 1 ("default") is geekfit.db itself, which also holds the profile list and the
+// `active_profile_id` setting. Every other profile gets its own database file, so all
+// exercises, logs, stats and settings are scoped to the active profile by construction.
 
-    Ok(LogExerciseResult {
-        xp_earned,
-        new_exercise_level: new_level,
-        leveled_up,
-    })
-}
+const DEFAULT_PROFILE_ID: i64 = 1;
 
-fn check_achievements(
-    conn: &Connection,
-    exercise_level: i32,
-    streak: i32,
-    total_level: i32,
-) -> Result<(), String> {
-    let today = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+    pub active: bool,
+}
 
-    // First exercise achievement
-    let log_count: i32 = conn
-        .query_row("SELECT COUNT(*) FROM exercise_logs", [], |row| row.get(0))
-        .map_err(|e| e.to_string())?;
-    if log_count == 1 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'first_exercise' AND unlocked_at IS NULL",
-            params![today],
-        )
-        .map_err(|e| e.to_string())?;
+fn profile_db_path(app_dir: &Path, profile_id: i64) -> PathBuf {
+    if profile_id == DEFAULT_PROFILE_ID {
+        app_dir.join("geekfit.db")
+    } else {
+        app_dir
+            .join("profiles")
+            .join(format!("profile-{}.db", profile_id))
     }
+}
 
-    // Skill level achievements (any single exercise)
-    if exercise_level >= 10 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'skill_10' AND unlocked_at IS NULL",
-            params![today],
-        )
-        .map_err(|e| e.to_string())?;
-    }
-    if exercise_level >= 25 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'skill_25' AND unlocked_at IS NULL",
-            params![today],
-        )
-        .map_err(|e| e.to_string())?;
-    }
-    if exercise_level >= 50 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'skill_50' AND unlocked_at IS NULL",
-            params![today],
-        )
-        .map_err(|e| e.to_string())?;
+/// Daily snapshots of geekfit.db go in `backups/`, other profiles' in `backups/profile-<id>/`.
+fn profile_backup_dir(app_dir: &Path, profile_id: i64) -> PathBuf {
+    let backups = app_dir.join("backups");
+    if profile_id == DEFAULT_PROFILE_ID {
+        backups
+    } else {
+        backups.join(format!("profile-{}", profile_id))
     }
+}
 
-    // Total level achievement
-    if total_level >= 100 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'total_100' AND unlocked_at IS NULL",
-            params![today],
-        )
-        .map_err(|e| e.to_string())?;
+/// Opens a profile's database to make it the active one, with the same health check,
+/// repair or restore and daily snapshot as geekfit.db gets at startup.
+fn open_checked_profile(app_dir: &Path, profile_id: i64) -> Result<(Connection, DbHealth), String> {
+    let path = profile_db_path(app_dir, profile_id);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
+    open_checked_database(&path, &profile_backup_dir(app_dir, profile_id))
+}
 
-    // Streak achievements
-    if streak >= 7 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'week_streak' AND unlocked_at IS NULL",
-            params![today],
-        )
-        .map_err(|e| e.to_string())?;
-    }
-    if streak >= 30 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'month_streak' AND unlocked_at IS NULL",
-            params![today],
-        )
-        .map_err(|e| e.to_string())?;
+fn open_profile_database(app_dir: &Path, profile_id: i64) -> Result<Connection, String> {
+    let path = profile_db_path(app_dir, profile_id);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
+    let conn = Connection::open(&path).map_err(|e| e.to_string())?;
+    init_database(&conn).map_err(|e| e.to_string())?;
+    Ok(conn)
+}
 
-    // Variety achievement
-    let distinct_exercises: i32 = conn
-        .query_row(
-            "SELECT COUNT(DISTINCT exercise_id) FROM exercise_logs",
-            [],
-            |row| row.get(0),
-        )
-        .map_err(|e| e.to_string())?;
-    if distinct_exercises >= 5 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'variety' AND unlocked_at IS NULL",
-            params![today],
-        )
-        .map_err(|e| e.to_string())?;
-    }
+/// The profile registry lives in the default profile's database.
+fn open_profile_registry(app_dir: &Path) -> Result<Connection, String> {
+    open_profile_database(app_dir, DEFAULT_PROFILE_ID)
+}
 
-    // Century achievement (100 pushups in a day)
-    let today_date = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let pushups_today: i32 = conn
+fn active_profile_id(registry: &Connection) -> i64 {
+    get_setting_value(registry, "active_profile_id", "1")
+        .parse()
+        .unwrap_or(DEFAULT_PROFILE_ID)
+}
+
+fn insert_profile(registry: &Connection, name: &str) -> Result<i64, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    let exists: bool = registry
         .query_row(
-            "SELECT COALESCE(SUM(reps), 0) FROM exercise_logs el
-             JOIN exercises e ON el.exercise_id = e.id
-             WHERE e.name = 'Pushups' AND DATE(el.logged_at) = ?",
-            params![today_date],
+            "SELECT COUNT(*) > 0 FROM profiles WHERE LOWER(name) = LOWER(?)",
+            params![name],
             |row| row.get(0),
         )
-        .unwrap_or(0);
-    if pushups_today >= 100 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'hundred_pushups' AND unlocked_at IS NULL",
-            params![today],
-        )
         .map_err(|e| e.to_string())?;
+    if exists {
+        return Err(format!("A profile named '{}' already exists", name));
     }
 
-    // Time-based achievements
-    let current_hour = chrono::Local::now().hour();
-    if current_hour < 7 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'early_bird' AND unlocked_at IS NULL",
-            params![today],
-        )
-        .map_err(|e| e.to_string())?;
-    }
-    if current_hour >= 22 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'night_owl' AND unlocked_at IS NULL",
-            params![today],
-        )
+    registry
+        .execute("INSERT INTO profiles (name) VALUES (?)", params![name])
         .map_err(|e| e.to_string())?;
-    }
+    Ok(registry.last_insert_rowid())
+}
 
-    // Total reps achievements
-    let total_reps: i64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(reps), 0) FROM exercise_logs",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(0);
-    if total_reps >= 1000 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'thousand_reps' AND unlocked_at IS NULL",
-            params![today],
-        )
+fn list_profiles_in(registry: &Connection) -> Result<Vec<Profile>, String> {
+    let active_id = active_profile_id(registry);
+    let mut stmt = registry
+        .prepare("SELECT id, name, created_at FROM profiles ORDER BY id")
         .map_err(|e| e.to_string())?;
-    }
-    if total_reps >= 10000 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'ten_thousand_reps' AND unlocked_at IS NULL",
-            params![today],
-        )
+
+    let profiles = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            Ok(Profile {
+                id,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+                active: id == active_id,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
+
+    Ok(profiles)
+}
+
+#[tauri::command]
+fn create_profile(profiles: State<ProfileState>, name: String) -> Result<i64, String> {
+    let registry = open_profile_registry(&profiles.app_dir)?;
+    let id = insert_profile(&registry, &name)?;
+    open_profile_database(&profiles.app_dir, id)?;
+    Ok(id)
+}
+
+#[tauri::command]
+fn list_profiles(profiles: State<ProfileState>) -> Result<Vec<Profile>, String> {
+    let registry = open_profile_registry(&profiles.app_dir)?;
+    list_profiles_in(&registry)
+}
+
+#[tauri::command]
+fn switch_profile(
+    app: AppHandle,
+    state: State<DbState>,
+    profiles: State<ProfileState>,
+    health: State<DbHealthState>,
+    last_log: State<LastLogState>,
+    undo: State<UndoState>,
+    id: i64,
+) -> Result<(), String> {
+    let registry = open_profile_registry(&profiles.app_dir)?;
+    if !list_profiles_in(&registry)?.iter().any(|p| p.id == id) {
+        return Err(format!("Profile {} not found", id));
     }
 
-    // Nice achievement (level 69)
-    if exercise_level == 69 {
-        conn.execute(
-            "UPDATE achievements SET unlocked_at = ? WHERE key = 'nice' AND unlocked_at IS NULL",
-            params![today],
+    let (conn, db_health) = open_checked_profile(&profiles.app_dir, id)?;
+    *state.0.lock().map_err(|e| e.to_string())? = conn;
+    *health.0.lock().map_err(|e| e.to_string())? = db_health;
+    registry
+        .execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('active_profile_id', ?)",
+            params![id.to_string()],
         )
         .map_err(|e| e.to_string())?;
-    }
 
+    // The last log, undo history and tray presets belonged to the previous profile
+    last_log.clear();
+    undo.clear();
+    refresh_tray_menu(&app);
+    let _ = app.emit("profile-switched", id);
     Ok(())
 }
 
+// ============ Tauri Commands ============
+
 #[tauri::command]
-fn get_stats(state: State<DbState>) -> Result<UserStats, String> {
+fn get_exercises(state: State<DbState>) -> Result<Vec<Exercise>, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
+    list_exercises(&conn)
+}
 
-    // Calculate totals from exercises
-    let (total_xp, total_level, exercise_count): (i64, i32, i32) = conn
-        .query_row(
-            "SELECT COALESCE(SUM(total_xp), 0), COALESCE(SUM(current_level), 0), COUNT(*) FROM exercises",
-            [],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        )
-        .unwrap_or((0, 0, 0));
+fn list_exercises(conn: &Connection) -> Result<Vec<Exercise>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM {} ORDER BY current_level DESC, total_xp DESC",
+            EXERCISE_COLUMNS, EXERCISE_SOURCE
+        ))
+        .map_err(|e| e.to_string())?;
 
-    // Get streak info
-    let (current_streak, longest_streak, last_exercise_date): (i32, i32, Option<String>) = conn
-        .query_row(
-            "SELECT current_streak, longest_streak, last_exercise_date FROM user_stats WHERE id = 1",
-            [],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-        )
-        .unwrap_or((0, 0, None));
+    let mut exercises = stmt
+        .query_map([], exercise_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
 
-    Ok(UserStats {
-        total_xp,
-        total_level,
-        current_streak,
-        longest_streak,
-        last_exercise_date,
-        exercise_count,
-    })
+    apply_xp_decay(conn, &mut exercises, effective_today(conn));
+    Ok(exercises)
+}
+
+/// Lowers each exercise's XP and level by its idle time as of `today`. Lists, single
+/// exercises, stats, the dashboard and exports all read through here so they agree.
+/// The stored totals only catch up on the exercise's next log, which builds on the
+/// decayed value; until then, turning decay off restores the full XP.
+fn apply_xp_decay(conn: &Connection, exercises: &mut [Exercise], today: chrono::NaiveDate) {
+    for exercise in exercises.iter_mut() {
+        (exercise.total_xp, exercise.current_level) = decayed_totals(
+            conn,
+            exercise.id,
+            exercise.total_xp,
+            exercise.current_level,
+            today,
+        );
+    }
 }
 
 #[tauri::command]
-fn get_achievements(state: State<DbState>) -> Result<Vec<Achievement>, String> {
+fn add_exercise(state: State<DbState>, name: String, xp_per_rep: i32) -> Result<(), String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, key, name, description, icon, unlocked_at FROM achievements ORDER BY id",
+    conn.execute(
+        "INSERT INTO exercises (name, xp_per_rep, total_xp, current_level) VALUES (?, ?, 0, 1)",
+        params![name, xp_per_rep],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+fn delete_exercise(state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM exercise_logs WHERE exercise_id = ?",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM level_history WHERE exercise_id = ?",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM routine_steps WHERE exercise_id = ?",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM exercises WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+fn set_difficulty(state: State<DbState>, id: i64, difficulty: f64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    set_exercise_difficulty(&conn, id, difficulty)
+}
+
+fn set_exercise_difficulty(conn: &Connection, id: i64, difficulty: f64) -> Result<(), String> {
+    if !(0.1..=5.0).contains(&difficulty) {
+        return Err("Difficulty must be between 0.1 and 5.0".to_string());
+    }
+    let updated = conn
+        .execute(
+            "UPDATE exercises SET difficulty = ? WHERE id = ?",
+            params![difficulty, id],
         )
         .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Exercise {} not found", id));
+    }
+    Ok(())
+}
 
-    let achievements = stmt
-        .query_map([], |row| {
-            Ok(Achievement {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                name: row.get(2)?,
-                description: row.get(3)?,
-                icon: row.get(4)?,
-                unlocked_at: row.get(5)?,
-            })
-        })
+/// Material icon names the UI can render for exercises
+const AVAILABLE_ICONS: &[&str] = &[
+    "fitness_center",
+    "self_improvement",
+    "directions_run",
+    "directions_walk",
+    "directions_bike",
+    "accessibility",
+    "accessibility_new",
+    "sports_gymnastics",
+    "sports_martial_arts",
+    "sports_kabaddi",
+    "sports_handball",
+    "hiking",
+    "rowing",
+    "pool",
+    "spa",
+    "timer",
+    "favorite",
+];
+
+#[tauri::command]
+fn get_available_icons() -> Vec<String> {
+    AVAILABLE_ICONS
+        .iter()
+        .map(|icon| icon.to_string())
+        .collect()
+}
+
+#[tauri::command]
+fn set_exercise_icon(state: State<DbState>, id: i64, icon: String) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    update_exercise_icon(&conn, id, &icon)
+}
+
+fn update_exercise_icon(conn: &Connection, id: i64, icon: &str) -> Result<(), String> {
+    if !AVAILABLE_ICONS.contains(&icon) {
+        return Err(format!(
+            "Unknown icon '{}'. Valid icons: {}",
+            icon,
+            AVAILABLE_ICONS.join(", ")
+        ));
+    }
+    let updated = conn
+        .execute(
+            "UPDATE exercises SET icon = ? WHERE id = ?",
+            params![icon, id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Exercise {} not found", id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn duplicate_exercise(state: State<DbState>, id: i64, new_name: String) -> Result<i64, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    copy_exercise(&conn, id, &new_name)
+}
+
+/// Creates a new exercise with the same config as `id`, starting fresh at level 1.
+fn copy_exercise(conn: &Connection, id: i64, new_name: &str) -> Result<i64, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("Exercise name cannot be empty".to_string());
+    }
+
+    let exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM exercises WHERE LOWER(name) = LOWER(?)",
+            params![new_name],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if exists {
+        return Err(format!("An exercise named '{}' already exists", new_name));
+    }
+
+    let inserted = conn
+        .execute(
+            "INSERT INTO exercises (name, xp_per_rep, icon, difficulty, category, seconds_per_rep, color, remind, total_xp, current_level)
+             SELECT ?, xp_per_rep, icon, difficulty, category, seconds_per_rep, color, remind, 0, 1 FROM exercises WHERE id = ?",
+            params![new_name, id],
+        )
+        .map_err(|e| e.to_string())?;
+    if inserted == 0 {
+        return Err(format!("Exercise {} not found", id));
+    }
+
+    Ok(conn.last_insert_rowid())
+}
+
+#[tauri::command]
+fn merge_exercises(state: State<DbState>, from_id: i64, into_id: i64) -> Result<Exercise, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    merge_exercise_into(&conn, from_id, into_id)
+}
+
+/// Moves all of `from_id`'s logs and XP onto `into_id`, then deletes `from_id`.
+fn merge_exercise_into(conn: &Connection, from_id: i64, into_id: i64) -> Result<Exercise, String> {
+    if from_id == into_id {
+        return Err("Cannot merge an exercise into itself".to_string());
+    }
+    let from = get_exercise_by_id(conn, from_id)?;
+    let into = get_exercise_by_id(conn, into_id)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    tx.execute(
+        "UPDATE exercise_logs SET exercise_id = ? WHERE exercise_id = ?",
+        params![into_id, from_id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE challenges SET exercise_id = ? WHERE exercise_id = ?",
+        params![into_id, from_id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE routine_steps SET exercise_id = ? WHERE exercise_id = ?",
+        params![into_id, from_id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute(
+        "DELETE FROM level_history WHERE exercise_id = ?",
+        params![from_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    let new_xp = into.total_xp + from.total_xp;
+    let new_level = level_from_xp_capped(new_xp, get_max_level(&tx));
+    tx.execute(
+        "UPDATE exercises SET total_xp = ?, current_level = ? WHERE id = ?",
+        params![new_xp, new_level, into_id],
+    )
+    .map_err(|e| e.to_string())?;
+    record_level_ups(&tx, into_id, into.current_level, new_level, None)
+        .map_err(|e| e.to_string())?;
+
+    tx.execute("DELETE FROM exercises WHERE id = ?", params![from_id])
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    get_exercise_by_id(conn, into_id)
+}
+
+#[tauri::command]
+fn recalculate_all_levels(state: State<DbState>) -> Result<i32, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    recalculate_levels(&conn)
+}
+
+/// Brings every exercise's `current_level` back in line with its `total_xp`.
+/// Returns how many rows were corrected.
+fn recalculate_levels(conn: &Connection) -> Result<i32, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let corrected = sync_levels_with_xp(&tx)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(corrected)
+}
+
+// Callers are expected to wrap this in a transaction
+fn sync_levels_with_xp(conn: &Connection) -> Result<i32, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, COALESCE(total_xp, 0), COALESCE(current_level, 1) FROM exercises")
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(i64, i64, i32)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
         .map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
 
-    Ok(achievements)
+    let max_level = get_max_level(conn);
+    let mut corrected = 0;
+    for (id, total_xp, current_level) in rows {
+        let level = level_from_xp_capped(total_xp, max_level);
+        if level != current_level {
+            conn.execute(
+                "UPDATE exercises SET current_level = ? WHERE id = ?",
+                params![level, id],
+            )
+            .map_err(|e| e.to_string())?;
+            corrected += 1;
+        }
+    }
+
+    Ok(corrected)
 }
 
 #[tauri::command]
-fn get_exercise_history(state: State<DbState>, days: i32) -> Result<Vec<ExerciseLog>, String> {
+fn clear_history_before(state: State<DbState>, date: String) -> Result<i32, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, exercise_id, reps, xp_earned, logged_at FROM exercise_logs
-             WHERE logged_at >= datetime('now', 'localtime', ? || ' days') ORDER BY logged_at DESC",
+    clear_logs_before(&conn, &date)
+}
+
+/// Deletes logs from before `date` (YYYY-MM-DD) and rebuilds XP, levels and streaks
+/// from the logs that remain. Returns the number of deleted logs.
+fn clear_logs_before(conn: &Connection, date: &str) -> Result<i32, String> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}', expected format YYYY-MM-DD", date))?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+
+    let deleted = tx
+        .execute(
+            &format!(
+                "DELETE FROM exercise_logs WHERE {} < ?",
+                log_day_sql(conn, "logged_at")
+            ),
+            params![date],
         )
         .map_err(|e| e.to_string())?;
 
-    let days_param = format!("-{}", days);
-    let logs = stmt
-        .query_map([days_param], |row| {
-            Ok(ExerciseLog {
-                id: row.get(0)?,
-                exercise_id: row.get(1)?,
-                reps: row.get(2)?,
-                xp_earned: row.get(3)?,
-                logged_at: row.get(4)?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
+    let exercise_ids: Vec<i64> = {
+        let mut stmt = tx
+            .prepare("SELECT id FROM exercises")
+            .map_err(|e| e.to_string())?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?;
+        ids
+    };
+    for id in exercise_ids {
+        let xp = replayed_xp(&tx, id).map_err(|e| e.to_string())?;
+        tx.execute(
+            "UPDATE exercises SET total_xp = ? WHERE id = ?",
+            params![xp, id],
+        )
         .map_err(|e| e.to_string())?;
+    }
+    sync_levels_with_xp(&tx)?;
+
+    // Drop milestones for levels that are no longer reached
+    tx.execute(
+        "DELETE FROM level_history WHERE level > (
+            SELECT COALESCE(current_level, 1) FROM exercises
+            WHERE exercises.id = level_history.exercise_id
+        )",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
 
-    Ok(logs)
+    recompute_streak(&tx)?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(deleted as i32)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct ActivityData {
-    pub date: String,
-    pub count: i32,
-    pub xp: i32,
+pub struct ExerciseDetail {
+    #[serde(flatten)]
+    pub exercise: Exercise,
+    pub title: String,
+}
+
+fn get_exercise_by_id(conn: &Connection, id: i64) -> Result<Exercise, String> {
+    let exercise = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM {} WHERE id = ?",
+                EXERCISE_COLUMNS, EXERCISE_SOURCE
+            ),
+            params![id],
+            exercise_from_row,
+        )
+        .map_err(|e| e.to_string())?;
+    let mut exercises = [exercise];
+    apply_xp_decay(conn, &mut exercises, effective_today(conn));
+    let [exercise] = exercises;
+    Ok(exercise)
 }
 
 #[tauri::command]
-fn get_activity_data(state: State<DbState>, days: i32) -> Result<Vec<ActivityData>, String> {
+fn get_exercise_detail(state: State<DbState>, id: i64) -> Result<ExerciseDetail, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let exercise = get_exercise_by_id(&conn, id)?;
+    let title = levels::get_title_for_level(exercise.current_level).to_string();
+    Ok(ExerciseDetail { exercise, title })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LevelMilestone {
+    pub level: i32,
+    pub reached_at: String,
+}
 
+fn level_timeline(conn: &Connection, exercise_id: i64) -> Result<Vec<LevelMilestone>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT DATE(logged_at) as date, COUNT(*) as count, SUM(xp_earned) as xp
-             FROM exercise_logs
-             WHERE logged_at >= datetime('now', 'localtime', ? || ' days')
-             GROUP BY DATE(logged_at)
-             ORDER BY date",
+            "SELECT level, reached_at FROM level_history
+             WHERE exercise_id = ?
+             ORDER BY level ASC, reached_at ASC",
         )
         .map_err(|e| e.to_string())?;
 
-    let days_param = format!("-{}", days);
-    let activity = stmt
-        .query_map([days_param], |row| {
-            Ok(ActivityData {
-                date: row.get(0)?,
-                count: row.get(1)?,
-                xp: row.get(2)?,
+    let milestones = stmt
+        .query_map(params![exercise_id], |row| {
+            Ok(LevelMilestone {
+                level: row.get(0)?,
+                reached_at: row.get(1)?,
             })
         })
         .map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
 
-    Ok(activity)
+    Ok(milestones)
 }
 
 #[tauri::command]
-fn get_settings(state: State<DbState>) -> Result<Settings, String> {
+fn get_level_timeline(
+    state: State<DbState>,
+    exercise_id: i64,
+) -> Result<Vec<LevelMilestone>, String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
+    level_timeline(&conn, exercise_id)
+}
 
-    let get_setting = |key: &str, default: &str| -> String {
-        conn.query_row(
-            "SELECT value FROM settings WHERE key = ?",
-            params![key],
-            |row| row.get(0),
-        )
-        .unwrap_or_else(|_| default.to_string())
-    };
+#[tauri::command]
+fn get_level_title(level: i32) -> String {
+    levels::get_title_for_level(level).to_string()
+}
 
-    let theme_mode_str = get_setting("theme_mode", "dark");
-    Ok(Settings {
-        reminder_enabled: get_setting("reminder_enabled", "true") == "true",
-        reminder_interval_minutes: get_setting("reminder_interval_minutes", "120")
-            .parse()
-            .unwrap_or(120),
-        sound_enabled: get_setting("sound_enabled", "true") == "true",
-        daily_goal_xp: get_setting("daily_goal_xp", "500").parse().unwrap_or(500),
-        theme_mode: Some(theme_mode_str),
-    })
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DefaultExercise {
+    pub name: String,
+    pub xp_per_rep: i32,
+    pub icon: String,
+    pub category: String,
 }
 
 #[tauri::command]
-fn update_setting(state: State<DbState>, key: String, value: String) -> Result<(), String> {
-    let conn = state.0.lock().map_err(|e| e.to_string())?;
-    conn.execute(
-        "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
-        params![key, value],
-    )
-    .map_err(|e| e.to_string())?;
-    Ok(())
+fn get_default_exercises() -> Vec<DefaultExercise> {
+    get_default_exercises_list()
+        .into_iter()
+        .map(|(name, xp, icon, category)| DefaultExercise {
+            name: name.to_string(),
+            xp_per_rep: xp,
+            icon: icon.to_string(),
+            category: category.to_string(),
+        })
+        .collect()
 }
 
 #[tauri::command]
-fn get_wellness_settings(
+fn complete_initial_setup(
     state: State<DbState>,
-) -> Result<std::collections::HashMap<String, String>, String> {
+    selected_exercises: Vec<String>,
+) -> Result<(), String> {
     let conn = state.0.lock().map_err(|e| e.to_string())?;
-    let mut settings = std::collections::HashMap::new();
 
-    // Define wellness settings with their defaults
-    let wellness_keys = [
-        ("eye_care_enabled", "true"),
-        ("eye_care_interval", "20"),
-        ("hydration_enabled", "true"),
-        ("hydration_interval", "60"),
-        ("hydration_goal", "8"),
-        ("posture_enabled", "true"),
-        ("posture_interval", "45"),
-        ("focus_mode_enabled", "true"),
-        ("focus_mode_threshold", "90"),
-    ];
+    // Get default exercises with their details
+    let default_exercises = get_default_exercises_list();
 
-    for (key, default) in wellness_keys {
-        let value: String = conn
-            .query_row(
-                "SELECT value FROM settings WHERE key = ?",
-                params![format!("wellness_{}", key)],
-                |row| row.get(0),
+    // Add only the selected exercises
+    for (name, xp, icon, category) in default_exercises {
+        if selected_exercises.contains(&name.to_string()) {
+            conn.execute(
+                "INSERT OR IGNORE INTO exercises (name, xp_per_rep, icon, category, total_xp, current_level) VALUES (?, ?, ?, ?, 0, 1)",
+                params![name, xp, icon, category],
             )
-            .unwrap_or_else(|_| default.to_string());
-        settings.insert(key.to_string(), value);
+            .map_err(|e| e.to_string())?;
+        }
     }
 
-    Ok(settings)
+    Ok(())
 }
 
 #[tauri::command]
-fn reset_reminder_timer(
-    reminder_state: State<ReminderState>,
-    reminder_type: String,
+fn log_exercise(
+    app: AppHandle,
+    state: State<DbState>,
+    last_log: State<LastLogState>,
+    exercise_id: i64,
+    reps: i32,
+) -> Result<LogExerciseResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    validate_reps(reps, min_reps(&conn))?;
+
+    // Get exercise info
+    let (xp_per_rep, old_xp, old_level, difficulty): (i32, i64, i32, f64) = conn
+        .query_row(
+            "SELECT xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1), COALESCE(difficulty, 1.0) FROM exercises WHERE id = ?",
+            params![exercise_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let today_date = effective_today(&conn);
+    // Any XP decay becomes real now: the log builds on the decayed total
+    let (old_xp, old_level) = decayed_totals(&conn, exercise_id, old_xp, old_level, today_date);
+    let today = today_date.format("%Y-%m-%d").to_string();
+    let now = local_now(&conn);
+    let logged_at = now.format("%Y-%m-%d %H:%M:%S").to_string();
+    let combo_bonus = combo_bonus_for(&conn, exercise_id, &today);
+    let xp_multiplier = xp_multiplier_at(&conn, exercise_id, &logged_at);
+    let xp_earned = compute_xp(xp_per_rep, reps, difficulty, xp_multiplier) + combo_bonus;
+    let new_xp = old_xp + xp_earned as i64;
+    let new_level = level_from_xp_capped(new_xp, get_max_level(&conn));
+    let leveled_up = new_level > old_level;
+
+    // Log the exercise (in the home timezone, so day buckets stay consistent)
+    conn.execute(
+        "INSERT INTO exercise_logs (exercise_id, reps, xp_earned, logged_at) VALUES (?, ?, ?, ?)",
+        params![exercise_id, reps, xp_earned, logged_at],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Update exercise XP and level
+    conn.execute(
+        "UPDATE exercises SET total_xp = ?, current_level = ? WHERE id = ?",
+        params![new_xp, new_level, exercise_id],
+    )
+    .map_err(|e| e.to_string())?;
+    record_level_ups(&conn, exercise_id, old_level, new_level, None).map_err(|e| e.to_string())?;
+
+    // Update streak
+    let last_date: Option<String> = conn
+        .query_row(
+            "SELECT last_exercise_date FROM user_stats WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(None);
+
+    let (current_streak, longest_streak): (i32, i32) = conn
+        .query_row(
+            "SELECT current_streak, longest_streak FROM user_stats WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap_or((0, 0));
+
+    let new_streak = match &last_date {
+        Some(date) => {
+            if date == &today {
+                current_streak
+            } else {
+                let yesterday = (today_date - chrono::Duration::days(1))
+                    .format("%Y-%m-%d")
+                    .to_string();
+                if date == &yesterday {
+                    current_streak + 1
+                } else {
+                    1
+                }
+            }
+        }
+        None => 1,
+    };
+    let new_longest = std::cmp::max(new_streak, longest_streak);
+    let comeback = is_comeback(last_date.as_deref(), today_date);
+
+    conn.execute(
+        "UPDATE user_stats SET current_streak = ?, longest_streak = ?, last_exercise_date = ? WHERE id = 1",
+        params![new_streak, new_longest, today],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Calculate total level for achievements
+    let total_level: i32 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(current_level), 0) FROM exercises",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    // Check achievements
+    let unlocked_before = unlocked_achievement_count(&conn);
+    check_achievements(&conn, new_level, new_streak, total_level, now)?;
+    refresh_challenges(&conn, &today_string(&conn))?;
+    play_log_sound(&conn, leveled_up, unlocked_before);
+
+    // Release the database before touching the tray menu (it runs on the main thread)
+    let exercise = get_exercise_by_id(&conn, exercise_id);
+    drop(conn);
+    if let Ok(exercise) = exercise {
+        last_log.record(LastLog {
+            exercise_id,
+            exercise_name: exercise.name,
+            reps,
+        });
+    }
+
+    if comeback {
+        use tauri_plugin_notification::NotificationExt;
+        let _ = app
+            .notification()
+            .builder()
+            .title("GeekFit")
+            .body(COMEBACK_MESSAGE)
+            .show();
+    }
+
+    Ok(LogExerciseResult {
+        xp_earned,
+        new_exercise_level: new_level,
+        leveled_up,
+        comeback,
+        combo_awarded: combo_bonus > 0,
+        xp_multiplier,
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WeightedLogResult {
+    #[serde(flatten)]
+    pub result: LogExerciseResult,
+    pub new_weight_pr: bool, // Heaviest set yet for this exercise
+}
+
+#[tauri::command]
+fn log_exercise_weighted(
+    state: State<DbState>,
+    last_log: State<LastLogState>,
+    exercise_id: i64,
+    reps: i32,
+    weight: Option<f64>,
+) -> Result<WeightedLogResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let now = local_timestamp(&conn);
+    let result = log_weighted_set(&conn, exercise_id, reps, weight, &now)?;
+
+    let exercise = get_exercise_by_id(&conn, exercise_id);
+    drop(conn);
+    if let Ok(exercise) = exercise {
+        last_log.record(LastLog {
+            exercise_id,
+            exercise_name: exercise.name,
+            reps,
+        });
+    }
+    Ok(result)
+}
+
+fn log_weighted_set(
+    conn: &Connection,
+    exercise_id: i64,
+    reps: i32,
+    weight: Option<f64>,
+    timestamp: &str,
+) -> Result<WeightedLogResult, String> {
+    if weight.is_some_and(|w| !(w > 0.0 && w <= 1000.0)) {
+        return Err("Weight must be between 0 and 1000".to_string());
+    }
+    let previous_best = heaviest_weight(conn, exercise_id);
+    let result = log_set_at_time(conn, exercise_id, reps, weight, timestamp)?;
+    Ok(WeightedLogResult {
+        result,
+        new_weight_pr: weight.is_some_and(|w| previous_best.map_or(true, |best| w > best)),
+    })
+}
+
+fn heaviest_weight(conn: &Connection, exercise_id: i64) -> Option<f64> {
+    conn.query_row(
+        "SELECT MAX(weight) FROM exercise_logs WHERE exercise_id = ?",
+        params![exercise_id],
+        |row| row.get(0),
+    )
+    .unwrap_or(None)
+}
+
+#[tauri::command]
+fn log_exercise_at(
+    state: State<DbState>,
+    exercise_id: i64,
+    reps: i32,
+    timestamp: String,
+) -> Result<LogExerciseResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    log_exercise_at_time(&conn, exercise_id, reps, &timestamp)
+}
+
+/// Logs an exercise with an explicit `logged_at` (for backdating forgotten entries).
+/// The one log path shared by the app, the tray and the CLI.
+pub fn log_exercise_at_time(
+    conn: &Connection,
+    exercise_id: i64,
+    reps: i32,
+    timestamp: &str,
+) -> Result<LogExerciseResult, String> {
+    log_set_at_time(conn, exercise_id, reps, None, timestamp)
+}
+
+/// Like `log_exercise_at_time`, optionally recording the load of a weighted set.
+fn log_set_at_time(
+    conn: &Connection,
+    exercise_id: i64,
+    reps: i32,
+    weight: Option<f64>,
+    timestamp: &str,
+) -> Result<LogExerciseResult, String> {
+    let logged_at =
+        chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").map_err(|_| {
+            format!(
+                "Invalid timestamp '{}', expected format YYYY-MM-DD HH:MM:SS",
+                timestamp
+            )
+        })?;
+    if logged_at > local_now(conn) {
+        return Err("Cannot log an exercise in the future".to_string());
+    }
+    validate_reps(reps, min_reps(conn))?;
+
+    // Get exercise info
+    let (xp_per_rep, old_xp, old_level, difficulty): (i32, i64, i32, f64) = conn
+        .query_row(
+            "SELECT xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1), COALESCE(difficulty, 1.0) FROM exercises WHERE id = ?",
+            params![exercise_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let logged_date = streaks::effective_date(logged_at, streaks::day_rollover_hour(conn));
+    // Any XP decay up to the log's day becomes real now: the log builds on it
+    let (old_xp, old_level) = decayed_totals(conn, exercise_id, old_xp, old_level, logged_date);
+    let logged_day = logged_date.format("%Y-%m-%d").to_string();
+    let combo_bonus = combo_bonus_for(conn, exercise_id, &logged_day);
+    let xp_multiplier = xp_multiplier_at(conn, exercise_id, timestamp);
+    let xp_earned = weighted_xp(
+        compute_xp(xp_per_rep, reps, difficulty, xp_multiplier),
+        weight,
+        get_setting_value(conn, "scale_xp_by_weight", "true") == "true",
+    ) + combo_bonus;
+    let new_xp = old_xp + xp_earned as i64;
+    let new_level = level_from_xp_capped(new_xp, get_max_level(conn));
+    let leveled_up = new_level > old_level;
+
+    let logged_moment = logged_at;
+    let logged_at = logged_at.format("%Y-%m-%d %H:%M:%S").to_string();
+    conn.execute(
+        "INSERT INTO exercise_logs (exercise_id, reps, xp_earned, logged_at, weight) VALUES (?, ?, ?, ?, ?)",
+        params![exercise_id, reps, xp_earned, logged_at, weight],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE exercises SET total_xp = ?, current_level = ? WHERE id = ?",
+        params![new_xp, new_level, exercise_id],
+    )
+    .map_err(|e| e.to_string())?;
+    record_level_ups(conn, exercise_id, old_level, new_level, Some(&logged_at))
+        .map_err(|e| e.to_string())?;
+
+    let last_date: Option<String> = conn
+        .query_row(
+            "SELECT last_exercise_date FROM user_stats WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(None);
+    let comeback = is_comeback(last_date.as_deref(), logged_date);
+
+    // A past entry can bridge a gap, so rebuild the streak from the log table
+    let (current_streak, _) = recompute_streak(conn)?;
+
+    let total_level: i32 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(current_level), 0) FROM exercises",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    check_achievements(conn, new_level, current_streak, total_level, logged_moment)?;
+    refresh_challenges(conn, &today_string(conn))?;
+
+    Ok(LogExerciseResult {
+        xp_earned,
+        new_exercise_level: new_level,
+        leveled_up,
+        comeback,
+        combo_awarded: combo_bonus > 0,
+        xp_multiplier,
+    })
+}
+
+fn unlocked_achievement_count(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT COUNT(*) FROM achievements WHERE unlocked_at IS NOT NULL",
+        [],
+        |row| row.get(0),
+    )
+    .unwrap_or(0)
+}
+
+/// Chimes for a level-up or a new achievement when `sound_enabled` is on.
+#[cfg(feature = "sound")]
+fn play_log_sound(conn: &Connection, leveled_up: bool, unlocked_before: i64) {
+    if get_setting_value(conn, "sound_enabled", "true") != "true" {
+        return;
+    }
+    let unlocked_achievement = unlocked_achievement_count(conn) > unlocked_before;
+    if let Some(event) = sound::event_for_log(leveled_up, unlocked_achievement) {
+        sound::play(event);
+    }
+}
+
+#[cfg(not(feature = "sound"))]
+fn play_log_sound(_conn: &Connection, _leveled_up: bool, _unlocked_before: i64) {}
+
+/// Unlocks whatever the log at `logged_at` earned. Time-of-day and per-day achievements
+/// are judged by the log's own time, so backdated entries don't borrow the current
+/// clock, and unlocks are stamped with it.
+fn check_achievements(
+    conn: &Connection,
+    exercise_level: i32,
+    streak: i32,
+    total_level: i32,
+    logged_at: chrono::NaiveDateTime,
 ) -> Result<(), String> {
-    let now = Instant::now();
-    match reminder_type.as_str() {
-        "eye_care" => *reminder_state.last_eye_care.lock().unwrap() = now,
-        "hydration" => *reminder_state.last_hydration.lock().unwrap() = now,
-        "posture" => *reminder_state.last_posture.lock().unwrap() = now,
-        "exercise" => *reminder_state.last_exercise.lock().unwrap() = now,
-        "all" => {
-            *reminder_state.last_eye_care.lock().unwrap() = now;
-            *reminder_state.last_hydration.lock().unwrap() = now;
-            *reminder_state.last_posture.lock().unwrap() = now;
-            *reminder_state.last_exercise.lock().unwrap() = now;
+    let today = logged_at.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    // First exercise achievement
+    let log_count: i32 = conn
+        .query_row("SELECT COUNT(*) FROM exercise_logs", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if log_count == 1 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'first_exercise' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Skill level achievements (any single exercise)
+    if exercise_level >= 10 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'skill_10' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    if exercise_level >= 25 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'skill_25' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    if exercise_level >= 50 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'skill_50' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Total level achievement
+    if total_level >= 100 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'total_100' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Streak achievements
+    if streak >= 7 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'week_streak' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    if streak >= 30 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'month_streak' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Variety achievement
+    let distinct_exercises: i32 = conn
+        .query_row(
+            "SELECT COUNT(DISTINCT exercise_id) FROM exercise_logs",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    if distinct_exercises >= 5 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'variety' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Century achievement (100 pushups in a day)
+    let today_date = streaks::effective_date(logged_at, streaks::day_rollover_hour(conn))
+        .format("%Y-%m-%d")
+        .to_string();
+    let pushups_today: i32 = conn
+        .query_row(
+            &format!(
+                "SELECT COALESCE(SUM(reps), 0) FROM exercise_logs el
+                 JOIN exercises e ON el.exercise_id = e.id
+                 WHERE e.name = 'Pushups' AND {} = ?",
+                log_day_sql(conn, "el.logged_at")
+            ),
+            params![today_date],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    if pushups_today >= 100 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'hundred_pushups' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Time-based achievements
+    let current_hour = logged_at.hour();
+    if current_hour < 7 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'early_bird' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    if current_hour >= 22 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'night_owl' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Total reps achievements
+    let total_reps: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(reps), 0) FROM exercise_logs",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    if total_reps >= 1000 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'thousand_reps' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    if total_reps >= 10000 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'ten_thousand_reps' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    // Nice achievement (level 69)
+    if exercise_level == 69 {
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'nice' AND unlocked_at IS NULL",
+            params![today],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+fn reevaluate_achievements(state: State<DbState>) -> Result<Vec<String>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    replay_achievements(&conn)
+}
+
+/// Re-derives unlocks from the full history, for repairing achievements after manual
+/// edits or an old app version. Unlocks the data no longer supports are cleared; ones
+/// it still supports keep their original date. Returns the unlocked keys.
+fn replay_achievements(conn: &Connection) -> Result<Vec<String>, String> {
+    let query = |sql: &str| -> Result<i64, String> {
+        conn.query_row(sql, [], |row| row.get(0))
+            .map_err(|e| e.to_string())
+    };
+
+    let mut unlocked: Vec<&str> = Vec::new();
+    for (key, metric, target) in ACHIEVEMENT_THRESHOLDS {
+        if historical_metric_value(conn, *metric)? >= *target {
+            unlocked.push(key);
+        }
+    }
+
+    let early_logs = query(
+        "SELECT COUNT(*) FROM exercise_logs WHERE CAST(strftime('%H', logged_at) AS INTEGER) < 7",
+    )?;
+    let late_logs = query(
+        "SELECT COUNT(*) FROM exercise_logs WHERE CAST(strftime('%H', logged_at) AS INTEGER) >= 22",
+    )?;
+    let reached_69 = query(
+        "SELECT (SELECT COUNT(*) FROM level_history WHERE level = 69)
+              + (SELECT COUNT(*) FROM exercises WHERE current_level = 69)",
+    )?;
+    for (key, earned) in [
+        ("early_bird", early_logs > 0),
+        ("night_owl", late_logs > 0),
+        ("nice", reached_69 > 0),
+    ] {
+        if earned {
+            unlocked.push(key);
+        }
+    }
+
+    let all_keys: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT key FROM achievements")
+            .map_err(|e| e.to_string())?;
+        let keys = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| e.to_string())?;
+        keys
+    };
+
+    let now = local_timestamp(conn);
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    for key in &all_keys {
+        if unlocked.contains(&key.as_str()) {
+            tx.execute(
+                "UPDATE achievements SET unlocked_at = COALESCE(unlocked_at, ?) WHERE key = ?",
+                params![now, key],
+            )
+        } else {
+            tx.execute(
+                "UPDATE achievements SET unlocked_at = NULL WHERE key = ?",
+                params![key],
+            )
+        }
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT key FROM achievements WHERE unlocked_at IS NOT NULL ORDER BY id")
+        .map_err(|e| e.to_string())?;
+    let keys = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(keys)
+}
+
+/// Best value a metric ever reached, for replay: the longest streak rather than the
+/// current one and the best pushup day rather than today's.
+fn historical_metric_value(conn: &Connection, metric: AchievementMetric) -> Result<i64, String> {
+    match metric {
+        AchievementMetric::CurrentStreak => {
+            let recorded: i64 = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(longest_streak), 0) FROM user_stats",
+                    [],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            Ok(longest_run(&active_dates(conn)?)
+                .map(|(start, end)| (end - start).num_days() + 1)
+                .unwrap_or(0)
+                .max(recorded))
+        }
+        AchievementMetric::PushupsToday => conn
+            .query_row(
+                &format!(
+                    "SELECT COALESCE(MAX(day_reps), 0) FROM (
+                         SELECT SUM(el.reps) AS day_reps FROM exercise_logs el
+                         JOIN exercises e ON el.exercise_id = e.id
+                         WHERE e.name = 'Pushups'
+                         GROUP BY {}
+                     )",
+                    log_day_sql(conn, "el.logged_at")
+                ),
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string()),
+        other => achievement_metric_value(conn, other),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum AchievementMetric {
+    TotalLogs,
+    MaxExerciseLevel,
+    TotalLevel,
+    CurrentStreak,
+    DistinctExercises,
+    PushupsToday,
+    TotalReps,
+}
+
+/// Threshold achievements as (key, metric, target), mirroring `check_achievements`.
+/// Time-of-day and exact-level ones (early_bird, night_owl, nice) have no progress.
+const ACHIEVEMENT_THRESHOLDS: &[(&str, AchievementMetric, i64)] = &[
+    ("first_exercise", AchievementMetric::TotalLogs, 1),
+    ("skill_10", AchievementMetric::MaxExerciseLevel, 10),
+    ("skill_25", AchievementMetric::MaxExerciseLevel, 25),
+    ("skill_50", AchievementMetric::MaxExerciseLevel, 50),
+    ("total_100", AchievementMetric::TotalLevel, 100),
+    ("week_streak", AchievementMetric::CurrentStreak, 7),
+    ("month_streak", AchievementMetric::CurrentStreak, 30),
+    ("variety", AchievementMetric::DistinctExercises, 5),
+    ("hundred_pushups", AchievementMetric::PushupsToday, 100),
+    ("thousand_reps", AchievementMetric::TotalReps, 1000),
+    ("ten_thousand_reps", AchievementMetric::TotalReps, 10000),
+];
+
+fn achievement_metric_value(conn: &Connection, metric: AchievementMetric) -> Result<i64, String> {
+    let query = |sql: &str| -> Result<i64, String> {
+        conn.query_row(sql, [], |row| row.get(0))
+            .map_err(|e| e.to_string())
+    };
+
+    match metric {
+        AchievementMetric::TotalLogs => query("SELECT COUNT(*) FROM exercise_logs"),
+        AchievementMetric::MaxExerciseLevel => {
+            query("SELECT COALESCE(MAX(current_level), 0) FROM exercises")
+        }
+        AchievementMetric::TotalLevel => {
+            query("SELECT COALESCE(SUM(current_level), 0) FROM exercises")
+        }
+        AchievementMetric::CurrentStreak => Ok(active_streak(conn) as i64),
+        AchievementMetric::DistinctExercises => {
+            query("SELECT COUNT(DISTINCT exercise_id) FROM exercise_logs")
+        }
+        AchievementMetric::PushupsToday => conn
+            .query_row(
+                &format!(
+                    "SELECT COALESCE(SUM(reps), 0) FROM exercise_logs el
+                     JOIN exercises e ON el.exercise_id = e.id
+                     WHERE e.name = 'Pushups' AND {} = ?",
+                    log_day_sql(conn, "el.logged_at")
+                ),
+                params![today_string(conn)],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string()),
+        AchievementMetric::TotalReps => query("SELECT COALESCE(SUM(reps), 0) FROM exercise_logs"),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NextAchievement {
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub current: i64,
+    pub target: i64,
+    pub progress: f64, // 0.0-1.0
+}
+
+/// The locked threshold achievement closest to unlocking (earlier entries win ties).
+fn next_achievement(conn: &Connection) -> Result<Option<NextAchievement>, String> {
+    let mut best: Option<NextAchievement> = None;
+
+    for (key, metric, target) in ACHIEVEMENT_THRESHOLDS {
+        let locked: Option<(String, Option<String>)> = conn
+            .query_row(
+                "SELECT name, description FROM achievements WHERE key = ? AND unlocked_at IS NULL",
+                params![key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+        let Some((name, description)) = locked else {
+            continue;
+        };
+
+        let current = achievement_metric_value(conn, *metric)?.min(*target);
+        let progress = current as f64 / *target as f64;
+        if best.as_ref().map_or(true, |b| progress > b.progress) {
+            best = Some(NextAchievement {
+                key: key.to_string(),
+                name,
+                description,
+                current,
+                target: *target,
+                progress,
+            });
+        }
+    }
+
+    Ok(best)
+}
+
+#[tauri::command]
+fn get_next_achievement(state: State<DbState>) -> Result<Option<NextAchievement>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    next_achievement(&conn)
+}
+
+#[tauri::command]
+fn get_stats(state: State<DbState>) -> Result<UserStats, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    user_stats(&conn)
+}
+
+fn user_stats(conn: &Connection) -> Result<UserStats, String> {
+    // Totals from the exercise list, so any XP decay matches what the exercises show
+    let exercises = list_exercises(conn)?;
+    let total_xp = exercises.iter().map(|e| e.total_xp).sum();
+    let total_level = exercises.iter().map(|e| e.current_level).sum();
+    let exercise_count = exercises.len() as i32;
+
+    // Get streak info
+    let (current_streak, longest_streak, last_exercise_date): (i32, i32, Option<String>) = conn
+        .query_row(
+            "SELECT current_streak, longest_streak, last_exercise_date FROM user_stats WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap_or((0, 0, None));
+
+    Ok(UserStats {
+        total_xp,
+        total_level,
+        current_streak,
+        longest_streak,
+        last_exercise_date,
+        exercise_count,
+        goal_streak: goal_streak(conn)?,
+        consistency_score: consistency_score_for(conn, effective_today(conn))?,
+    })
+}
+
+/// Trailing window for the consistency score
+const CONSISTENCY_WINDOW_DAYS: i64 = 28;
+
+#[tauri::command]
+fn get_consistency_score(state: State<DbState>) -> Result<i32, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    consistency_score_for(&conn, effective_today(&conn))
+}
+
+fn consistency_score_for(conn: &Connection, today: chrono::NaiveDate) -> Result<i32, String> {
+    let window_start = today - chrono::Duration::days(CONSISTENCY_WINDOW_DAYS - 1);
+    let dates: Vec<chrono::NaiveDate> = active_dates(conn)?
+        .into_iter()
+        .filter(|date| *date >= window_start && *date <= today)
+        .collect();
+    Ok(streaks::consistency_score(&dates, CONSISTENCY_WINDOW_DAYS))
+}
+
+/// Consecutive days (ending today or yesterday) whose XP met the daily goal in effect
+/// that day. Stricter than the plain streak, which any log keeps alive.
+fn goal_streak(conn: &Connection) -> Result<i32, String> {
+    let mut stmt = conn
+        .prepare("SELECT effective_date, goal_xp FROM daily_goal_history ORDER BY effective_date")
+        .map_err(|e| e.to_string())?;
+    let history: Vec<(chrono::NaiveDate, i64)> = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .filter_map(|(date, goal)| {
+            chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .ok()
+                .map(|date| (date, goal))
+        })
+        .collect();
+    let current_goal = get_daily_goal_xp(conn);
+    let goal_on = |day: chrono::NaiveDate| {
+        history
+            .iter()
+            .rev()
+            .find(|(date, _)| *date <= day)
+            .map(|(_, goal)| *goal)
+            .unwrap_or(current_goal)
+    };
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}, SUM(xp_earned) FROM exercise_logs GROUP BY 1 ORDER BY 1",
+            log_day_sql(conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    let met_dates: Vec<chrono::NaiveDate> = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .filter_map(|(date, xp)| {
+            chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .ok()
+                .filter(|day| xp >= goal_on(*day))
+        })
+        .collect();
+
+    Ok(streaks::compute_streaks(&met_dates, effective_today(conn)).0)
+}
+
+#[tauri::command]
+fn get_achievements(state: State<DbState>) -> Result<Vec<Achievement>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    achievements_with_progress(&conn)
+}
+
+/// Every achievement, with live progress toward the locked threshold ones.
+fn achievements_with_progress(conn: &Connection) -> Result<Vec<Achievement>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, key, name, description, icon, unlocked_at FROM achievements ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let achievements = stmt
+        .query_map([], |row| {
+            Ok(Achievement {
+                id: row.get(0)?,
+                key: row.get(1)?,
+                name: row.get(2)?,
+                description: row.get(3)?,
+                icon: row.get(4)?,
+                unlocked_at: row.get(5)?,
+                progress: None,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    achievements
+        .into_iter()
+        .map(|mut achievement| {
+            let threshold = ACHIEVEMENT_THRESHOLDS
+                .iter()
+                .find(|(key, _, _)| *key == achievement.key);
+            if let (None, Some((_, metric, target))) = (&achievement.unlocked_at, threshold) {
+                let current = achievement_metric_value(conn, *metric)?.min(*target);
+                achievement.progress = Some((current, *target));
+            }
+            Ok(achievement)
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn get_exercise_history(state: State<DbState>, days: i32) -> Result<Vec<ExerciseLog>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, exercise_id, reps, xp_earned, logged_at, weight FROM exercise_logs
+             WHERE logged_at >= datetime(?, ? || ' days') ORDER BY logged_at DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let days_param = format!("-{}", days);
+    let logs = stmt
+        .query_map([local_timestamp(&conn), days_param], |row| {
+            Ok(ExerciseLog {
+                id: row.get(0)?,
+                exercise_id: row.get(1)?,
+                reps: row.get(2)?,
+                xp_earned: row.get(3)?,
+                logged_at: row.get(4)?,
+                weight: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(logs)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActivityData {
+    pub date: String,
+    pub count: i32,
+    pub xp: i32,
+}
+
+#[tauri::command]
+fn get_activity_data(state: State<DbState>, days: i32) -> Result<Vec<ActivityData>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} as date, COUNT(*) as count, SUM(xp_earned) as xp
+             FROM exercise_logs
+             WHERE logged_at >= datetime(?, ? || ' days')
+             GROUP BY date
+             ORDER BY date",
+            log_day_sql(&conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let days_param = format!("-{}", days);
+    let activity = stmt
+        .query_map([local_timestamp(&conn), days_param], |row| {
+            Ok(ActivityData {
+                date: row.get(0)?,
+                count: row.get(1)?,
+                xp: row.get(2)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(activity)
+}
+
+/// One entry per day of the month (zero-filled), for paging the history by month.
+fn month_summary(conn: &Connection, year: i32, month: u32) -> Result<Vec<ActivityData>, String> {
+    let first = chrono::NaiveDate::from_ymd_opt(year, month, 1)
+        .ok_or_else(|| format!("Invalid month {}-{}", year, month))?;
+    let next_month = if month == 12 {
+        chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        chrono::NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }
+    .ok_or_else(|| format!("Invalid month {}-{}", year, month))?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {day} AS date, COUNT(*), COALESCE(SUM(xp_earned), 0)
+             FROM exercise_logs
+             WHERE {day} >= ? AND {day} < ?
+             GROUP BY date",
+            day = log_day_sql(conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    let logged: std::collections::HashMap<String, (i32, i32)> = stmt
+        .query_map(
+            params![
+                first.format("%Y-%m-%d").to_string(),
+                next_month.format("%Y-%m-%d").to_string()
+            ],
+            |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))),
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(first
+        .iter_days()
+        .take_while(|day| *day < next_month)
+        .map(|day| {
+            let date = day.format("%Y-%m-%d").to_string();
+            let (count, xp) = logged.get(&date).copied().unwrap_or((0, 0));
+            ActivityData { date, count, xp }
+        })
+        .collect())
+}
+
+#[tauri::command]
+fn get_calendar_summary(
+    state: State<DbState>,
+    year: i32,
+    month: u32,
+) -> Result<Vec<ActivityData>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    month_summary(&conn, year, month)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeriodStats {
+    pub start_date: String,
+    pub end_date: String,
+    pub xp: i64,
+    pub reps: i64,
+    pub sessions: i64, // Individual logs
+    pub active_days: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeriodComparison {
+    pub first: PeriodStats,
+    pub second: PeriodStats,
+    pub xp_delta: i64, // second - first
+    pub reps_delta: i64,
+    pub sessions_delta: i64,
+    pub active_days_delta: i64,
+    pub overlap_days: i64, // Days counted in both periods
+}
+
+#[tauri::command]
+fn get_comparison(
+    state: State<DbState>,
+    first_start: String,
+    first_end: String,
+    second_start: String,
+    second_end: String,
+) -> Result<PeriodComparison, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    compare_periods(
+        &conn,
+        (&first_start, &first_end),
+        (&second_start, &second_end),
+    )
+}
+
+fn parse_period(start: &str, end: &str) -> Result<(chrono::NaiveDate, chrono::NaiveDate), String> {
+    let parse = |date: &str| {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{}', expected format YYYY-MM-DD", date))
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    if start > end {
+        return Err(format!(
+            "Period starts after it ends ({} to {})",
+            start, end
+        ));
+    }
+    Ok((start, end))
+}
+
+fn period_stats(
+    conn: &Connection,
+    start: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+) -> Result<PeriodStats, String> {
+    let start_date = start.format("%Y-%m-%d").to_string();
+    let end_date = end.format("%Y-%m-%d").to_string();
+    let (xp, reps, sessions, active_days): (i64, i64, i64, i64) = conn
+        .query_row(
+            &format!(
+                "SELECT COALESCE(SUM(xp_earned), 0), COALESCE(SUM(reps), 0), COUNT(*), COUNT(DISTINCT {day})
+                 FROM exercise_logs WHERE {day} BETWEEN ? AND ?",
+                day = log_day_sql(conn, "logged_at")
+            ),
+            params![start_date, end_date],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(PeriodStats {
+        start_date,
+        end_date,
+        xp,
+        reps,
+        sessions,
+        active_days,
+    })
+}
+
+/// Side-by-side totals for two inclusive date ranges. Overlapping ranges are allowed;
+/// each period counts its own days and `overlap_days` says how many they share.
+fn compare_periods(
+    conn: &Connection,
+    first: (&str, &str),
+    second: (&str, &str),
+) -> Result<PeriodComparison, String> {
+    let (first_start, first_end) = parse_period(first.0, first.1)?;
+    let (second_start, second_end) = parse_period(second.0, second.1)?;
+
+    let first = period_stats(conn, first_start, first_end)?;
+    let second = period_stats(conn, second_start, second_end)?;
+    let overlap_days = (std::cmp::min(first_end, second_end)
+        - std::cmp::max(first_start, second_start))
+    .num_days()
+        + 1;
+
+    Ok(PeriodComparison {
+        xp_delta: second.xp - first.xp,
+        reps_delta: second.reps - first.reps,
+        sessions_delta: second.sessions - first.sessions,
+        active_days_delta: second.active_days - first.active_days,
+        overlap_days: overlap_days.max(0),
+        first,
+        second,
+    })
+}
+
+/// Log counts per hour of day (index 0 = midnight), zero-filled for quiet hours.
+fn exercise_frequency_by_hour(conn: &Connection) -> Result<Vec<i32>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT CAST(strftime('%H', logged_at) AS INTEGER) AS hour, COUNT(*)
+             FROM exercise_logs
+             GROUP BY hour",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut buckets = vec![0; 24];
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, i32>(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+    for row in rows {
+        let (hour, count) = row.map_err(|e| e.to_string())?;
+        if let Some(hour) = hour.filter(|h| (0..24).contains(h)) {
+            buckets[hour as usize] = count;
+        }
+    }
+
+    Ok(buckets)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub version: String,
+    pub db_health: DbHealth,
+    pub data_dir: String,
+    pub data_dir_fallback_from: Option<String>, // Set when the usual data directory was read-only
+}
+
+#[tauri::command]
+fn get_version_info(
+    health: State<DbHealthState>,
+    data_dir: State<DataDirState>,
+) -> Result<VersionInfo, String> {
+    let db_health = health.0.lock().map_err(|e| e.to_string())?.clone();
+    Ok(VersionInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        db_health,
+        data_dir: data_dir.path.display().to_string(),
+        data_dir_fallback_from: data_dir
+            .fallback_from
+            .as_ref()
+            .map(|dir| dir.display().to_string()),
+    })
+}
+
+#[tauri::command]
+fn get_exercise_frequency_by_hour(state: State<DbState>) -> Result<Vec<i32>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    exercise_frequency_by_hour(&conn)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WeekdayAverage {
+    pub weekday: String,  // "Mon" through "Sun"
+    pub average_xp: f64,  // Mean XP over the days of this weekday with any activity
+    pub active_days: i64, // How many such days there were
+}
+
+#[tauri::command]
+fn get_weekday_averages(state: State<DbState>) -> Result<Vec<WeekdayAverage>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    weekday_averages(&conn)
+}
+
+/// Average daily XP per weekday over all history, Monday first. Weekdays without
+/// activity report 0 rather than being left out.
+fn weekday_averages(conn: &Connection) -> Result<Vec<WeekdayAverage>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT CAST(strftime('%w', day) AS INTEGER) AS weekday, AVG(xp), COUNT(*)
+             FROM (
+                 SELECT {} AS day, SUM(xp_earned) AS xp
+                 FROM exercise_logs GROUP BY day
+             )
+             WHERE day IS NOT NULL
+             GROUP BY weekday",
+            log_day_sql(conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    // strftime('%w') counts from Sunday = 0
+    let by_weekday: std::collections::HashMap<u32, (f64, i64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut weekday = chrono::Weekday::Mon;
+    let mut averages = Vec::with_capacity(7);
+    for _ in 0..7 {
+        let (average_xp, active_days) = by_weekday
+            .get(&weekday.num_days_from_sunday())
+            .copied()
+            .unwrap_or((0.0, 0));
+        averages.push(WeekdayAverage {
+            weekday: weekday.to_string(),
+            average_xp,
+            active_days,
+        });
+        weekday = weekday.succ();
+    }
+    Ok(averages)
+}
+
+#[tauri::command]
+fn get_settings(app: AppHandle, state: State<DbState>) -> Result<Settings, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+
+    let get_setting = |key: &str, default: &str| -> String {
+        conn.query_row(
+            "SELECT value FROM settings WHERE key = ?",
+            params![key],
+            |row| row.get(0),
+        )
+        .unwrap_or_else(|_| default.to_string())
+    };
+
+    let theme_mode_str = get_setting("theme_mode", "dark");
+    Ok(Settings {
+        reminder_enabled: get_setting("reminder_enabled", "true") == "true",
+        reminder_interval_minutes: get_setting("reminder_interval_minutes", "120")
+            .parse()
+            .unwrap_or(120),
+        sound_enabled: get_setting("sound_enabled", "true") == "true",
+        daily_goal_xp: get_setting("daily_goal_xp", "500").parse().unwrap_or(500),
+        theme_mode: Some(theme_mode_str),
+        launch_on_startup: launch_on_startup(&conn, &app),
+    })
+}
+
+fn get_setting_value(conn: &Connection, key: &str, default: &str) -> String {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?",
+        params![key],
+        |row| row.get(0),
+    )
+    .unwrap_or_else(|_| default.to_string())
+}
+
+/// Settings stored as "true" / "false"
+const BOOLEAN_SETTINGS: &[&str] = &[
+    "reminder_enabled",
+    "sound_enabled",
+    "scale_xp_by_weight",
+    "xp_decay_enabled",
+    "xp_decay_allow_delevel",
+    "wellness_eye_care_enabled",
+    "wellness_hydration_enabled",
+    "wellness_posture_enabled",
+    "wellness_pause_during_fullscreen",
+    "streak_warning_enabled",
+    "daily_summary_enabled",
+];
+
+/// Integer settings with their inclusive bounds
+const INTEGER_SETTINGS: &[(&str, i64, i64)] = &[
+    ("reminder_interval_minutes", 1, 1440),
+    ("wellness_eye_care_interval", 1, 1440),
+    ("wellness_hydration_interval", 1, 1440),
+    ("wellness_posture_interval", 1, 1440),
+    ("daily_goal_xp", 1, 1_000_000),
+    (
+        "max_level",
+        levels::DEFAULT_MAX_LEVEL as i64,
+        levels::MAX_LEVEL_LIMIT as i64,
+    ),
+    ("min_reps", 1, levels::MAX_REPS as i64),
+    ("xp_decay_grace_days", 0, 365),
+    ("combo_bonus", 0, 10_000),
+    ("day_rollover_hour", 0, 23),
+    ("streak_warn_hour", 0, 23),
+    ("daily_summary_hour", 0, 23),
+    (
+        "reminder_escalation_cap",
+        0,
+        ESCALATION_TITLES.len() as i64 - 1,
+    ),
+    ("local_api_port", 1, u16::MAX as i64),
+];
+
+/// Theme ids the frontend knows (`ThemeId` in src/themes/index.ts)
+const THEME_IDS: &[&str] = &[
+    "geekfit-dark",
+    "geekfit-light",
+    "dracula",
+    "nord",
+    "monokai",
+    "solarized-dark",
+    "solarized-light",
+    "github-dark",
+    "one-dark",
+    "catppuccin",
+];
+
+/// Rejects values the readers would otherwise silently replace with their defaults.
+/// Keys without a known type (templates, names) are accepted as-is.
+fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    if BOOLEAN_SETTINGS.contains(&key) {
+        if value != "true" && value != "false" {
+            return Err(format!(
+                "{} must be 'true' or 'false', got '{}'",
+                key, value
+            ));
+        }
+        return Ok(());
+    }
+    if let Some((_, min, max)) = INTEGER_SETTINGS.iter().find(|(k, _, _)| *k == key) {
+        // An empty summary hour falls back to the day rollover
+        if key == "daily_summary_hour" && value.is_empty() {
+            return Ok(());
+        }
+        return match value.trim().parse::<i64>() {
+            Ok(n) if (*min..=*max).contains(&n) => Ok(()),
+            _ => Err(format!(
+                "{} must be a whole number between {} and {}, got '{}'",
+                key, min, max, value
+            )),
+        };
+    }
+    match key {
+        "xp_decay_percent_per_day" => match value.trim().parse::<f64>() {
+            Ok(p) if (0.0..=100.0).contains(&p) => Ok(()),
+            _ => Err(format!(
+                "xp_decay_percent_per_day must be between 0 and 100, got '{}'",
+                value
+            )),
+        },
+        // An empty value clears the home timezone and falls back to the system zone
+        "timezone" if !value.is_empty() && value.parse::<chrono_tz::Tz>().is_err() => {
+            Err(format!("Unknown timezone '{}'", value))
+        }
+        "rest_days" => {
+            for day in value.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+                if day.parse::<chrono::Weekday>().is_err() {
+                    return Err(format!("Unknown rest day '{}'", day));
+                }
+            }
+            Ok(())
+        }
+        "theme_mode" if !THEME_IDS.contains(&value) => Err(format!("Unknown theme '{}'", value)),
+        "quick_log_presets" => {
+            let presets: std::collections::HashMap<String, Vec<i32>> = serde_json::from_str(value)
+                .map_err(|e| {
+                    format!(
+                        "quick_log_presets must map exercise ids to rep counts: {}",
+                        e
+                    )
+                })?;
+            for (id, reps) in &presets {
+                if id.parse::<i64>().is_err() {
+                    return Err(format!("Unknown exercise id '{}' in quick_log_presets", id));
+                }
+                for r in reps {
+                    validate_reps(*r, 1)?;
+                }
+            }
+            Ok(())
+        }
+        "reminder_weekday_intervals" => {
+            let intervals: std::collections::HashMap<String, u64> = serde_json::from_str(value)
+                .map_err(|e| {
+                    format!(
+                        "reminder_weekday_intervals must map weekdays to minutes: {}",
+                        e
+                    )
+                })?;
+            for (day, minutes) in &intervals {
+                if day.parse::<chrono::Weekday>().is_err() {
+                    return Err(format!("Unknown weekday '{}'", day));
+                }
+                if *minutes > 1440 {
+                    return Err(format!(
+                        "Reminder interval for {} must be at most 1440 minutes, got {}",
+                        day, minutes
+                    ));
+                }
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[tauri::command]
+fn update_setting(
+    app: AppHandle,
+    state: State<DbState>,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    save_setting(&conn, &key, &value)?;
+    drop(conn);
+
+    // The tray's rep-count submenus are built from this setting
+    if key == "quick_log_presets" {
+        refresh_tray_menu(&app);
+    }
+    Ok(())
+}
+
+/// Validates and stores one setting, along with the bookkeeping some keys need.
+fn save_setting(conn: &Connection, key: &str, value: &str) -> Result<(), String> {
+    validate_setting(key, value)?;
+
+    // Keep past days judged by the goal they had when computing the goal streak
+    if key == "daily_goal_xp" {
+        if let Ok(new_goal) = value.parse::<i64>() {
+            let previous_goal = get_daily_goal_xp(conn);
+            if new_goal != previous_goal {
+                record_daily_goal_change(
+                    conn,
+                    previous_goal,
+                    new_goal,
+                    &effective_today(conn).format("%Y-%m-%d").to_string(),
+                )?;
+            }
+        }
+    }
+
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+        params![key, value],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // A new level cap changes which level each exercise's XP maps to
+    if key == "max_level" {
+        recalculate_levels(conn)?;
+    }
+    Ok(())
+}
+
+/// Every stored setting, for keys `get_settings` doesn't type. Keys that were never
+/// written are absent, so callers supply their own defaults.
+#[tauri::command]
+fn get_all_settings(
+    state: State<DbState>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    all_settings(&conn)
+}
+
+fn all_settings(conn: &Connection) -> Result<std::collections::HashMap<String, String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM settings WHERE value IS NOT NULL")
+        .map_err(|e| e.to_string())?;
+    let settings = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+#[tauri::command]
+fn get_wellness_settings(
+    state: State<DbState>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut settings = std::collections::HashMap::new();
+
+    // Define wellness settings with their defaults
+    let wellness_keys = [
+        ("eye_care_enabled", "true"),
+        ("eye_care_interval", "20"),
+        ("hydration_enabled", "true"),
+        ("hydration_interval", "60"),
+        ("hydration_goal", "8"),
+        ("posture_enabled", "true"),
+        ("posture_interval", "45"),
+        ("focus_mode_enabled", "true"),
+        ("focus_mode_threshold", "90"),
+        ("pause_during_fullscreen", "false"),
+    ];
+
+    for (key, default) in wellness_keys {
+        let value: String = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?",
+                params![format!("wellness_{}", key)],
+                |row| row.get(0),
+            )
+            .unwrap_or_else(|_| default.to_string());
+        settings.insert(key.to_string(), value);
+    }
+
+    Ok(settings)
+}
+
+#[tauri::command]
+fn reset_reminder_timer(
+    reminder_state: State<ReminderState>,
+    reminder_type: String,
+) -> Result<(), String> {
+    let now = Instant::now();
+    match reminder_type.as_str() {
+        "eye_care" => *reminder_state.last_eye_care.lock().unwrap() = now,
+        "hydration" => *reminder_state.last_hydration.lock().unwrap() = now,
+        "posture" => *reminder_state.last_posture.lock().unwrap() = now,
+        "exercise" => *reminder_state.last_exercise.lock().unwrap() = now,
+        "all" => {
+            *reminder_state.last_eye_care.lock().unwrap() = now;
+            *reminder_state.last_hydration.lock().unwrap() = now;
+            *reminder_state.last_posture.lock().unwrap() = now;
+            *reminder_state.last_exercise.lock().unwrap() = now;
+        }
+        _ => return Err(format!("Unknown reminder type: {}", reminder_type)),
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReminderStatus {
+    pub enabled: bool,
+    pub paused_until: Option<String>,
+    pub minutes_remaining: i64,
+}
+
+#[tauri::command]
+fn pause_reminders_for(state: State<DbState>, minutes: i64) -> Result<ReminderStatus, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    pause_reminders(&conn, minutes, local_now(&conn))?;
+    Ok(reminder_status(&conn, local_now(&conn)))
+}
+
+#[tauri::command]
+fn get_reminder_status(state: State<DbState>) -> Result<ReminderStatus, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(reminder_status(&conn, local_now(&conn)))
+}
+
+/// Longest pause accepted, one week
+const MAX_PAUSE_MINUTES: i64 = 7 * 24 * 60;
+
+/// Stores `reminders_paused_until`; zero or negative minutes resume immediately.
+fn pause_reminders(
+    conn: &Connection,
+    minutes: i64,
+    now: chrono::NaiveDateTime,
+) -> Result<(), String> {
+    if minutes > MAX_PAUSE_MINUTES {
+        return Err(format!(
+            "Reminders can be paused for at most {} minutes (got {})",
+            MAX_PAUSE_MINUTES, minutes
+        ));
+    }
+    if minutes <= 0 {
+        conn.execute(
+            "DELETE FROM settings WHERE key = 'reminders_paused_until'",
+            [],
+        )
+        .map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+    let until = now + chrono::Duration::minutes(minutes);
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('reminders_paused_until', ?)",
+        params![until.format("%Y-%m-%d %H:%M:%S").to_string()],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn parse_paused_until(value: &str) -> Option<chrono::NaiveDateTime> {
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").ok()
+}
+
+fn reminders_paused(
+    paused_until: Option<chrono::NaiveDateTime>,
+    now: chrono::NaiveDateTime,
+) -> bool {
+    paused_until.is_some_and(|until| now < until)
+}
+
+fn reminder_status(conn: &Connection, now: chrono::NaiveDateTime) -> ReminderStatus {
+    let paused_until = parse_paused_until(&get_setting_value(conn, "reminders_paused_until", ""))
+        .filter(|until| reminders_paused(Some(*until), now));
+    ReminderStatus {
+        enabled: get_setting_value(conn, "reminder_enabled", "true") == "true",
+        paused_until: paused_until.map(|until| until.format("%Y-%m-%d %H:%M:%S").to_string()),
+        // Rounded up so the last partial minute still reads "1 more minute"
+        minutes_remaining: paused_until
+            .map(|until| ((until - now).num_seconds() + 59) / 60)
+            .unwrap_or(0),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DueReminders {
+    pub next_reminder_at: Option<String>, // None while exercise reminders are off or not yet scheduled
+    pub minutes_until_next: Option<i64>,
+    pub exercise: Option<Exercise>, // What the next reminder would suggest
+    pub paused: bool,
+    pub paused_until: Option<String>,
+}
+
+#[tauri::command]
+fn get_due_reminders(
+    state: State<DbState>,
+    reminder_state: State<ReminderState>,
+) -> Result<DueReminders, String> {
+    let next = *reminder_state
+        .next_exercise_at
+        .lock()
+        .map_err(|e| e.to_string())?;
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(due_reminders(&conn, next, local_now(&conn)))
+}
+
+/// When the exercise reminder fires, given the time since the last one and the interval.
+fn next_reminder_at(
+    now: chrono::NaiveDateTime,
+    since_last: Duration,
+    interval_minutes: u64,
+) -> chrono::NaiveDateTime {
+    let remaining = Duration::from_secs(interval_minutes * 60).saturating_sub(since_last);
+    now + chrono::Duration::seconds(remaining.as_secs() as i64)
+}
+
+/// Whole minutes until `next`, rounded up like the pause countdown and never negative.
+fn minutes_until(next: chrono::NaiveDateTime, now: chrono::NaiveDateTime) -> i64 {
+    ((next - now).num_seconds().max(0) + 59) / 60
+}
+
+fn due_reminders(
+    conn: &Connection,
+    next: Option<chrono::NaiveDateTime>,
+    now: chrono::NaiveDateTime,
+) -> DueReminders {
+    let status = reminder_status(conn, now);
+    let paused_until = status.paused_until.as_deref().and_then(parse_paused_until);
+    // A pause holds the reminder back until it ends
+    let next = if status.enabled {
+        next.map(|next| paused_until.map_or(next, |until| next.max(until)))
+    } else {
+        None
+    };
+    DueReminders {
+        next_reminder_at: next.map(|next| next.format("%Y-%m-%d %H:%M:%S").to_string()),
+        minutes_until_next: next.map(|next| minutes_until(next, now)),
+        exercise: suggest_neglected_exercise(conn, true),
+        paused: paused_until.is_some(),
+        paused_until: status.paused_until,
+    }
+}
+
+// ============ Balance Score ============
+
+/// Categories weighed by the balance score, matching the default exercise list
+const BALANCE_CATEGORIES: &[&str] = &["Upper Body", "Core", "Lower Body", "Cardio", "Stretches"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryLevel {
+    pub category: String,
+    pub total_level: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BalanceScore {
+    pub score: i32, // 0-100, higher when levels are spread evenly across categories
+    pub weakest_category: String,
+    pub categories: Vec<CategoryLevel>,
+}
+
+/// Scores the evenness of per-category levels using normalized Shannon entropy:
+/// 100 when every category has the same total level, 0 when one has them all.
+fn compute_balance_score(categories: Vec<CategoryLevel>) -> BalanceScore {
+    let total: i64 = categories.iter().map(|c| c.total_level.max(0)).sum();
+    let score = if total == 0 || categories.len() < 2 {
+        0
+    } else {
+        let entropy: f64 = categories
+            .iter()
+            .filter(|c| c.total_level > 0)
+            .map(|c| {
+                let p = c.total_level as f64 / total as f64;
+                -p * p.ln()
+            })
+            .sum();
+        (entropy / (categories.len() as f64).ln() * 100.0).round() as i32
+    };
+
+    // First category wins ties so the suggestion is stable
+    let weakest_category = categories
+        .iter()
+        .fold(None::<&CategoryLevel>, |weakest, c| match weakest {
+            Some(w) if w.total_level <= c.total_level => Some(w),
+            _ => Some(c),
+        })
+        .map(|c| c.category.clone())
+        .unwrap_or_default();
+
+    BalanceScore {
+        score,
+        weakest_category,
+        categories,
+    }
+}
+
+fn balance_score(conn: &Connection) -> Result<BalanceScore, String> {
+    let mut stmt = conn
+        .prepare("SELECT COALESCE(SUM(current_level), 0) FROM exercises WHERE category = ?")
+        .map_err(|e| e.to_string())?;
+
+    let categories = BALANCE_CATEGORIES
+        .iter()
+        .map(|category| {
+            let total_level: i64 = stmt
+                .query_row(params![category], |row| row.get(0))
+                .map_err(|e| e.to_string())?;
+            Ok(CategoryLevel {
+                category: category.to_string(),
+                total_level,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(compute_balance_score(categories))
+}
+
+#[tauri::command]
+fn get_balance_score(state: State<DbState>) -> Result<BalanceScore, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    balance_score(&conn)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryShare {
+    pub category: String,
+    pub xp: i64,
+    pub percentage: f64, // Share of the day's total XP, 0-100
+}
+
+/// XP logged on `date` (YYYY-MM-DD) per category, largest first. Empty when nothing was logged.
+fn category_breakdown(conn: &Connection, date: &str) -> Result<Vec<CategoryShare>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT COALESCE(e.category, 'Uncategorized') AS category, SUM(l.xp_earned) AS xp
+             FROM exercise_logs l
+             JOIN exercises e ON e.id = l.exercise_id
+             WHERE {} = ?
+             GROUP BY category
+             ORDER BY xp DESC, category ASC",
+            log_day_sql(conn, "l.logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let totals: Vec<(String, i64)> = stmt
+        .query_map(params![date], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let day_total: i64 = totals.iter().map(|(_, xp)| xp).sum();
+    if day_total <= 0 {
+        return Ok(Vec::new());
+    }
+
+    Ok(totals
+        .into_iter()
+        .map(|(category, xp)| CategoryShare {
+            category,
+            xp,
+            percentage: xp as f64 / day_total as f64 * 100.0,
+        })
+        .collect())
+}
+
+#[tauri::command]
+fn get_today_breakdown(state: State<DbState>) -> Result<Vec<CategoryShare>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    category_breakdown(&conn, &today_string(&conn))
+}
+
+// ============ Active Time ============
+
+/// Estimated seconds per rep: the hold time for timed exercises like "Plank (10 sec)",
+/// otherwise a per-category heuristic.
+fn default_seconds_per_rep(name: &str, category: Option<&str>) -> f64 {
+    let hold_seconds = name
+        .split_once('(')
+        .and_then(|(_, rest)| rest.strip_suffix("sec)"))
+        .and_then(|seconds| seconds.trim().parse::<f64>().ok());
+    if let Some(seconds) = hold_seconds {
+        return seconds;
+    }
+
+    match category {
+        Some("Upper Body") => 2.0,
+        Some("Core") | Some("Lower Body") => 2.5,
+        Some("Cardio") => 1.0,
+        Some("Stretches") => 5.0,
+        _ => 3.0,
+    }
+}
+
+/// True for `#RRGGBB` hex colors.
+fn is_valid_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[tauri::command]
+fn set_exercise_color(state: State<DbState>, id: i64, color: Option<String>) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    update_exercise_color(&conn, id, color.as_deref())
+}
+
+/// Sets the accent color; None clears it back to the theme default.
+fn update_exercise_color(conn: &Connection, id: i64, color: Option<&str>) -> Result<(), String> {
+    if let Some(color) = color {
+        if !is_valid_hex_color(color) {
+            return Err(format!(
+                "Invalid color '{}', expected format #RRGGBB",
+                color
+            ));
+        }
+    }
+    let updated = conn
+        .execute(
+            "UPDATE exercises SET color = ? WHERE id = ?",
+            params![color.map(|c| c.to_uppercase()), id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Exercise {} not found", id));
+    }
+    Ok(())
+}
+
+/// Opts an exercise in or out of reminder suggestions. It stays loggable either way.
+#[tauri::command]
+fn set_exercise_reminder(state: State<DbState>, id: i64, enabled: bool) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    update_exercise_reminder(&conn, id, enabled)
+}
+
+fn update_exercise_reminder(conn: &Connection, id: i64, enabled: bool) -> Result<(), String> {
+    let updated = conn
+        .execute(
+            "UPDATE exercises SET remind = ? WHERE id = ?",
+            params![enabled, id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Exercise {} not found", id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn set_seconds_per_rep(state: State<DbState>, id: i64, seconds: Option<f64>) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    if seconds.is_some_and(|s| !(s > 0.0 && s <= 600.0)) {
+        return Err("Seconds per rep must be between 0 and 600".to_string());
+    }
+    let updated = conn
+        .execute(
+            "UPDATE exercises SET seconds_per_rep = ? WHERE id = ?",
+            params![seconds, id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Exercise {} not found", id));
+    }
+    Ok(())
+}
+
+/// Estimated minutes spent exercising, optionally only counting logs on `date` (YYYY-MM-DD).
+fn active_minutes(conn: &Connection, date: Option<&str>) -> Result<f64, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT e.name, e.category, e.seconds_per_rep, COALESCE(SUM(l.reps), 0)
+             FROM exercise_logs l
+             JOIN exercises e ON e.id = l.exercise_id
+             WHERE ?1 IS NULL OR {} = ?1
+             GROUP BY e.id",
+            log_day_sql(conn, "l.logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![date], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<f64>>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut seconds = 0.0;
+    for row in rows {
+        let (name, category, seconds_per_rep, reps) = row.map_err(|e| e.to_string())?;
+        let per_rep =
+            seconds_per_rep.unwrap_or_else(|| default_seconds_per_rep(&name, category.as_deref()));
+        seconds += reps as f64 * per_rep;
+    }
+    Ok(seconds / 60.0)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActiveTime {
+    pub lifetime_minutes: f64,
+    pub today_minutes: f64,
+}
+
+#[tauri::command]
+fn get_total_active_time(state: State<DbState>) -> Result<ActiveTime, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(ActiveTime {
+        lifetime_minutes: active_minutes(&conn, None)?,
+        today_minutes: active_minutes(&conn, Some(&today_string(&conn)))?,
+    })
+}
+
+// ============ Share Card ============
+
+/// Renders the shareable summary card as PNG bytes, also writing it to `path` when given.
+#[tauri::command]
+fn export_share_card(state: State<DbState>, path: Option<String>) -> Result<Vec<u8>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let stats = share_card_stats(&conn, effective_today(&conn))?;
+    drop(conn);
+
+    let bytes = share_card::render(&stats)?;
+    if let Some(path) = path {
+        std::fs::write(&path, &bytes).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    Ok(bytes)
+}
+
+fn share_card_stats(
+    conn: &Connection,
+    today: chrono::NaiveDate,
+) -> Result<share_card::CardStats, String> {
+    let stats = user_stats(conn)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(current_level, 1), color FROM exercises
+             ORDER BY total_xp DESC, id LIMIT 3",
+        )
+        .map_err(|e| e.to_string())?;
+    let top_exercises = stmt
+        .query_map([], |row| {
+            Ok(share_card::CardExercise {
+                level: row.get(0)?,
+                color: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let start = today - chrono::Duration::days(share_card::HEATMAP_DAYS as i64 - 1);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {day}, SUM(xp_earned) FROM exercise_logs
+             WHERE {day} BETWEEN ? AND ? GROUP BY 1",
+            day = log_day_sql(conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    let xp_by_day: std::collections::HashMap<String, i64> = stmt
+        .query_map(
+            params![
+                start.format("%Y-%m-%d").to_string(),
+                today.format("%Y-%m-%d").to_string()
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+    let daily_xp = start
+        .iter_days()
+        .take(share_card::HEATMAP_DAYS)
+        .map(|day| {
+            xp_by_day
+                .get(&day.format("%Y-%m-%d").to_string())
+                .copied()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    Ok(share_card::CardStats {
+        total_level: stats.total_level,
+        current_streak: stats.current_streak,
+        max_level: get_max_level(conn),
+        top_exercises,
+        daily_xp,
+    })
+}
+
+// ============ Records ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreakRecord {
+    pub days: i32,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DayRecord {
+    pub date: String,
+    pub value: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BestSet {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub reps: i32,
+    pub logged_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeaviestSet {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub weight: f64,
+    pub reps: i32,
+    pub logged_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Records {
+    pub first_log_date: Option<String>,
+    pub longest_streak: Option<StreakRecord>,
+    pub best_sets: Vec<BestSet>, // Highest single-log reps per exercise
+    pub heaviest_sets: Vec<HeaviestSet>, // Highest weight per weighted exercise
+    pub most_xp_in_a_day: Option<DayRecord>,
+    pub most_exercises_in_a_day: Option<DayRecord>, // Distinct exercises
+}
+
+fn first_log_date(conn: &Connection) -> Result<Option<String>, String> {
+    conn.query_row(
+        &format!(
+            "SELECT MIN({}) FROM exercise_logs",
+            log_day_sql(conn, "logged_at")
+        ),
+        [],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn longest_streak_record(conn: &Connection) -> Result<Option<StreakRecord>, String> {
+    let dates = active_dates(conn)?;
+    Ok(longest_run(&dates).map(|(start, end)| StreakRecord {
+        days: (end - start).num_days() as i32 + 1,
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: end.format("%Y-%m-%d").to_string(),
+    }))
+}
+
+fn best_sets(conn: &Connection) -> Result<Vec<BestSet>, String> {
+    // With a lone MAX() aggregate, SQLite takes the bare columns from the maximal row
+    let mut stmt = conn
+        .prepare(
+            "SELECT e.id, e.name, MAX(l.reps), l.logged_at
+             FROM exercise_logs l
+             JOIN exercises e ON e.id = l.exercise_id
+             GROUP BY e.id
+             ORDER BY MAX(l.reps) DESC, e.name ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let sets = stmt
+        .query_map([], |row| {
+            Ok(BestSet {
+                exercise_id: row.get(0)?,
+                exercise_name: row.get(1)?,
+                reps: row.get(2)?,
+                logged_at: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(sets)
+}
+
+fn heaviest_sets(conn: &Connection) -> Result<Vec<HeaviestSet>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT e.id, e.name, MAX(l.weight), l.reps, l.logged_at
+             FROM exercise_logs l
+             JOIN exercises e ON e.id = l.exercise_id
+             WHERE l.weight IS NOT NULL
+             GROUP BY e.id
+             ORDER BY e.name ASC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let sets = stmt
+        .query_map([], |row| {
+            Ok(HeaviestSet {
+                exercise_id: row.get(0)?,
+                exercise_name: row.get(1)?,
+                weight: row.get(2)?,
+                reps: row.get(3)?,
+                logged_at: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(sets)
+}
+
+/// Best day by an aggregate over that day's logs (earliest day wins ties).
+fn best_day(conn: &Connection, aggregate: &str) -> Result<Option<DayRecord>, String> {
+    let result = conn.query_row(
+        &format!(
+            "SELECT {} AS day, {} AS value FROM exercise_logs
+             GROUP BY day ORDER BY value DESC, day ASC LIMIT 1",
+            log_day_sql(conn, "logged_at"),
+            aggregate
+        ),
+        [],
+        |row| {
+            Ok(DayRecord {
+                date: row.get(0)?,
+                value: row.get(1)?,
+            })
+        },
+    );
+
+    match result {
+        Ok(record) => Ok(Some(record)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn most_xp_in_a_day(conn: &Connection) -> Result<Option<DayRecord>, String> {
+    best_day(conn, "SUM(xp_earned)")
+}
+
+fn most_exercises_in_a_day(conn: &Connection) -> Result<Option<DayRecord>, String> {
+    best_day(conn, "COUNT(DISTINCT exercise_id)")
+}
+
+#[tauri::command]
+fn get_records(state: State<DbState>) -> Result<Records, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(Records {
+        first_log_date: first_log_date(&conn)?,
+        longest_streak: longest_streak_record(&conn)?,
+        best_sets: best_sets(&conn)?,
+        heaviest_sets: heaviest_sets(&conn)?,
+        most_xp_in_a_day: most_xp_in_a_day(&conn)?,
+        most_exercises_in_a_day: most_exercises_in_a_day(&conn)?,
+    })
+}
+
+/// Most days the hall of fame returns
+const MAX_TOP_DAYS: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DayExercise {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub reps: i64,
+    pub xp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TopDay {
+    pub date: String,
+    pub total_xp: i64,
+    pub exercises: Vec<DayExercise>, // Most XP first
+}
+
+#[tauri::command]
+fn get_heaviest_days(state: State<DbState>, limit: usize) -> Result<Vec<TopDay>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    heaviest_days(&conn, limit)
+}
+
+/// The `limit` days with the most XP, best first (earlier days win ties). Returns
+/// every active day when there are fewer than `limit`.
+fn heaviest_days(conn: &Connection, limit: usize) -> Result<Vec<TopDay>, String> {
+    let limit = limit.min(MAX_TOP_DAYS);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} AS day, SUM(xp_earned) AS total FROM exercise_logs
+             GROUP BY day ORDER BY total DESC, day ASC LIMIT ?",
+            log_day_sql(conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    let days: Vec<(String, i64)> = stmt
+        .query_map(params![limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut breakdown = conn
+        .prepare(&format!(
+            "SELECT el.exercise_id, e.name, SUM(el.reps), SUM(el.xp_earned) AS xp
+             FROM exercise_logs el
+             JOIN exercises e ON el.exercise_id = e.id
+             WHERE {} = ?
+             GROUP BY el.exercise_id ORDER BY xp DESC, e.name",
+            log_day_sql(conn, "el.logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    days.into_iter()
+        .map(|(date, total_xp)| {
+            let exercises = breakdown
+                .query_map(params![date], |row| {
+                    Ok(DayExercise {
+                        exercise_id: row.get(0)?,
+                        exercise_name: row.get(1)?,
+                        reps: row.get(2)?,
+                        xp: row.get(3)?,
+                    })
+                })
+                .map_err(|e| e.to_string())?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            Ok(TopDay {
+                date,
+                total_xp,
+                exercises,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreakRun {
+    pub start_date: String,
+    pub end_date: String,
+    pub days: i32,
+}
+
+#[tauri::command]
+fn get_streak_history(state: State<DbState>) -> Result<Vec<StreakRun>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    streak_history(&conn)
+}
+
+/// Every streak the user has had, oldest first (the last one may still be running).
+fn streak_history(conn: &Connection) -> Result<Vec<StreakRun>, String> {
+    let dates = active_dates(conn)?;
+    Ok(streak_runs(&dates)
+        .into_iter()
+        .map(|(start, end)| StreakRun {
+            start_date: start.format("%Y-%m-%d").to_string(),
+            end_date: end.format("%Y-%m-%d").to_string(),
+            days: (end - start).num_days() as i32 + 1,
+        })
+        .collect())
+}
+
+/// Overrides the stored streak for QA and manual corrections. A log for today builds
+/// on these values, but anything that recomputes from the log history (backdated logs,
+/// undo, deleting a log) replaces them.
+#[tauri::command]
+fn set_streak(
+    state: State<DbState>,
+    current: i32,
+    longest: i32,
+    last_date: Option<String>,
+) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    override_streak(&conn, current, longest, last_date.as_deref())
+}
+
+fn override_streak(
+    conn: &Connection,
+    current: i32,
+    longest: i32,
+    last_date: Option<&str>,
+) -> Result<(), String> {
+    if current < 0 || longest < 0 {
+        return Err("Streaks cannot be negative".to_string());
+    }
+    if current > longest {
+        return Err(format!(
+            "Current streak ({}) cannot exceed the longest streak ({})",
+            current, longest
+        ));
+    }
+    if let Some(date) = last_date {
+        let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{}', expected format YYYY-MM-DD", date))?;
+        if date > effective_today(conn) {
+            return Err("Last exercise date cannot be in the future".to_string());
+        }
+    } else if current > 0 {
+        return Err("A current streak needs a last exercise date".to_string());
+    }
+
+    conn.execute(
+        "UPDATE user_stats SET current_streak = ?, longest_streak = ?, last_exercise_date = ? WHERE id = 1",
+        params![current, longest, last_date],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ============ Undo History ============
+
+/// Consecutive undos allowed before something is redone
+const UNDO_DEPTH: usize = 20;
+
+/// A deleted log row and the level history it produced, kept to put both back exactly.
+#[derive(Debug, Clone, PartialEq)]
+struct UndoneLog {
+    log_id: i64,
+    exercise_id: i64,
+    reps: i32,
+    xp_earned: i32,
+    logged_at: String,
+    weight: Option<f64>,
+    level_history: Vec<(i64, i32, String)>, // (id, level, reached_at)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UndoResult {
+    pub log_id: i64,
+    pub exercise_id: i64,
+    pub reps: i32,
+    pub xp_earned: i32,
+    pub new_exercise_level: i32,
+}
+
+#[tauri::command]
+fn undo_last_log(state: State<DbState>, undo: State<UndoState>) -> Result<UndoResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut undone = undo.undone.lock().map_err(|e| e.to_string())?;
+    undo_log(&conn, &mut undone)
+}
+
+#[tauri::command]
+fn redo_last_undo(state: State<DbState>, undo: State<UndoState>) -> Result<UndoResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut undone = undo.undone.lock().map_err(|e| e.to_string())?;
+    redo_log(&conn, &mut undone)
+}
+
+fn undo_result(conn: &Connection, log: &UndoneLog) -> Result<UndoResult, String> {
+    Ok(UndoResult {
+        log_id: log.log_id,
+        exercise_id: log.exercise_id,
+        reps: log.reps,
+        xp_earned: log.xp_earned,
+        new_exercise_level: get_exercise_by_id(conn, log.exercise_id)?.current_level,
+    })
+}
+
+/// Deletes the newest log, takes its XP back off the exercise and pushes it onto `undone`.
+fn undo_log(conn: &Connection, undone: &mut Vec<UndoneLog>) -> Result<UndoResult, String> {
+    if undone.len() >= UNDO_DEPTH {
+        return Err(format!("Only the last {} logs can be undone", UNDO_DEPTH));
+    }
+    let newest = conn
+        .query_row(
+            "SELECT id, exercise_id, reps, xp_earned, logged_at, weight FROM exercise_logs
+             ORDER BY id DESC LIMIT 1",
+            [],
+            |row| {
+                Ok(UndoneLog {
+                    log_id: row.get(0)?,
+                    exercise_id: row.get(1)?,
+                    reps: row.get(2)?,
+                    xp_earned: row.get(3)?,
+                    logged_at: row.get(4)?,
+                    weight: row.get(5)?,
+                    level_history: Vec::new(),
+                })
+            },
+        )
+        .ok();
+    let Some(mut log) = newest else {
+        return Err("Nothing to undo".to_string());
+    };
+
+    let old_xp: i64 = conn
+        .query_row(
+            "SELECT COALESCE(total_xp, 0) FROM exercises WHERE id = ?",
+            params![log.exercise_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let new_xp = (old_xp - log.xp_earned as i64).max(0);
+    let new_level = level_from_xp_capped(new_xp, get_max_level(conn));
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "SELECT id, level, reached_at FROM level_history
+                 WHERE exercise_id = ? AND level > ? ORDER BY id",
+            )
+            .map_err(|e| e.to_string())?;
+        log.level_history = stmt
+            .query_map(params![log.exercise_id, new_level], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?;
+    }
+    tx.execute(
+        "DELETE FROM level_history WHERE exercise_id = ? AND level > ?",
+        params![log.exercise_id, new_level],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute(
+        "DELETE FROM exercise_logs WHERE id = ?",
+        params![log.log_id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE exercises SET total_xp = ?, current_level = ? WHERE id = ?",
+        params![new_xp, new_level, log.exercise_id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    recompute_streak(conn)?;
+    refresh_challenges(conn, &today_string(conn))?;
+
+    let result = undo_result(conn, &log)?;
+    undone.push(log);
+    Ok(result)
+}
+
+/// Puts the most recently undone log back under its original id, unless a newer log
+/// has been added since (which also drops the rest of the redo history).
+fn redo_log(conn: &Connection, undone: &mut Vec<UndoneLog>) -> Result<UndoResult, String> {
+    let Some(log) = undone.pop() else {
+        return Err("Nothing to redo".to_string());
+    };
+    let newest_id: Option<i64> = conn
+        .query_row("SELECT MAX(id) FROM exercise_logs", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if newest_id.is_some_and(|id| id > log.log_id) {
+        undone.clear();
+        return Err("A newer log was added since the undo".to_string());
+    }
+    if get_exercise_by_id(conn, log.exercise_id).is_err() {
+        undone.clear();
+        return Err("The exercise was deleted since the undo".to_string());
+    }
+
+    let old_xp: i64 = conn
+        .query_row(
+            "SELECT COALESCE(total_xp, 0) FROM exercises WHERE id = ?",
+            params![log.exercise_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let new_xp = old_xp + log.xp_earned as i64;
+    let new_level = level_from_xp_capped(new_xp, get_max_level(conn));
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO exercise_logs (id, exercise_id, reps, xp_earned, logged_at, weight) VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            log.log_id,
+            log.exercise_id,
+            log.reps,
+            log.xp_earned,
+            log.logged_at,
+            log.weight
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE exercises SET total_xp = ?, current_level = ? WHERE id = ?",
+        params![new_xp, new_level, log.exercise_id],
+    )
+    .map_err(|e| e.to_string())?;
+    for (id, level, reached_at) in &log.level_history {
+        tx.execute(
+            "INSERT INTO level_history (id, exercise_id, level, reached_at) VALUES (?, ?, ?, ?)",
+            params![id, log.exercise_id, level, reached_at],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    recompute_streak(conn)?;
+    refresh_challenges(conn, &today_string(conn))?;
+
+    undo_result(conn, &log)
+}
+
+// ============ XP Sources ============
+
+/// One logged set on the day timeline
+#[derive(Debug, Serialize, Deserialize)]
+pub struct XpSource {
+    pub log_id: i64,
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub reps: i32,
+    pub xp_earned: i32,
+    pub running_xp: i64, // XP earned so far that day, including this set
+    pub logged_at: String,
+}
+
+#[tauri::command]
+fn get_xp_sources(state: State<DbState>, date: String) -> Result<Vec<XpSource>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    xp_sources(&conn, &date)
+}
+
+/// Every set logged on `date` (YYYY-MM-DD), oldest first. Empty for days without activity.
+fn xp_sources(conn: &Connection, date: &str) -> Result<Vec<XpSource>, String> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}', expected format YYYY-MM-DD", date))?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT l.id, l.exercise_id, e.name, l.reps, l.xp_earned, l.logged_at
+             FROM exercise_logs l
+             JOIN exercises e ON e.id = l.exercise_id
+             WHERE {} = ?
+             ORDER BY l.logged_at, l.id",
+            log_day_sql(conn, "l.logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![date], |row| {
+            Ok(XpSource {
+                log_id: row.get(0)?,
+                exercise_id: row.get(1)?,
+                exercise_name: row.get(2)?,
+                reps: row.get(3)?,
+                xp_earned: row.get(4)?,
+                running_xp: 0,
+                logged_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut running_xp = 0;
+    let mut sources = Vec::new();
+    for row in rows {
+        let mut source = row.map_err(|e| e.to_string())?;
+        running_xp += source.xp_earned as i64;
+        source.running_xp = running_xp;
+        sources.push(source);
+    }
+    Ok(sources)
+}
+
+// ============ Exercise Trend ============
+
+/// Window for the trend's moving average, in days
+const TREND_WINDOW_DAYS: usize = 7;
+/// Daily change in the moving average, relative to its mean, that counts as a trend
+const TREND_SLOPE_THRESHOLD: f64 = 0.02;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub date: String,
+    pub reps: i64,
+    pub moving_average: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExerciseTrend {
+    pub exercise_id: i64,
+    pub points: Vec<TrendPoint>, // Oldest first, one per day including rest days
+    pub slope: f64,              // Change in the moving average per day
+    pub trend: String,           // "improving", "steady" or "declining"
+}
+
+/// Trailing moving average; the first points average over the days available so far.
+fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    (0..values.len())
+        .map(|i| {
+            let start = (i + 1).saturating_sub(window);
+            let slice = &values[start..=i];
+            slice.iter().sum::<f64>() / slice.len() as f64
+        })
+        .collect()
+}
+
+/// Least-squares slope of `values` against their index (0 for fewer than two points).
+fn linear_slope(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+    let (mut covariance, mut variance) = (0.0, 0.0);
+    for (i, y) in values.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        covariance += dx * (y - mean_y);
+        variance += dx * dx;
+    }
+    covariance / variance
+}
+
+/// Labels a slope relative to the average level, so 1 rep/day means more at 5 reps than at 100.
+fn trend_label(slope: f64, mean: f64) -> &'static str {
+    if mean <= 0.0 {
+        return "steady";
+    }
+    let relative = slope / mean;
+    if relative > TREND_SLOPE_THRESHOLD {
+        "improving"
+    } else if relative < -TREND_SLOPE_THRESHOLD {
+        "declining"
+    } else {
+        "steady"
+    }
+}
+
+#[tauri::command]
+fn get_exercise_trend(
+    state: State<DbState>,
+    exercise_id: i64,
+    days: i64,
+) -> Result<ExerciseTrend, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    exercise_trend(&conn, exercise_id, days, effective_today(&conn))
+}
+
+fn exercise_trend(
+    conn: &Connection,
+    exercise_id: i64,
+    days: i64,
+    today: chrono::NaiveDate,
+) -> Result<ExerciseTrend, String> {
+    if !(2..=365).contains(&days) {
+        return Err("Days must be between 2 and 365".to_string());
+    }
+    get_exercise_by_id(conn, exercise_id)?;
+
+    let start = today - chrono::Duration::days(days - 1);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {day} AS day, SUM(reps) FROM exercise_logs
+             WHERE exercise_id = ?1 AND {day} BETWEEN ?2 AND ?3
+             GROUP BY day",
+            day = log_day_sql(conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    let reps_by_day: std::collections::HashMap<String, i64> = stmt
+        .query_map(
+            params![
+                exercise_id,
+                start.format("%Y-%m-%d").to_string(),
+                today.format("%Y-%m-%d").to_string()
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // Rest days count as zero so sparse logging still yields a continuous series
+    let dates: Vec<String> = (0..days)
+        .map(|offset| {
+            (start + chrono::Duration::days(offset))
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .collect();
+    let reps: Vec<i64> = dates
+        .iter()
+        .map(|date| reps_by_day.get(date).copied().unwrap_or(0))
+        .collect();
+    let averages = moving_average(
+        &reps.iter().map(|r| *r as f64).collect::<Vec<_>>(),
+        TREND_WINDOW_DAYS,
+    );
+
+    let slope = linear_slope(&averages);
+    let mean = averages.iter().sum::<f64>() / averages.len() as f64;
+
+    Ok(ExerciseTrend {
+        exercise_id,
+        points: dates
+            .into_iter()
+            .zip(reps)
+            .zip(averages.iter())
+            .map(|((date, reps), average)| TrendPoint {
+                date,
+                reps,
+                moving_average: *average,
+            })
+            .collect(),
+        slope,
+        trend: trend_label(slope, mean).to_string(),
+    })
+}
+
+// ============ Exercise Distribution ============
+
+/// Series drawn individually in the stacked chart; smaller ones are summed into "Other"
+const MAX_DISTRIBUTION_SERIES: usize = 6;
+const OTHER_SERIES: &str = "Other";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DistributionDay {
+    pub date: String,
+    pub xp: std::collections::BTreeMap<String, i64>, // Every series, 0 when idle
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExerciseDistribution {
+    pub series: Vec<String>,        // Most XP first, "Other" last when present
+    pub days: Vec<DistributionDay>, // Oldest first, one per day including rest days
+}
+
+#[tauri::command]
+fn get_exercise_distribution(
+    state: State<DbState>,
+    days: i64,
+    by_category: bool,
+) -> Result<ExerciseDistribution, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    exercise_distribution(&conn, days, by_category, effective_today(&conn))
+}
+
+/// Daily XP per exercise (or per category) over the `days` ending `today`.
+fn exercise_distribution(
+    conn: &Connection,
+    days: i64,
+    by_category: bool,
+    today: chrono::NaiveDate,
+) -> Result<ExerciseDistribution, String> {
+    if !(1..=365).contains(&days) {
+        return Err("Days must be between 1 and 365".to_string());
+    }
+
+    let start = today - chrono::Duration::days(days - 1);
+    let series_column = if by_category {
+        "COALESCE(e.category, 'Uncategorized')"
+    } else {
+        "e.name"
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {day} AS day, {series} AS series, SUM(el.xp_earned)
+             FROM exercise_logs el
+             JOIN exercises e ON el.exercise_id = e.id
+             WHERE {day} BETWEEN ?1 AND ?2
+             GROUP BY day, series",
+            day = log_day_sql(conn, "el.logged_at"),
+            series = series_column
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<(String, String, i64)> = stmt
+        .query_map(
+            params![
+                start.format("%Y-%m-%d").to_string(),
+                today.format("%Y-%m-%d").to_string()
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    // Rank series by their total over the window; past the cap they share "Other"
+    let mut totals: std::collections::HashMap<&str, i64> = std::collections::HashMap::new();
+    for (_, series, xp) in &rows {
+        *totals.entry(series.as_str()).or_insert(0) += xp;
+    }
+    let mut ranked: Vec<(&str, i64)> = totals.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let mut series: Vec<String> = ranked.iter().map(|(name, _)| name.to_string()).collect();
+    if series.len() > MAX_DISTRIBUTION_SERIES {
+        series.truncate(MAX_DISTRIBUTION_SERIES - 1);
+        series.push(OTHER_SERIES.to_string());
+    }
+    let series_for = |name: &str| -> String {
+        if series.iter().any(|s| s == name) {
+            name.to_string()
+        } else {
+            OTHER_SERIES.to_string()
+        }
+    };
+
+    let mut days_out: Vec<DistributionDay> = (0..days)
+        .map(|offset| DistributionDay {
+            date: (start + chrono::Duration::days(offset))
+                .format("%Y-%m-%d")
+                .to_string(),
+            xp: series.iter().map(|name| (name.clone(), 0)).collect(),
+        })
+        .collect();
+    for (date, name, xp) in &rows {
+        let Ok(day) = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            continue;
+        };
+        let index = (day - start).num_days() as usize;
+        if let Some(entry) = days_out.get_mut(index) {
+            *entry.xp.entry(series_for(name)).or_insert(0) += xp;
+        }
+    }
+
+    Ok(ExerciseDistribution {
+        series,
+        days: days_out,
+    })
+}
+
+// ============ Level Projection ============
+
+/// Trailing window used to estimate each exercise's XP pace, in days
+const PROJECTION_WINDOW_DAYS: i64 = 30;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LevelProjection {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub current_level: i32,
+    pub average_daily_xp: f64, // Over the trailing window, rest days included
+    pub projected_date: Option<String>, // None when inactive over the window
+}
+
+/// Days needed to climb from `current_xp` to `target_level` at `xp_per_day`.
+/// Zero when already there, None when there is no pace to project from.
+fn estimate_time_to_level(current_xp: i64, target_level: i32, xp_per_day: f64) -> Option<i64> {
+    let remaining = levels::xp_for_level(target_level) - current_xp;
+    if remaining <= 0 {
+        return Some(0);
+    }
+    if xp_per_day <= 0.0 {
+        return None;
+    }
+    Some((remaining as f64 / xp_per_day).ceil() as i64)
+}
+
+#[tauri::command]
+fn get_projected_level_99_date(state: State<DbState>) -> Result<Vec<LevelProjection>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    level_99_projections(&conn, effective_today(&conn))
+}
+
+fn level_99_projections(
+    conn: &Connection,
+    today: chrono::NaiveDate,
+) -> Result<Vec<LevelProjection>, String> {
+    let start = today - chrono::Duration::days(PROJECTION_WINDOW_DAYS - 1);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT exercise_id, SUM(xp_earned) FROM exercise_logs
+             WHERE {} BETWEEN ?1 AND ?2
+             GROUP BY exercise_id",
+            log_day_sql(conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    let recent_xp: std::collections::HashMap<i64, i64> = stmt
+        .query_map(
+            params![
+                start.format("%Y-%m-%d").to_string(),
+                today.format("%Y-%m-%d").to_string()
+            ],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(list_exercises(conn)?
+        .into_iter()
+        .map(|exercise| {
+            let xp = recent_xp.get(&exercise.id).copied().unwrap_or(0);
+            let average_daily_xp = xp as f64 / PROJECTION_WINDOW_DAYS as f64;
+            // Without recent activity the pace says nothing, even if the exercise is already maxed
+            let projected_date = (xp > 0)
+                .then(|| {
+                    estimate_time_to_level(
+                        exercise.total_xp,
+                        levels::DEFAULT_MAX_LEVEL,
+                        average_daily_xp,
+                    )
+                })
+                .flatten()
+                .map(|days| {
+                    (today + chrono::Duration::days(days))
+                        .format("%Y-%m-%d")
+                        .to_string()
+                });
+            LevelProjection {
+                exercise_id: exercise.id,
+                exercise_name: exercise.name,
+                current_level: exercise.current_level,
+                average_daily_xp,
+                projected_date,
+            }
+        })
+        .collect())
+}
+
+// ============ XP Events ============
+
+/// Largest multiplier an XP event may grant
+const MAX_XP_MULTIPLIER: f64 = 10.0;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct XpEvent {
+    #[serde(default)]
+    pub id: i64, // Ignored when creating
+    pub name: String,
+    pub starts_at: String,
+    pub ends_at: String, // Exclusive
+    pub multiplier: f64,
+    pub exercise_id: Option<i64>, // Only this exercise, when set
+    pub category: Option<String>, // Only this category, when set
+}
+
+#[tauri::command]
+fn create_xp_event(state: State<DbState>, event: XpEvent) -> Result<i64, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    insert_xp_event(&conn, &event)
+}
+
+fn insert_xp_event(conn: &Connection, event: &XpEvent) -> Result<i64, String> {
+    let name = event.name.trim();
+    if name.is_empty() {
+        return Err("Event name cannot be empty".to_string());
+    }
+    if !(event.multiplier > 0.0 && event.multiplier <= MAX_XP_MULTIPLIER) {
+        return Err(format!(
+            "Multiplier must be above 0 and at most {}",
+            MAX_XP_MULTIPLIER
+        ));
+    }
+    let parse = |at: &str| {
+        chrono::NaiveDateTime::parse_from_str(at, "%Y-%m-%d %H:%M:%S").map_err(|_| {
+            format!(
+                "Invalid timestamp '{}', expected format YYYY-MM-DD HH:MM:SS",
+                at
+            )
+        })
+    };
+    if parse(&event.starts_at)? >= parse(&event.ends_at)? {
+        return Err("Event must end after it starts".to_string());
+    }
+    if let Some(exercise_id) = event.exercise_id {
+        get_exercise_by_id(conn, exercise_id)?;
+    }
+
+    conn.execute(
+        "INSERT INTO xp_events (name, starts_at, ends_at, multiplier, exercise_id, category) VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            name,
+            event.starts_at,
+            event.ends_at,
+            event.multiplier,
+            event.exercise_id,
+            event.category
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+#[tauri::command]
+fn get_xp_events(state: State<DbState>) -> Result<Vec<XpEvent>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, starts_at, ends_at, multiplier, exercise_id, category
+             FROM xp_events ORDER BY starts_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let events = stmt
+        .query_map([], |row| {
+            Ok(XpEvent {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                starts_at: row.get(2)?,
+                ends_at: row.get(3)?,
+                multiplier: row.get(4)?,
+                exercise_id: row.get(5)?,
+                category: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(events)
+}
+
+#[tauri::command]
+fn delete_xp_event(state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM xp_events WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// ============ Challenges ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChallengeProgress {
+    pub id: i64,
+    pub name: String,
+    pub exercise_id: Option<i64>,
+    pub target_reps: i64,
+    pub progress_reps: i64,
+    pub percent_complete: f64,
+    pub start_date: String,
+    pub end_date: String,
+    pub days_remaining: i64, // Including today
+    pub completed_at: Option<String>,
+}
+
+#[tauri::command]
+fn create_challenge(
+    state: State<DbState>,
+    name: String,
+    exercise_id: Option<i64>,
+    target_reps: i64,
+    start_date: String,
+    end_date: String,
+) -> Result<i64, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    insert_challenge(
+        &conn,
+        &name,
+        exercise_id,
+        target_reps,
+        &start_date,
+        &end_date,
+    )
+}
+
+fn insert_challenge(
+    conn: &Connection,
+    name: &str,
+    exercise_id: Option<i64>,
+    target_reps: i64,
+    start_date: &str,
+    end_date: &str,
+) -> Result<i64, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Challenge name cannot be empty".to_string());
+    }
+    if target_reps <= 0 {
+        return Err("Target reps must be positive".to_string());
+    }
+    let parse = |date: &str| {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{}', expected format YYYY-MM-DD", date))
+    };
+    if parse(start_date)? > parse(end_date)? {
+        return Err("Challenge must end on or after its start date".to_string());
+    }
+
+    conn.execute(
+        "INSERT INTO challenges (name, exercise_id, target_reps, start_date, end_date) VALUES (?, ?, ?, ?, ?)",
+        params![name, exercise_id, target_reps, start_date, end_date],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+#[tauri::command]
+fn delete_challenge(state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM challenges WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_active_challenges(state: State<DbState>) -> Result<Vec<ChallengeProgress>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    refresh_challenges(&conn, &today_string(&conn))
+}
+
+/// Computes progress for challenges running on `today` (YYYY-MM-DD), stamping
+/// `completed_at` on any that have just reached their target.
+fn refresh_challenges(conn: &Connection, today: &str) -> Result<Vec<ChallengeProgress>, String> {
+    let today_date =
+        chrono::NaiveDate::parse_from_str(today, "%Y-%m-%d").map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT c.id, c.name, c.exercise_id, c.target_reps, c.start_date, c.end_date, c.completed_at,
+                    (SELECT COALESCE(SUM(l.reps), 0) FROM exercise_logs l
+                     WHERE {} BETWEEN c.start_date AND c.end_date
+                       AND (c.exercise_id IS NULL OR l.exercise_id = c.exercise_id))
+             FROM challenges c
+             WHERE c.start_date <= ?1 AND c.end_date >= ?1
+             ORDER BY c.end_date ASC, c.id ASC",
+            log_day_sql(conn, "l.logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let mut challenges = stmt
+        .query_map(params![today], |row| {
+            Ok(ChallengeProgress {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                exercise_id: row.get(2)?,
+                target_reps: row.get(3)?,
+                start_date: row.get(4)?,
+                end_date: row.get(5)?,
+                completed_at: row.get(6)?,
+                progress_reps: row.get(7)?,
+                percent_complete: 0.0,
+                days_remaining: 0,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let now = local_timestamp(conn);
+    for challenge in &mut challenges {
+        challenge.percent_complete =
+            (challenge.progress_reps as f64 / challenge.target_reps as f64 * 100.0).min(100.0);
+        challenge.days_remaining =
+            chrono::NaiveDate::parse_from_str(&challenge.end_date, "%Y-%m-%d")
+                .map(|end| (end - today_date).num_days() + 1)
+                .unwrap_or(0);
+
+        if challenge.completed_at.is_none() && challenge.progress_reps >= challenge.target_reps {
+            conn.execute(
+                "UPDATE challenges SET completed_at = ? WHERE id = ?",
+                params![now, challenge.id],
+            )
+            .map_err(|e| e.to_string())?;
+            challenge.completed_at = Some(now.clone());
+        }
+    }
+
+    Ok(challenges)
+}
+
+// ============ Routines ============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutineStep {
+    pub exercise_id: i64,
+    pub reps: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Routine {
+    pub id: i64,
+    pub name: String,
+    pub steps: Vec<RoutineStep>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoutineLevelUp {
+    pub exercise_id: i64,
+    pub new_level: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoutineResult {
+    pub routine_name: String,
+    pub sets_logged: usize,
+    pub xp_earned: i32,
+    pub level_ups: Vec<RoutineLevelUp>,
+}
+
+#[tauri::command]
+fn create_routine(
+    app: AppHandle,
+    state: State<DbState>,
+    name: String,
+    steps: Vec<RoutineStep>,
+) -> Result<i64, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let id = insert_routine(&conn, &name, &steps)?;
+    drop(conn);
+    refresh_tray_menu(&app);
+    Ok(id)
+}
+
+fn insert_routine(conn: &Connection, name: &str, steps: &[RoutineStep]) -> Result<i64, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Routine name cannot be empty".to_string());
+    }
+    if steps.is_empty() {
+        return Err("A routine needs at least one exercise".to_string());
+    }
+    if steps.iter().any(|step| step.reps <= 0) {
+        return Err("Reps must be positive".to_string());
+    }
+    for step in steps {
+        get_exercise_by_id(conn, step.exercise_id)?;
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("INSERT INTO routines (name) VALUES (?)", params![name])
+        .map_err(|e| e.to_string())?;
+    let routine_id = tx.last_insert_rowid();
+    for (position, step) in steps.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO routine_steps (routine_id, position, exercise_id, reps) VALUES (?, ?, ?, ?)",
+            params![routine_id, position as i64, step.exercise_id, step.reps],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(routine_id)
+}
+
+#[tauri::command]
+fn get_routines(state: State<DbState>) -> Result<Vec<Routine>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    list_routines(&conn)
+}
+
+fn list_routines(conn: &Connection) -> Result<Vec<Routine>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, name FROM routines ORDER BY name")
+        .map_err(|e| e.to_string())?;
+    let routines = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    routines
+        .into_iter()
+        .map(|(id, name)| {
+            Ok(Routine {
+                id,
+                name,
+                steps: routine_steps(conn, id)?,
+            })
+        })
+        .collect()
+}
+
+fn routine_steps(conn: &Connection, routine_id: i64) -> Result<Vec<RoutineStep>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT exercise_id, reps FROM routine_steps WHERE routine_id = ? ORDER BY position",
+        )
+        .map_err(|e| e.to_string())?;
+    let steps = stmt
+        .query_map(params![routine_id], |row| {
+            Ok(RoutineStep {
+                exercise_id: row.get(0)?,
+                reps: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(steps)
+}
+
+#[tauri::command]
+fn delete_routine(app: AppHandle, state: State<DbState>, id: i64) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM routine_steps WHERE routine_id = ?",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM routines WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+    drop(conn);
+    refresh_tray_menu(&app);
+    Ok(())
+}
+
+#[tauri::command]
+fn run_routine(app: AppHandle, state: State<DbState>, id: i64) -> Result<RoutineResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let result = run_routine_now(&conn, id)?;
+    drop(conn);
+    let _ = app.emit("exercise-logged", ());
+    Ok(result)
+}
+
+/// Logs every step of a routine in one transaction; nothing is logged if any step fails.
+fn run_routine_now(conn: &Connection, routine_id: i64) -> Result<RoutineResult, String> {
+    let routine_name: String = conn
+        .query_row(
+            "SELECT name FROM routines WHERE id = ?",
+            params![routine_id],
+            |row| row.get(0),
+        )
+        .map_err(|_| format!("Routine {} not found", routine_id))?;
+    let steps = routine_steps(conn, routine_id)?;
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let now = local_timestamp(conn);
+    let mut xp_earned = 0;
+    let mut level_ups: Vec<RoutineLevelUp> = Vec::new();
+    for step in &steps {
+        let result = log_exercise_at_time(&tx, step.exercise_id, step.reps, &now)?;
+        xp_earned += result.xp_earned;
+        if result.leveled_up {
+            // Report each exercise once, at the level it finished on
+            level_ups.retain(|level_up| level_up.exercise_id != step.exercise_id);
+            level_ups.push(RoutineLevelUp {
+                exercise_id: step.exercise_id,
+                new_level: result.new_exercise_level,
+            });
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(RoutineResult {
+        routine_name,
+        sets_logged: steps.len(),
+        xp_earned,
+        level_ups,
+    })
+}
+
+// ============ Today's Plan ============
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TodaysPlan {
+    pub today_xp: i64,
+    pub daily_goal_xp: i64,
+    pub remaining_xp: i64,
+    pub suggested_exercise: Option<Exercise>,
+    pub current_streak: i32,
+    pub streak_at_risk: bool,
+}
+
+/// Today's date in the home timezone, after the day rollover.
+fn today_string(conn: &Connection) -> String {
+    effective_today(conn).format("%Y-%m-%d").to_string()
+}
+
+fn get_today_xp(conn: &Connection) -> i64 {
+    conn.query_row(
+        &format!(
+            "SELECT COALESCE(SUM(xp_earned), 0) FROM exercise_logs WHERE {} = ?",
+            log_day_sql(conn, "logged_at")
+        ),
+        params![today_string(conn)],
+        |row| row.get(0),
+    )
+    .unwrap_or(0)
+}
+
+fn get_daily_goal_xp(conn: &Connection) -> i64 {
+    get_setting_value(conn, "daily_goal_xp", "500")
+        .parse()
+        .unwrap_or(500)
+}
+
+fn has_logged_today(conn: &Connection) -> bool {
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(*) > 0 FROM exercise_logs WHERE {} = ?",
+            log_day_sql(conn, "logged_at")
+        ),
+        params![today_string(conn)],
+        |row| row.get(0),
+    )
+    .unwrap_or(false)
+}
+
+/// The exercise that has gone the longest without a log (never-logged ones first).
+/// With `reminders_only`, exercises opted out of reminders are skipped.
+fn suggest_neglected_exercise(conn: &Connection, reminders_only: bool) -> Option<Exercise> {
+    let id: i64 = conn
+        .query_row(
+            "SELECT e.id FROM exercises e
+             LEFT JOIN exercise_logs el ON el.exercise_id = e.id
+             WHERE ? = 0 OR COALESCE(e.remind, 1) = 1
+             GROUP BY e.id
+             ORDER BY MAX(el.logged_at) IS NOT NULL, MAX(el.logged_at), e.id
+             LIMIT 1",
+            params![reminders_only],
+            |row| row.get(0),
+        )
+        .ok()?;
+    get_exercise_by_id(conn, id).ok()
+}
+
+fn build_todays_plan(conn: &Connection) -> TodaysPlan {
+    let today_xp = get_today_xp(conn);
+    let daily_goal_xp = get_daily_goal_xp(conn);
+    let current_streak: i32 = conn
+        .query_row(
+            "SELECT current_streak FROM user_stats WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    TodaysPlan {
+        today_xp,
+        daily_goal_xp,
+        remaining_xp: (daily_goal_xp - today_xp).max(0),
+        suggested_exercise: suggest_neglected_exercise(conn, false),
+        current_streak,
+        streak_at_risk: current_streak > 0 && !has_logged_today(conn),
+    }
+}
+
+#[tauri::command]
+fn get_todays_plan(state: State<DbState>) -> Result<TodaysPlan, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(build_todays_plan(&conn))
+}
+
+// ============ Weekly Recommendations ============
+
+const PLAN_DAYS: i64 = 7;
+/// Categories (and so exercises) suggested per training day
+const PLAN_SETS_PER_DAY: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedSet {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub category: String,
+    pub reps: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlannedDay {
+    pub date: String,
+    pub rest_day: bool,
+    pub sets: Vec<PlannedSet>,
+}
+
+/// Exercise fields the planner needs
+#[derive(Debug, Clone)]
+struct PlanExercise {
+    id: i64,
+    name: String,
+    category: String,
+    xp_per_rep: i32,
+}
+
+/// SplitMix64, so a seed always yields the same plan
+struct PlanRng(u64);
+
+impl PlanRng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Rest days from the comma-separated `rest_days` setting, e.g. "Sat,Sun".
+fn rest_days(conn: &Connection) -> Vec<chrono::Weekday> {
+    get_setting_value(conn, "rest_days", "")
+        .split(',')
+        .filter_map(|day| day.trim().parse().ok())
+        .collect()
+}
+
+/// Plans `PLAN_DAYS` days from `start`. Each training day draws distinct categories,
+/// weighted towards those with the lowest total level, and sizes reps so the day adds
+/// up to roughly the daily XP goal.
+fn plan_week(
+    categories: &[CategoryLevel],
+    exercises: &[PlanExercise],
+    start: chrono::NaiveDate,
+    rest: &[chrono::Weekday],
+    daily_goal_xp: i64,
+    seed: u64,
+) -> Vec<PlannedDay> {
+    // Only categories the user can actually train
+    let categories: Vec<&CategoryLevel> = categories
+        .iter()
+        .filter(|c| exercises.iter().any(|e| e.category == c.category))
+        .collect();
+    let strongest = categories.iter().map(|c| c.total_level).max().unwrap_or(0);
+    let slots = PLAN_SETS_PER_DAY.min(categories.len());
+    let xp_per_set = daily_goal_xp.max(1) as f64 / slots.max(1) as f64;
+
+    let mut rng = PlanRng(seed);
+    (0..PLAN_DAYS)
+        .map(|offset| {
+            let date = start + chrono::Duration::days(offset);
+            let rest_day = rest.contains(&date.weekday());
+            let mut sets = Vec::new();
+
+            let mut pool = categories.clone();
+            while !rest_day && sets.len() < slots {
+                let weight = |c: &CategoryLevel| (strongest - c.total_level + 1) as f64;
+                let mut pick = rng.next_f64() * pool.iter().map(|c| weight(c)).sum::<f64>();
+                let index = pool
+                    .iter()
+                    .position(|c| {
+                        pick -= weight(c);
+                        pick < 0.0
+                    })
+                    .unwrap_or(pool.len() - 1);
+                let category = pool.remove(index);
+
+                let options: Vec<&PlanExercise> = exercises
+                    .iter()
+                    .filter(|e| e.category == category.category)
+                    .collect();
+                let exercise = options[(rng.next() % options.len() as u64) as usize];
+                let reps = (xp_per_set / exercise.xp_per_rep.max(1) as f64).ceil() as i32;
+                sets.push(PlannedSet {
+                    exercise_id: exercise.id,
+                    exercise_name: exercise.name.clone(),
+                    category: category.category.clone(),
+                    reps: reps.clamp(5, 100),
+                });
+            }
+
+            PlannedDay {
+                date: date.format("%Y-%m-%d").to_string(),
+                rest_day,
+                sets,
+            }
+        })
+        .collect()
+}
+
+fn recommendations(
+    conn: &Connection,
+    start: chrono::NaiveDate,
+    seed: u64,
+) -> Result<Vec<PlannedDay>, String> {
+    let balance = balance_score(conn)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, category, xp_per_rep FROM exercises
+             WHERE category IS NOT NULL ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+    let exercises = stmt
+        .query_map([], |row| {
+            Ok(PlanExercise {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                category: row.get(2)?,
+                xp_per_rep: row.get(3)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(plan_week(
+        &balance.categories,
+        &exercises,
+        start,
+        &rest_days(conn),
+        get_daily_goal_xp(conn),
+        seed,
+    ))
+}
+
+/// Suggested sets for the next week. Without a seed the plan stays the same all day.
+#[tauri::command]
+fn get_recommendations(
+    state: State<DbState>,
+    seed: Option<u64>,
+) -> Result<Vec<PlannedDay>, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let start = effective_today(&conn);
+    let seed = seed.unwrap_or(start.num_days_from_ce() as u64);
+    recommendations(&conn, start, seed)
+}
+
+// ============ User Profile ============
+
+const MAX_USER_NAME_CHARS: usize = 40;
+
+/// Display name and avatar for the header (empty until the user sets them)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub user_name: String,
+    pub avatar: String, // Icon name or image path
+}
+
+#[tauri::command]
+fn get_profile(state: State<DbState>) -> Result<UserProfile, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(user_profile(&conn))
+}
+
+#[tauri::command]
+fn set_profile(state: State<DbState>, user_name: String, avatar: String) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    save_user_profile(&conn, &user_name, &avatar)
+}
+
+fn user_profile(conn: &Connection) -> UserProfile {
+    UserProfile {
+        user_name: get_setting_value(conn, "user_name", ""),
+        avatar: get_setting_value(conn, "avatar", ""),
+    }
+}
+
+fn save_user_profile(conn: &Connection, user_name: &str, avatar: &str) -> Result<(), String> {
+    let user_name = user_name.trim();
+    if user_name.chars().count() > MAX_USER_NAME_CHARS {
+        return Err(format!(
+            "Name must be at most {} characters",
+            MAX_USER_NAME_CHARS
+        ));
+    }
+    for (key, value) in [("user_name", user_name), ("avatar", avatar.trim())] {
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+            params![key, value],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// ============ Dashboard Snapshot ============
+
+/// Everything the main view renders on load, read under one lock.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DashboardSnapshot {
+    pub profile: UserProfile,
+    pub stats: UserStats,
+    pub exercises: Vec<Exercise>,
+    pub unlocked_achievements: i64,
+    pub total_achievements: i64,
+    pub today_xp: i64,
+    pub daily_goal_xp: i64,
+}
+
+#[tauri::command]
+fn get_dashboard_snapshot(state: State<DbState>) -> Result<DashboardSnapshot, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    dashboard_snapshot(&conn)
+}
+
+fn dashboard_snapshot(conn: &Connection) -> Result<DashboardSnapshot, String> {
+    let (unlocked_achievements, total_achievements): (i64, i64) = conn
+        .query_row(
+            "SELECT COUNT(unlocked_at), COUNT(*) FROM achievements",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(DashboardSnapshot {
+        profile: user_profile(conn),
+        stats: user_stats(conn)?,
+        exercises: list_exercises(conn)?,
+        unlocked_achievements,
+        total_achievements,
+        today_xp: get_today_xp(conn),
+        daily_goal_xp: get_daily_goal_xp(conn),
+    })
+}
+
+// ============ Background Reminder System ============
+
+/// Exercise reminder interval for `weekday`, honouring the `reminder_weekday_intervals`
+/// setting (a JSON map like `{"mon": 60, "fri": 120}`) before the global interval.
+fn exercise_reminder_interval(
+    default_minutes: u64,
+    weekday_overrides: &str,
+    weekday: chrono::Weekday,
+) -> u64 {
+    let overrides: std::collections::HashMap<String, u64> =
+        serde_json::from_str(weekday_overrides).unwrap_or_default();
+    overrides
+        .iter()
+        .find(|(day, minutes)| {
+            **minutes > 0 && day.parse::<chrono::Weekday>().ok() == Some(weekday)
+        })
+        .map(|(_, minutes)| *minutes)
+        .unwrap_or(default_minutes)
+}
+
+/// Exercise reminder titles by escalation level
+const ESCALATION_TITLES: [&str; 3] = [
+    "Exercise Break! 💪",
+    "Still There? Time to Move! 💪",
+    "No More Excuses, Get Moving! 🚨",
+];
+/// Shortest interval escalation may shrink the exercise reminder to, in minutes
+const MIN_ESCALATED_INTERVAL_MINUTES: u64 = 15;
+
+/// Escalation for exercise reminders the user keeps ignoring. A reminder counts as
+/// ignored when nothing was logged before the next one fires; any log, or a new day,
+/// starts over.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ReminderEscalation {
+    day: String,
+    ignored: u32,
+    logs_at_last_reminder: Option<i64>,
+}
+
+impl ReminderEscalation {
+    /// Resets on a new day, or once something was logged since the last reminder.
+    fn observe(&mut self, day: &str, logs_today: i64) {
+        if self.day != day {
+            *self = ReminderEscalation {
+                day: day.to_string(),
+                ..Default::default()
+            };
+        } else if self
+            .logs_at_last_reminder
+            .is_some_and(|logs| logs_today > logs)
+        {
+            self.ignored = 0;
+            self.logs_at_last_reminder = None;
+        }
+    }
+
+    /// Records a reminder firing and returns the level to send it at (0 = normal).
+    fn fire(&mut self, day: &str, logs_today: i64, cap: u32) -> u32 {
+        self.observe(day, logs_today);
+        if self.logs_at_last_reminder.is_some() {
+            self.ignored += 1;
+        }
+        self.logs_at_last_reminder = Some(logs_today);
+        self.level(cap)
+    }
+
+    fn level(&self, cap: u32) -> u32 {
+        self.ignored.min(cap)
+    }
+}
+
+/// Halves the interval per escalation level, down to MIN_ESCALATED_INTERVAL_MINUTES.
+fn escalated_interval(base_minutes: u64, level: u32) -> u64 {
+    (base_minutes >> level.min(63)).max(base_minutes.min(MIN_ESCALATED_INTERVAL_MINUTES))
+}
+
+fn logs_today_count(conn: &Connection) -> i64 {
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM exercise_logs WHERE {} = ?",
+            log_day_sql(conn, "logged_at")
+        ),
+        params![today_string(conn)],
+        |row| row.get(0),
+    )
+    .unwrap_or(0)
+}
+
+const DEFAULT_REMINDER_TEMPLATE: &str =
+    "Time for a quick exercise break! Move your body, refresh your mind.";
+
+/// Substitutes `{name}` placeholders from `ctx`. Unknown placeholders and ones without
+/// a value are left as written, so a typo in the `reminder_template` setting never
+/// breaks the reminder.
+fn render_template(template: &str, ctx: &std::collections::HashMap<&str, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find('}') {
+            Some(close) => match ctx.get(&after[..close]) {
+                Some(value) => {
+                    out.push_str(value);
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = after;
+                }
+            },
+            None => {
+                out.push_str(&rest[open..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Placeholder values for the exercise reminder: the most neglected exercise, the
+/// reps of its last set (10 if never logged), its level and the current streak.
+fn reminder_template_context(conn: &Connection) -> std::collections::HashMap<&'static str, String> {
+    let mut ctx = std::collections::HashMap::new();
+    ctx.insert("streak", active_streak(conn).to_string());
+    if let Some(exercise) = suggest_neglected_exercise(conn, true) {
+        let reps: i32 = conn
+            .query_row(
+                "SELECT reps FROM exercise_logs WHERE exercise_id = ? ORDER BY logged_at DESC LIMIT 1",
+                params![exercise.id],
+                |row| row.get(0),
+            )
+            .unwrap_or(10);
+        ctx.insert("reps", reps.to_string());
+        ctx.insert("level", exercise.current_level.to_string());
+        ctx.insert("exercise", exercise.name);
+    }
+    ctx
+}
+
+fn start_reminder_loop(app_handle: AppHandle) {
+    let handle = app_handle.clone();
+
+    std::thread::spawn(move || {
+        // Check every 30 seconds
+        let check_interval = Duration::from_secs(30);
+
+        loop {
+            std::thread::sleep(check_interval);
+
+            // Get reminder state
+            let reminder_state = match handle.try_state::<ReminderState>() {
+                Some(state) => state,
+                None => continue,
+            };
+
+            if !reminder_state.running.load(Ordering::Relaxed) {
+                continue;
+            }
+
+            // Get database connection
+            let db_state = match handle.try_state::<DbState>() {
+                Some(state) => state,
+                None => continue,
+            };
+
+            let conn = match db_state.0.lock() {
+                Ok(conn) => conn,
+                Err(_) => continue,
+            };
+
+            // Helper to get setting value
+            let get_setting = |key: &str, default: &str| -> String {
+                conn.query_row(
+                    "SELECT value FROM settings WHERE key = ?",
+                    params![key],
+                    |row| row.get(0),
+                )
+                .unwrap_or_else(|_| default.to_string())
+            };
+
+            // Stay quiet while a fullscreen app (presentation, game, pairing) is in front
+            let pause_during_fullscreen =
+                get_setting("wellness_pause_during_fullscreen", "false") == "true";
+            if should_suppress_reminders(
+                pause_during_fullscreen,
+                reminder_state.fullscreen_detector.as_ref(),
+            ) {
+                continue;
+            }
+
+            // Skip everything while paused from the tray or settings, then resume with fresh timers
+            let paused_until = get_setting("reminders_paused_until", "");
+            if !paused_until.is_empty() {
+                if reminders_paused(parse_paused_until(&paused_until), local_now(&conn)) {
+                    continue;
+                }
+                log::info!("Reminders resumed (paused until {})", paused_until);
+                let _ = conn.execute(
+                    "DELETE FROM settings WHERE key = 'reminders_paused_until'",
+                    [],
+                );
+                let resumed_at = Instant::now();
+                *reminder_state.last_eye_care.lock().unwrap() = resumed_at;
+                *reminder_state.last_hydration.lock().unwrap() = resumed_at;
+                *reminder_state.last_posture.lock().unwrap() = resumed_at;
+                *reminder_state.last_exercise.lock().unwrap() = resumed_at;
+            }
+
+            let now = Instant::now();
+
+            // Check eye care reminder
+            let eye_care_enabled = get_setting("wellness_eye_care_enabled", "true") == "true";
+            let eye_care_interval: u64 = get_setting("wellness_eye_care_interval", "20")
+                .parse()
+                .unwrap_or(20);
+
+            if eye_care_enabled {
+                let last = *reminder_state.last_eye_care.lock().unwrap();
+                if now.duration_since(last) >= Duration::from_secs(eye_care_interval * 60) {
+                    send_reminder_notification(
+                        &handle,
+                        "Eye Break Time! 👀",
+                        "Look at something 20 feet away for 20 seconds. Your eyes will thank you!",
+                    );
+                    *reminder_state.last_eye_care.lock().unwrap() = now;
+                }
+            }
+
+            // Check hydration reminder
+            let hydration_enabled = get_setting("wellness_hydration_enabled", "true") == "true";
+            let hydration_interval: u64 = get_setting("wellness_hydration_interval", "60")
+                .parse()
+                .unwrap_or(60);
+
+            if hydration_enabled {
+                let last = *reminder_state.last_hydration.lock().unwrap();
+                if now.duration_since(last) >= Duration::from_secs(hydration_interval * 60) {
+                    send_reminder_notification(
+                        &handle,
+                        "Hydration Reminder 💧",
+                        "Time to drink some water! Stay hydrated for better focus.",
+                    );
+                    *reminder_state.last_hydration.lock().unwrap() = now;
+                }
+            }
+
+            // Check posture reminder
+            let posture_enabled = get_setting("wellness_posture_enabled", "true") == "true";
+            let posture_interval: u64 = get_setting("wellness_posture_interval", "45")
+                .parse()
+                .unwrap_or(45);
+
+            if posture_enabled {
+                let last = *reminder_state.last_posture.lock().unwrap();
+                if now.duration_since(last) >= Duration::from_secs(posture_interval * 60) {
+                    send_reminder_notification(
+                        &handle,
+                        "Posture Check! 🧘",
+                        "Roll your shoulders back, unclench your jaw, and sit up straight.",
+                    );
+                    *reminder_state.last_posture.lock().unwrap() = now;
+                }
+            }
+
+            // Check exercise reminder
+            let exercise_enabled = get_setting("reminder_enabled", "true") == "true";
+            let exercise_interval = exercise_reminder_interval(
+                get_setting("reminder_interval_minutes", "120")
+                    .parse()
+                    .unwrap_or(120),
+                &get_setting("reminder_weekday_intervals", "{}"),
+                local_now(&conn).weekday(),
+            );
+
+            if exercise_enabled {
+                // Ignored reminders come sooner and firmer, up to the configured cap (0 = off)
+                let escalation_cap = get_setting("reminder_escalation_cap", "2")
+                    .parse::<u32>()
+                    .unwrap_or(2)
+                    .min(ESCALATION_TITLES.len() as u32 - 1);
+                let day = today_string(&conn);
+                let logs_today = logs_today_count(&conn);
+                let mut escalation = reminder_state.exercise_escalation.lock().unwrap();
+                escalation.observe(&day, logs_today);
+                let interval =
+                    escalated_interval(exercise_interval, escalation.level(escalation_cap));
+
+                let last = *reminder_state.last_exercise.lock().unwrap();
+                if now.duration_since(last) >= Duration::from_secs(interval * 60) {
+                    let level = escalation.fire(&day, logs_today, escalation_cap);
+                    let template = get_setting("reminder_template", DEFAULT_REMINDER_TEMPLATE);
+                    let body = render_template(&template, &reminder_template_context(&conn));
+                    send_reminder_notification(&handle, ESCALATION_TITLES[level as usize], &body);
+                    *reminder_state.last_exercise.lock().unwrap() = now;
+                }
+
+                // Publish the next nudge for get_due_reminders
+                let since_last = now.duration_since(*reminder_state.last_exercise.lock().unwrap());
+                let interval =
+                    escalated_interval(exercise_interval, escalation.level(escalation_cap));
+                *reminder_state.next_exercise_at.lock().unwrap() =
+                    Some(next_reminder_at(local_now(&conn), since_last, interval));
+            } else {
+                *reminder_state.next_exercise_at.lock().unwrap() = None;
+            }
+
+            // Evening warning when an active streak has no log yet today
+            let streak_warning_enabled = get_setting("streak_warning_enabled", "true") == "true";
+            let streak_warn_hour: u32 = get_setting("streak_warn_hour", "20").parse().unwrap_or(20);
+            let today = today_string(&conn);
+            let already_warned = get_setting("streak_warning_last_date", "") == today;
+
+            if streak_warning_enabled && !already_warned {
+                let streak = active_streak(&conn);
+                if should_warn_streak(
+                    streak,
+                    local_now(&conn).hour(),
+                    streak_warn_hour,
+                    has_logged_today(&conn),
+                ) {
+                    send_reminder_notification(
+                        &handle,
+                        "Streak at Risk! 🔥",
+                        &format!(
+                            "Don't break your {}-day streak! A quick set before midnight keeps it alive.",
+                            streak
+                        ),
+                    );
+                    let _ = conn.execute(
+                        "INSERT OR REPLACE INTO settings (key, value) VALUES ('streak_warning_last_date', ?)",
+                        params![today],
+                    );
+                }
+            }
+
+            // Recap of yesterday, once per day after the summary hour (the day rollover by default)
+            if get_setting("daily_summary_enabled", "true") == "true" {
+                let summary_hour: u32 = get_setting("daily_summary_hour", "")
+                    .parse()
+                    .unwrap_or(streaks::day_rollover_hour(&conn) as u32);
+                let today = effective_today(&conn);
+                if should_send_summary(
+                    local_now(&conn).hour(),
+                    summary_hour,
+                    &get_setting("daily_summary_last_date", ""),
+                    today,
+                ) {
+                    let body = daily_summary_text(&conn, today - chrono::Duration::days(1));
+                    send_reminder_notification(&handle, "Daily Summary 📊", &body);
+                    let _ = conn.execute(
+                        "INSERT OR REPLACE INTO settings (key, value) VALUES ('daily_summary_last_date', ?)",
+                        params![today.format("%Y-%m-%d").to_string()],
+                    );
+                }
+            }
+
+            // Drop the connection lock before sleeping
+            drop(conn);
+        }
+    });
+}
+
+/// Current streak, or 0 if it was already broken (last log before yesterday).
+fn active_streak(conn: &Connection) -> i32 {
+    let (current_streak, last_date): (i32, Option<String>) = conn
+        .query_row(
+            "SELECT current_streak, last_exercise_date FROM user_stats WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap_or((0, None));
+
+    let today = effective_today(conn);
+    let still_alive = last_date
+        .and_then(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok())
+        .is_some_and(|d| today - d <= chrono::Duration::days(1));
+    if still_alive {
+        current_streak
+    } else {
+        0
+    }
+}
+
+fn should_warn_streak(
+    current_streak: i32,
+    current_hour: u32,
+    warn_hour: u32,
+    logged_today: bool,
+) -> bool {
+    current_streak > 0 && current_hour >= warn_hour && !logged_today
+}
+
+/// Whether the daily summary is due: past the summary hour and not yet sent for `today`.
+fn should_send_summary(
+    current_hour: u32,
+    summary_hour: u32,
+    last_summary_date: &str,
+    today: chrono::NaiveDate,
+) -> bool {
+    current_hour >= summary_hour.min(23)
+        && last_summary_date != today.format("%Y-%m-%d").to_string()
+}
+
+/// "Yesterday: 5 exercises, +320 XP, streak 12 days" for the given day.
+fn daily_summary_text(conn: &Connection, day: chrono::NaiveDate) -> String {
+    let (exercises, xp): (i64, i64) = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(DISTINCT exercise_id), COALESCE(SUM(xp_earned), 0) FROM exercise_logs
+                 WHERE {} = ?",
+                log_day_sql(conn, "logged_at")
+            ),
+            params![day.format("%Y-%m-%d").to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap_or((0, 0));
+    if exercises == 0 {
+        return "Yesterday was a rest day. Today's a fresh start!".to_string();
+    }
+    format!(
+        "Yesterday: {} exercise{}, +{} XP, streak {} days",
+        exercises,
+        if exercises == 1 { "" } else { "s" },
+        xp,
+        active_streak(conn)
+    )
+}
+
+fn should_suppress_reminders(
+    pause_during_fullscreen: bool,
+    detector: &dyn FullscreenDetector,
+) -> bool {
+    pause_during_fullscreen && detector.is_fullscreen_active()
+}
+
+// ============ Launch on Startup ============
+
+/// Registers GeekFit to start with the OS session.
+trait AutostartLauncher {
+    fn is_enabled(&self) -> Result<bool, String>;
+    fn set_enabled(&self, enabled: bool) -> Result<(), String>;
+}
+
+impl AutostartLauncher for AppHandle {
+    fn is_enabled(&self) -> Result<bool, String> {
+        use tauri_plugin_autostart::ManagerExt;
+        self.autolaunch().is_enabled().map_err(|e| e.to_string())
+    }
+
+    fn set_enabled(&self, enabled: bool) -> Result<(), String> {
+        use tauri_plugin_autostart::ManagerExt;
+        let autolaunch = self.autolaunch();
+        if enabled {
+            autolaunch.enable()
+        } else {
+            autolaunch.disable()
+        }
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[tauri::command]
+fn enable_autostart(app: AppHandle, state: State<DbState>) -> Result<bool, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    set_launch_on_startup(&conn, &app, true)
+}
+
+#[tauri::command]
+fn disable_autostart(app: AppHandle, state: State<DbState>) -> Result<bool, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    set_launch_on_startup(&conn, &app, false)
+}
+
+/// Updates the OS registration, then stores `launch_on_startup` to match.
+/// Returns the state the OS reports afterwards.
+fn set_launch_on_startup(
+    conn: &Connection,
+    launcher: &dyn AutostartLauncher,
+    enabled: bool,
+) -> Result<bool, String> {
+    launcher.set_enabled(enabled)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('launch_on_startup', ?)",
+        params![enabled.to_string()],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(launch_on_startup(conn, launcher))
+}
+
+/// The OS registration, since it can be changed outside the app (e.g. the Windows
+/// startup apps list). Falls back to the stored setting when the OS can't be queried.
+fn launch_on_startup(conn: &Connection, launcher: &dyn AutostartLauncher) -> bool {
+    launcher
+        .is_enabled()
+        .unwrap_or_else(|_| get_setting_value(conn, "launch_on_startup", "false") == "true")
+}
+
+// ============ Fullscreen Detection ============
+
+/// Reports whether the foreground window currently covers its whole monitor.
+trait FullscreenDetector: Send + Sync {
+    fn is_fullscreen_active(&self) -> bool;
+}
+
+struct SystemFullscreenDetector;
+
+impl FullscreenDetector for SystemFullscreenDetector {
+    fn is_fullscreen_active(&self) -> bool {
+        platform_fullscreen_active()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_fullscreen_active() -> bool {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct Rect {
+        left: i32,
+        top: i32,
+        right: i32,
+        bottom: i32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct MonitorInfo {
+        cb_size: u32,
+        rc_monitor: Rect,
+        rc_work: Rect,
+        dw_flags: u32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetForegroundWindow() -> *mut c_void;
+        fn GetDesktopWindow() -> *mut c_void;
+        fn GetShellWindow() -> *mut c_void;
+        fn GetWindowRect(hwnd: *mut c_void, rect: *mut Rect) -> i32;
+        fn MonitorFromWindow(hwnd: *mut c_void, flags: u32) -> *mut c_void;
+        fn GetMonitorInfoW(monitor: *mut c_void, info: *mut MonitorInfo) -> i32;
+    }
+
+    const MONITOR_DEFAULTTONEAREST: u32 = 2;
+
+    // SAFETY: plain user32 queries with valid out-pointers to repr(C) structs
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() || hwnd == GetDesktopWindow() || hwnd == GetShellWindow() {
+            return false;
+        }
+
+        let mut window = Rect::default();
+        if GetWindowRect(hwnd, &mut window) == 0 {
+            return false;
+        }
+
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info = MonitorInfo {
+            cb_size: std::mem::size_of::<MonitorInfo>() as u32,
+            ..Default::default()
+        };
+        if monitor.is_null() || GetMonitorInfoW(monitor, &mut info) == 0 {
+            return false;
+        }
+
+        window.left <= info.rc_monitor.left
+            && window.top <= info.rc_monitor.top
+            && window.right >= info.rc_monitor.right
+            && window.bottom >= info.rc_monitor.bottom
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn platform_fullscreen_active() -> bool {
+    use std::process::Command;
+
+    // X11 only: ask the window manager for the active window's _NET_WM_STATE
+    let active = match Command::new("xprop")
+        .args(["-root", "_NET_ACTIVE_WINDOW"])
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        }
+        _ => return false,
+    };
+
+    let window_id = match active.split_whitespace().last() {
+        Some(id) if id.starts_with("0x") && id != "0x0" => id.to_string(),
+        _ => return false,
+    };
+
+    match Command::new("xprop")
+        .args(["-id", window_id.as_str(), "_NET_WM_STATE"])
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).contains("_NET_WM_STATE_FULLSCREEN")
+        }
+        _ => false,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_fullscreen_active() -> bool {
+    use std::ffi::c_void;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGPoint {
+        x: f64,
+        y: f64,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGSize {
+        width: f64,
+        height: f64,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct CGRect {
+        origin: CGPoint,
+        size: CGSize,
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFArrayGetCount(array: *const c_void) -> isize;
+        fn CFArrayGetValueAtIndex(array: *const c_void, index: isize) -> *const c_void;
+        fn CFDictionaryGetValue(dict: *const c_void, key: *const c_void) -> *const c_void;
+        fn CFNumberGetValue(number: *const c_void, number_type: isize, value: *mut c_void) -> u8;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    #[allow(non_upper_case_globals)]
+    extern "C" {
+        static kCGWindowLayer: *const c_void;
+        static kCGWindowBounds: *const c_void;
+        fn CGWindowListCopyWindowInfo(option: u32, relative_to_window: u32) -> *const c_void;
+        fn CGRectMakeWithDictionaryRepresentation(dict: *const c_void, rect: *mut CGRect) -> u8;
+        fn CGGetActiveDisplayList(max_displays: u32, displays: *mut u32, count: *mut u32) -> i32;
+        fn CGDisplayBounds(display: u32) -> CGRect;
+    }
+
+    const WINDOW_LIST_ON_SCREEN_ONLY: u32 = 1 << 0;
+    const WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS: u32 = 1 << 4;
+    const CF_NUMBER_SINT32_TYPE: isize = 3;
+    const MAX_DISPLAYS: u32 = 16;
+
+    // SAFETY: CoreGraphics/CoreFoundation queries with valid out-pointers; the window
+    // list is owned by us (Copy rule) and released before returning
+    unsafe {
+        let mut display_ids = [0u32; MAX_DISPLAYS as usize];
+        let mut display_count = 0u32;
+        if CGGetActiveDisplayList(MAX_DISPLAYS, display_ids.as_mut_ptr(), &mut display_count) != 0 {
+            return false;
+        }
+        let displays: Vec<CGRect> = display_ids[..display_count as usize]
+            .iter()
+            .map(|&id| CGDisplayBounds(id))
+            .collect();
+
+        let windows = CGWindowListCopyWindowInfo(
+            WINDOW_LIST_ON_SCREEN_ONLY | WINDOW_LIST_EXCLUDE_DESKTOP_ELEMENTS,
+            0,
+        );
+        if windows.is_null() {
+            return false;
+        }
+
+        // Windows come front to back; the first one on the normal layer (0) belongs to
+        // the frontmost app, above it are only the menu bar, Dock and overlays
+        let mut covers_display = false;
+        for index in 0..CFArrayGetCount(windows) {
+            let window = CFArrayGetValueAtIndex(windows, index);
+            let layer_value = CFDictionaryGetValue(window, kCGWindowLayer);
+            let mut layer = 0i32;
+            if layer_value.is_null()
+                || CFNumberGetValue(
+                    layer_value,
+                    CF_NUMBER_SINT32_TYPE,
+                    &mut layer as *mut i32 as *mut c_void,
+                ) == 0
+                || layer != 0
+            {
+                continue;
+            }
+
+            let bounds_value = CFDictionaryGetValue(window, kCGWindowBounds);
+            let mut bounds = CGRect::default();
+            if !bounds_value.is_null()
+                && CGRectMakeWithDictionaryRepresentation(bounds_value, &mut bounds) != 0
+            {
+                // Window and display bounds share the global top-left coordinate space
+                covers_display = displays.iter().any(|display| {
+                    bounds.origin.x <= display.origin.x
+                        && bounds.origin.y <= display.origin.y
+                        && bounds.origin.x + bounds.size.width
+                            >= display.origin.x + display.size.width
+                        && bounds.origin.y + bounds.size.height
+                            >= display.origin.y + display.size.height
+                });
+            }
+            break;
+        }
+        CFRelease(windows);
+        covers_display
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn platform_fullscreen_active() -> bool {
+    // No detection available on this platform, so reminders are never suppressed
+    false
+}
+
+fn send_reminder_notification(app_handle: &AppHandle, title: &str, body: &str) {
+    use tauri_plugin_notification::NotificationExt;
+    let _ = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show();
+}
+
+// ============ Motivational Quotes ============
+
+const MOTIVATIONAL_QUOTES: &[&str] = &[
+    "Your code compiles. Now make your muscles compile too.",
+    "git commit -m \"Did my pushups\"",
+    "There's no such thing as a merge conflict between you and a squat.",
+    "Refactor your routine: extract method `stand_up()`.",
+    "Stack overflow? Try a stack of burpees instead.",
+    "Every rep is a unit test for your future self.",
+    "Your spine called. It wants to be deployed upright.",
+    "Don't let your body become legacy code.",
+    "Ship reps, not excuses.",
+    "Garbage collect that stress with a quick set.",
+    "The best debugger is a walk around the block.",
+    "Level up IRL: 10 squats before the next build finishes.",
+    "Hydrate, iterate, elevate.",
+    "A body in motion stays out of technical debt.",
+    "sudo make me_stronger",
+    "Keep calm and increment your streak.",
+];
+
+/// Picks a quote deterministically from the seed, so a date seed shows the same quote all day.
+fn quote_for_seed(seed: &str) -> &'static str {
+    // FNV-1a, stable across Rust versions unlike DefaultHasher
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in seed.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    MOTIVATIONAL_QUOTES[(hash % MOTIVATIONAL_QUOTES.len() as u64) as usize]
+}
+
+#[tauri::command]
+fn get_motivational_quote(state: State<DbState>, seed: Option<String>) -> Result<String, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    Ok(quote_of_the_day(&conn, seed.as_deref()).to_string())
+}
+
+/// The quote for `seed`, or for today in the home timezone when there is none.
+fn quote_of_the_day(conn: &Connection, seed: Option<&str>) -> &'static str {
+    match seed {
+        Some(seed) => quote_for_seed(seed),
+        None => quote_for_seed(&today_string(conn)),
+    }
+}
+
+// ============ Export/Import Data ============
+
+#[tauri::command]
+fn export_data(state: State<DbState>) -> Result<String, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    data_io::build_export_json(&conn)
+}
+
+/// Imports an export. `mode` is "replace" (the default) to swap in the exported data,
+/// or "keep_higher" to keep whichever side has more XP for each exercise.
+#[tauri::command]
+fn import_data(
+    state: State<DbState>,
+    undo: State<UndoState>,
+    json_data: String,
+    mode: Option<String>,
+) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    data_io::import_json(&conn, &json_data, mode.as_deref())?;
+    // Undone logs may clash with the imported history
+    undo.clear();
+    Ok(())
+}
+
+/// Returns (period, xp in period, cumulative total_xp) rows bucketed by `day`, `week` or `month`.
+fn build_xp_timeseries(
+    conn: &Connection,
+    granularity: &str,
+) -> Result<Vec<(String, i64, i64)>, String> {
+    if !matches!(granularity, "day" | "week" | "month") {
+        return Err(format!(
+            "Invalid granularity '{}', expected day, week or month",
+            granularity
+        ));
+    }
+
+    let bucket = |date: chrono::NaiveDate| -> String {
+        match granularity {
+            "week" => {
+                let monday =
+                    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+                monday.format("%Y-%m-%d").to_string()
+            }
+            "month" => date.format("%Y-%m").to_string(),
+            _ => date.format("%Y-%m-%d").to_string(),
+        }
+    };
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}, SUM(xp_earned) FROM exercise_logs GROUP BY 1 ORDER BY 1",
+            log_day_sql(conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    let daily: Vec<(String, i64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut series: Vec<(String, i64, i64)> = Vec::new();
+    let mut running_total = 0;
+    for (date, xp) in daily {
+        let date = match chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+            Ok(d) => d,
+            Err(_) => continue,
+        };
+        let period = bucket(date);
+        running_total += xp;
+        match series.last_mut() {
+            Some(last) if last.0 == period => {
+                last.1 += xp;
+                last.2 = running_total;
+            }
+            _ => series.push((period, xp, running_total)),
+        }
+    }
+
+    Ok(series)
+}
+
+#[tauri::command]
+fn export_timeseries_csv(state: State<DbState>, granularity: String) -> Result<String, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    let series = build_xp_timeseries(&conn, &granularity)?;
+
+    let mut csv = String::from("period,xp,total_xp\n");
+    for (period, xp, total_xp) in series {
+        csv.push_str(&format!("{},{},{}\n", period, xp, total_xp));
+    }
+    Ok(csv)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CsvImportResult {
+    pub imported: i32,
+    pub created_exercises: Vec<String>,
+    pub unresolved: Vec<String>, // "Line N: reason" for each skipped row
+}
+
+#[tauri::command]
+fn import_logs_csv(
+    state: State<DbState>,
+    csv_data: String,
+    mode: String,
+) -> Result<CsvImportResult, String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    import_logs_from_csv(&conn, &csv_data, &mode)
+}
+
+/// Imports `exercise_name,reps,logged_at` rows. `mode` is "skip" to report unknown
+/// exercise names or "create" to add them (using default-list settings when known).
+fn import_logs_from_csv(
+    conn: &Connection,
+    csv_data: &str,
+    mode: &str,
+) -> Result<CsvImportResult, String> {
+    let create_missing = match mode {
+        "skip" => false,
+        "create" => true,
+        _ => {
+            return Err(format!(
+                "Unknown import mode '{}', expected 'skip' or 'create'",
+                mode
+            ))
+        }
+    };
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut result = CsvImportResult::default();
+
+    for (index, line) in csv_data.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() || (index == 0 && line.starts_with("exercise_name")) {
+            continue;
+        }
+
+        // Split from the right so exercise names may contain commas
+        let mut fields = line.rsplitn(3, ',');
+        let (Some(logged_at), Some(reps), Some(name)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            result.unresolved.push(format!(
+                "Line {}: expected exercise_name,reps,logged_at",
+                line_no
+            ));
+            continue;
+        };
+        let name = name.trim().trim_matches('"');
+
+        let reps = match reps.trim().parse::<i32>() {
+            Ok(reps) if reps > 0 => reps,
+            _ => {
+                result
+                    .unresolved
+                    .push(format!("Line {}: invalid rep count '{}'", line_no, reps));
+                continue;
+            }
+        };
+
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM exercises WHERE LOWER(name) = LOWER(?)",
+                params![name],
+                |row| row.get(0),
+            )
+            .ok();
+        let exercise_id = match existing {
+            Some(id) => id,
+            None if create_missing => {
+                let default = get_default_exercises_list()
+                    .into_iter()
+                    .find(|(default_name, ..)| default_name.eq_ignore_ascii_case(name));
+                let (xp_per_rep, icon, category) = match default {
+                    Some((_, xp, icon, category)) => (xp, Some(icon), Some(category)),
+                    None => (10, None, None),
+                };
+                tx.execute(
+                    "INSERT INTO exercises (name, xp_per_rep, icon, category, total_xp, current_level) VALUES (?, ?, ?, ?, 0, 1)",
+                    params![name, xp_per_rep, icon, category],
+                )
+                .map_err(|e| e.to_string())?;
+                result.created_exercises.push(name.to_string());
+                tx.last_insert_rowid()
+            }
+            None => {
+                result
+                    .unresolved
+                    .push(format!("Line {}: unknown exercise '{}'", line_no, name));
+                continue;
+            }
+        };
+
+        // Handles XP, levels, level history and streaks for each row
+        match log_exercise_at_time(&tx, exercise_id, reps, logged_at.trim()) {
+            Ok(_) => result.imported += 1,
+            Err(e) => result.unresolved.push(format!("Line {}: {}", line_no, e)),
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+#[tauri::command]
+fn reset_all_data(state: State<DbState>, undo: State<UndoState>) -> Result<(), String> {
+    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    undo.clear();
+
+    // Clear all data - user must go through onboarding to add exercises again
+    conn.execute_batch(
+        "
+        DELETE FROM exercise_logs;
+        DELETE FROM level_history;
+        DELETE FROM exercises;
+        DELETE FROM challenges;
+        DELETE FROM routine_steps;
+        DELETE FROM routines;
+        DELETE FROM daily_goal_history;
+        UPDATE user_stats SET current_streak = 0, longest_streak = 0, last_exercise_date = NULL WHERE id = 1;
+        UPDATE achievements SET unlocked_at = NULL;
+        ",
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// ============ System Tray Setup ============
+
+/// Tray quick-log exercises: (exercise_id, name, category used if the exercise has none)
+const TRAY_EXERCISES: &[(i64, &str, &str)] = &[
+    (1, "Pushups", "Upper Body"),
+    (8, "Squats", "Lower Body"),
+    (3, "Sit-ups", "Core"),
+    (14, "Jumping Jacks", "Cardio"),
+];
+
+/// Quick desk stretches, grouped under their own submenu
+const TRAY_STRETCHES: &[(i64, &str, &str)] = &[
+    (19, "Neck Stretches", "Stretches"),
+    (21, "Wrist Circles", "Stretches"),
+    (20, "Shoulder Shrugs", "Stretches"),
+];
+
+/// Default tray rep counts for a category: high-rep cardio, short stretch sets,
+/// and lower counts for strength work.
+fn default_quick_log_reps(category: &str) -> &'static [i32] {
+    match category {
+        "Cardio" => &[10, 25, 50],
+        "Core" => &[10, 15, 25],
+        "Stretches" => &[5, 10],
+        _ => &[5, 10, 20],
+    }
+}
+
+fn exercise_categories(conn: &Connection) -> std::collections::HashMap<i64, String> {
+    let mut stmt =
+        match conn.prepare("SELECT id, category FROM exercises WHERE category IS NOT NULL") {
+            Ok(stmt) => stmt,
+            Err(_) => return std::collections::HashMap::new(),
+        };
+    stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Payload of the `quick-log-exercise` event, which opens the quick log pre-filled
+#[derive(Debug, Clone, Serialize)]
+struct QuickLogExercisePayload {
+    exercise_id: i64,
+}
+
+fn quick_log_exercise_payload(exercise_id: i64) -> QuickLogExercisePayload {
+    QuickLogExercisePayload { exercise_id }
+}
+
+/// Parses the `quick_log_presets` setting, a JSON map of exercise id to rep counts
+/// (e.g. `{"1": [10, 25]}`). Invalid entries, including counts `validate_reps` would
+/// reject, are ignored.
+fn parse_quick_log_presets(value: &str, min_reps: i32) -> std::collections::HashMap<i64, Vec<i32>> {
+    let raw: std::collections::HashMap<String, Vec<i32>> =
+        serde_json::from_str(value).unwrap_or_default();
+    raw.into_iter()
+        .filter_map(|(id, reps)| {
+            let reps: Vec<i32> = reps
+                .into_iter()
+                .filter(|r| validate_reps(*r, min_reps).is_ok())
+                .collect();
+            match id.parse::<i64>() {
+                Ok(id) if !reps.is_empty() => Some((id, reps)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// Format: "log_{exercise_id}_{reps}" and "custom_{exercise_id}" - parsed in the event handler
+fn quick_log_submenu(
+    app: &AppHandle,
+    exercise_id: i64,
+    name: &str,
+    reps: &[i32],
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    let mut items = reps
+        .iter()
+        .map(|r| {
+            MenuItem::with_id(
+                app,
+                format!("log_{}_{}", exercise_id, r),
+                format!("{} reps", r),
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    items.push(MenuItem::with_id(
+        app,
+        format!("custom_{}", exercise_id),
+        "Custom...",
+        true,
+        None::<&str>,
+    )?);
+
+    let refs: Vec<&dyn IsMenuItem<tauri::Wry>> = items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<tauri::Wry>)
+        .collect();
+    Submenu::with_items(app, name, true, &refs)
+}
+
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let open = MenuItem::with_id(app, "open", "Open Dashboard", true, None::<&str>)?;
+    let quick_log_window = MenuItem::with_id(
+        app,
+        "quick_log_window",
+        "Quick Log... (Ctrl+Shift+Alt+G)",
+        true,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app, "quit", "Quit GeekFit", true, None::<&str>)?;
+
+    // Repeats the last logged set; enabled once something has been logged
+    let last = app
+        .try_state::<LastLogState>()
+        .and_then(|state| state.get());
+    let log_again = MenuItem::with_id(
+        app,
+        "repeat_last_log",
+        log_again_label(last.as_ref()),
+        last.is_some(),
+        None::<&str>,
+    )?;
+    if let Some(last_log) = app.try_state::<LastLogState>() {
+        *last_log.menu_item.lock().unwrap() = Some(log_again.clone());
+    }
+
+    // Rep counts per exercise can be overridden via the quick_log_presets setting
+    // and otherwise default by the exercise's category
+    let (presets, categories) = match app.try_state::<DbState>() {
+        Some(db_state) => match db_state.0.lock() {
+            Ok(conn) => (
+                parse_quick_log_presets(
+                    &get_setting_value(&conn, "quick_log_presets", "{}"),
+                    min_reps(&conn),
+                ),
+                exercise_categories(&conn),
+            ),
+            Err(_) => Default::default(),
+        },
+        None => Default::default(),
+    };
+    let build_submenus = |exercises: &[(i64, &str, &str)]| {
+        exercises
+            .iter()
+            .map(|(id, name, category)| {
+                let category = categories.get(id).map(|c| c.as_str()).unwrap_or(category);
+                let reps = presets
+                    .get(id)
+                    .map(|r| r.as_slice())
+                    .unwrap_or_else(|| default_quick_log_reps(category));
+                quick_log_submenu(app, *id, name, reps)
+            })
+            .collect::<tauri::Result<Vec<_>>>()
+    };
+
+    let exercise_menus = build_submenus(TRAY_EXERCISES)?;
+
+    // One "Run ..." item per saved routine (format: routine_{id})
+    let routines = match app.try_state::<DbState>() {
+        Some(db_state) => match db_state.0.lock() {
+            Ok(conn) => list_routines(&conn).unwrap_or_default(),
+            Err(_) => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+    let routine_items = routines
+        .iter()
+        .map(|routine| {
+            MenuItem::with_id(
+                app,
+                format!("routine_{}", routine.id),
+                format!("Run {}", routine.name),
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let stretch_menus = build_submenus(TRAY_STRETCHES)?;
+
+    // Stretches parent submenu
+    let stretch_refs: Vec<&dyn IsMenuItem<tauri::Wry>> = stretch_menus
+        .iter()
+        .map(|menu| menu as &dyn IsMenuItem<tauri::Wry>)
+        .collect();
+    let stretches_menu = Submenu::with_items(app, "Stretches", true, &stretch_refs)?;
+
+    let separator1 = PredefinedMenuItem::separator(app)?;
+    let separator2 = PredefinedMenuItem::separator(app)?;
+    let separator3 = PredefinedMenuItem::separator(app)?;
+    let separator4 = PredefinedMenuItem::separator(app)?;
+
+    // Format: "pause_{minutes}" - parsed in the event handler
+    let pause_15 = MenuItem::with_id(app, "pause_15", "Pause for 15m", true, None::<&str>)?;
+    let pause_60 = MenuItem::with_id(app, "pause_60", "Pause for 1h", true, None::<&str>)?;
+    let pause_120 = MenuItem::with_id(app, "pause_120", "Pause for 2h", true, None::<&str>)?;
+    let pause_menu = Submenu::with_items(
+        app,
+        "Pause Reminders",
+        true,
+        &[&pause_15, &pause_60, &pause_120],
+    )?;
+
+    // Main Quick Log submenu
+    let mut quick_log_items: Vec<&dyn IsMenuItem<tauri::Wry>> = exercise_menus
+        .iter()
+        .map(|menu| menu as &dyn IsMenuItem<tauri::Wry>)
+        .collect();
+    quick_log_items.push(&separator1);
+    quick_log_items.push(&stretches_menu);
+    let quick_log_menu = Submenu::with_items(app, "Quick Log", true, &quick_log_items)?;
+
+    let mut items: Vec<&dyn IsMenuItem<tauri::Wry>> = vec![
+        &open,
+        &quick_log_window,
+        &log_again,
+        &separator2,
+        &quick_log_menu,
+    ];
+    if !routine_items.is_empty() {
+        items.push(&separator4);
+        items.extend(
+            routine_items
+                .iter()
+                .map(|item| item as &dyn IsMenuItem<tauri::Wry>),
+        );
+    }
+    items.push(&separator3);
+    items.push(&pause_menu);
+    items.push(&quit);
+    Menu::with_items(app, &items)
+}
+
+/// Rebuilds the tray menu after settings that shape it change.
+fn refresh_tray_menu(app: &AppHandle) {
+    if let Some(tray) = app.tray_by_id("main") {
+        match build_tray_menu(app) {
+            Ok(menu) => {
+                let _ = tray.set_menu(Some(menu));
+            }
+            Err(e) => log::error!("Failed to rebuild tray menu: {}", e),
+        }
+    }
+}
+
+fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let menu = build_tray_menu(app)?;
+
+    let _tray = TrayIconBuilder::with_id("main")
+        .icon(app.default_window_icon().unwrap().clone())
+        .menu(&menu)
+        .tooltip("GeekFit - Stay fit while coding!")
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| {
+            let event_id = event.id.as_ref();
+
+            // Handle quick log events (format: log_{exercise_id}_{reps})
+            if event_id.starts_with("log_") {
+                let parts: Vec<&str> = event_id.split('_').collect();
+                if parts.len() == 3 {
+                    if let (Ok(exercise_id), Ok(reps)) =
+                        (parts[1].parse::<i64>(), parts[2].parse::<i32>())
+                    {
+                        quick_log_from_tray(app, exercise_id, reps);
+                    }
+                }
+                return;
+            }
+
+            // "Custom..." opens the quick log pre-filled with the exercise (format: custom_{exercise_id})
+            if let Some(id) = event_id.strip_prefix("custom_") {
+                if let Ok(exercise_id) = id.parse::<i64>() {
+                    let _ = app.emit(
+                        "quick-log-exercise",
+                        quick_log_exercise_payload(exercise_id),
+                    );
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.unminimize();
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+                return;
+            }
+
+            if let Some(minutes) = event_id.strip_prefix("pause_") {
+                if let (Ok(minutes), Some(db_state)) =
+                    (minutes.parse::<i64>(), app.try_state::<DbState>())
+                {
+                    if let Ok(conn) = db_state.0.lock() {
+                        match pause_reminders(&conn, minutes, local_now(&conn)) {
+                            Ok(()) => log::info!("Reminders paused for {} minutes", minutes),
+                            Err(e) => log::error!("Failed to pause reminders: {}", e),
+                        }
+                    }
+                }
+                return;
+            }
+
+            if let Some(id) = event_id.strip_prefix("routine_") {
+                if let Ok(routine_id) = id.parse::<i64>() {
+                    run_routine_from_tray(app, routine_id);
+                }
+                return;
+            }
+
+            // Handle other menu events
+            match event_id {
+                "repeat_last_log" => {
+                    let last = app
+                        .try_state::<LastLogState>()
+                        .and_then(|state| state.get());
+                    if let Some(last) = last {
+                        quick_log_from_tray(app, last.exercise_id, last.reps);
+                    }
+                }
+                "open" => {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+                "quick_log_window" => {
+                    // Emit event to frontend to open quick log dialog
+                    let _ = app.emit("global-quick-log", ());
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+                "quit" => {
+                    app.exit(0);
+                }
+                _ => {}
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let tauri::tray::TrayIconEvent::DoubleClick { .. } = event {
+                let app = tray.app_handle();
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+        })
+        .build(app)?;
+
+    Ok(())
+}
+
+/// Logs an exercise picked from the tray and shows the result as a notification.
+fn quick_log_from_tray(app: &AppHandle, exercise_id: i64, reps: i32) {
+    let Some(db_state) = app.try_state::<DbState>() else {
+        return;
+    };
+    let logged = match db_state.0.lock() {
+        Ok(conn) => {
+            // Counted before logging, so an unlock by this log still chimes
+            let unlocked_before = unlocked_achievement_count(&conn);
+            let timestamp = local_timestamp(&conn);
+            log_exercise_at_time(&conn, exercise_id, reps, &timestamp).map(|result| {
+                play_log_sound(&conn, result.leveled_up, unlocked_before);
+                let exercise_name = get_exercise_by_id(&conn, exercise_id)
+                    .map(|exercise| exercise.name)
+                    .unwrap_or_else(|_| "Exercise".to_string());
+                let streak = user_stats(&conn)
+                    .map(|stats| stats.current_streak)
+                    .unwrap_or(0);
+                (result, exercise_name, streak)
+            })
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    // The database is released here, before touching the tray menu
+    let (result, exercise_name, streak) = match logged {
+        Ok(logged) => logged,
+        Err(e) => {
+            log::warn!(
+                "Failed to log exercise {} from the tray: {}",
+                exercise_id,
+                e
+            );
+            return;
+        }
+    };
+
+    let title = if result.leveled_up {
+        format!(
+            "Level Up! {} is now Lv{}",
+            exercise_name, result.new_exercise_level
+        )
+    } else if result.comeback {
+        COMEBACK_MESSAGE.to_string()
+    } else {
+        format!("Logged {} x {}", exercise_name, reps)
+    };
+    let body = format!("+{} XP | Streak: {} days", result.xp_earned, streak);
+
+    // Emit event to frontend to refresh stats
+    let _ = app.emit("exercise-logged", ());
+
+    use tauri_plugin_notification::NotificationExt;
+    let _ = app
+        .notification()
+        .builder()
+        .title(&title)
+        .body(&body)
+        .show();
+
+    if let Some(last_log) = app.try_state::<LastLogState>() {
+        last_log.record(LastLog {
+            exercise_id,
+            exercise_name,
+            reps,
+        });
+    }
+}
+
+/// Runs a saved routine picked from the tray and summarizes it in a notification.
+fn run_routine_from_tray(app: &AppHandle, routine_id: i64) {
+    let Some(db_state) = app.try_state::<DbState>() else {
+        return;
+    };
+    let result = match db_state.0.lock() {
+        Ok(conn) => run_routine_now(&conn, routine_id),
+        Err(e) => Err(e.to_string()),
+    };
+
+    use tauri_plugin_notification::NotificationExt;
+    match result {
+        Ok(result) => {
+            let _ = app.emit("exercise-logged", ());
+            let body = if result.level_ups.is_empty() {
+                format!("{} sets | +{} XP", result.sets_logged, result.xp_earned)
+            } else {
+                format!(
+                    "{} sets | +{} XP | {} level up(s)!",
+                    result.sets_logged,
+                    result.xp_earned,
+                    result.level_ups.len()
+                )
+            };
+            let _ = app
+                .notification()
+                .builder()
+                .title(format!("{} complete", result.routine_name))
+                .body(&body)
+                .show();
+        }
+        Err(e) => log::error!("Failed to run routine {}: {}", routine_id, e),
+    }
+}
+
+// ============ Global Shortcut Setup ============
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+fn setup_global_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    use tauri_plugin_global_shortcut::{
+        Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState,
+    };
+
+    // Register Ctrl+Shift+Alt+G for quick log
+    let shortcut = Shortcut::new(
+        Some(Modifiers::CONTROL | Modifiers::SHIFT | Modifiers::ALT),
+        Code::KeyG,
+    );
+
+    // First try to unregister in case it was previously registered
+    let _ = app.global_shortcut().unregister(shortcut);
+
+    // Register the shortcut with explicit state handling
+    match app
+        .global_shortcut()
+        .on_shortcut(shortcut, |app, _shortcut, event| {
+            // Only trigger on key press, not release
+            if event.state == ShortcutState::Pressed {
+                log::info!("Global shortcut Ctrl+Shift+Alt+G triggered");
+
+                // Show and focus the window (unminimize if needed)
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.unminimize();
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+
+                // Emit event to frontend to open quick log
+                if let Err(e) = app.emit("global-quick-log", ()) {
+                    log::error!("Failed to emit global-quick-log event: {}", e);
+                }
+            }
+        }) {
+        Ok(_) => {
+            log::info!("Successfully registered global shortcut Ctrl+Shift+Alt+G");
+        }
+        Err(e) => {
+            log::error!("Failed to register global shortcut Ctrl+Shift+Alt+G: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+// ============ App Entry Point ============
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    let mut builder = tauri::Builder::default()
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            None,
+        ));
+
+    // Add logging in debug mode
+    if cfg!(debug_assertions) {
+        builder = builder.plugin(
+            tauri_plugin_log::Builder::default()
+                .level(log::LevelFilter::Debug)
+                .build(),
+        );
+    }
+
+    builder
+        .setup(|app| {
+            // Initialize database, falling back to a user-writable folder on locked-down
+            // machines where the app data directory is read-only
+            let primary_dir = app.path().app_data_dir()?;
+            let fallback_dirs: Vec<PathBuf> = app
+                .path()
+                .document_dir()
+                .ok()
+                .map(|dir| dir.join("GeekFit"))
+                .into_iter()
+                .chain(std::iter::once(std::env::temp_dir().join("GeekFit")))
+                .collect();
+            let app_dir = select_data_dir(&primary_dir, &fallback_dirs, is_writable_dir)
+                .ok_or("No writable location for the GeekFit database")?;
+            let fallback_from = if app_dir != primary_dir {
+                if !app_dir.join(FALLBACK_MARKER).exists() {
+                    log::warn!(
+                        "Data directory {} is not writable, storing data in {} instead",
+                        primary_dir.display(),
+                        app_dir.display()
+                    );
+                    adopt_fallback_dir(&primary_dir, &app_dir)?;
+
+                    use tauri_plugin_notification::NotificationExt;
+                    let _ = app
+                        .notification()
+                        .builder()
+                        .title("GeekFit")
+                        .body(format!(
+                            "{} is read-only, so your data is now stored in {}",
+                            primary_dir.display(),
+                            app_dir.display()
+                        ))
+                        .show();
+                }
+                Some(primary_dir)
+            } else {
+                None
+            };
+
+            let (conn, db_health) = open_checked_profile(&app_dir, DEFAULT_PROFILE_ID)?;
+
+            // Reopen the last active profile (the default one is geekfit.db itself)
+            let profile_id = active_profile_id(&conn);
+            let (conn, db_health) = if profile_id == DEFAULT_PROFILE_ID {
+                (conn, db_health)
+            } else {
+                match open_checked_profile(&app_dir, profile_id) {
+                    Ok(profile) => profile,
+                    Err(e) => {
+                        log::error!("Failed to open profile {}: {}", profile_id, e);
+                        (conn, db_health)
+                    }
+                }
+            };
+
+            app.manage(DbState(Mutex::new(conn)));
+            app.manage(ProfileState {
+                app_dir: app_dir.clone(),
+            });
+            app.manage(DbHealthState(Mutex::new(db_health)));
+            app.manage(DataDirState {
+                path: app_dir.clone(),
+                fallback_from,
+            });
+
+            // Initialize reminder state
+            let now = Instant::now();
+            app.manage(ReminderState {
+                last_eye_care: Mutex::new(now),
+                last_hydration: Mutex::new(now),
+                last_posture: Mutex::new(now),
+                last_exercise: Mutex::new(now),
+                exercise_escalation: Mutex::new(ReminderEscalation::default()),
+                next_exercise_at: Mutex::new(None),
+                running: AtomicBool::new(true),
+                fullscreen_detector: Box::new(SystemFullscreenDetector),
+            });
+
+            app.manage(LastLogState::default());
+            app.manage(UndoState::default());
+
+            // Start background reminder loop
+            start_reminder_loop(app.handle().clone());
+
+            #[cfg(feature = "local-api")]
+            local_api::start(app.handle().clone());
+
+            // Setup system tray
+            setup_tray(app.handle())?;
+
+            // Setup global shortcuts (desktop only)
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            setup_global_shortcuts(app.handle())?;
+
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            // Minimize to tray instead of closing
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                // Hide the window instead of closing
+                let _ = window.hide();
+                // Prevent the window from actually closing
+                api.prevent_close();
+            }
+        })
+        .invoke_handler(tauri::generate_handler![
+            get_exercises,
+            add_exercise,
+            delete_exercise,
+            duplicate_exercise,
+            set_difficulty,
+            get_exercise_detail,
+            get_level_title,
+            get_default_exercises,
+            complete_initial_setup,
+            log_exercise,
+            log_exercise_at,
+            get_stats,
+            get_achievements,
+            get_exercise_history,
+            get_activity_data,
+            get_exercise_frequency_by_hour,
+            get_calendar_summary,
+            get_balance_score,
+            get_today_breakdown,
+            merge_exercises,
+            create_routine,
+            get_routines,
+            delete_routine,
+            run_routine,
+            get_streak_history,
+            pause_reminders_for,
+            get_reminder_status,
+            get_dashboard_snapshot,
+            reevaluate_achievements,
+            log_exercise_weighted,
+            get_comparison,
+            get_exercise_trend,
+            get_consistency_score,
+            get_profile,
+            set_profile,
+            get_xp_sources,
+            export_share_card,
+            get_recommendations,
+            get_exercise_packs,
+            bulk_add_exercises,
+            set_streak,
+            get_all_settings,
+            create_xp_event,
+            get_xp_events,
+            delete_xp_event,
+            maintenance,
+            get_exercise_distribution,
+            enable_autostart,
+            disable_autostart,
+            get_heaviest_days,
+            get_due_reminders,
+            get_weekday_averages,
+            undo_last_log,
+            redo_last_undo,
+            get_projected_level_99_date,
+            get_version_info,
+            get_level_timeline,
+            clear_history_before,
+            get_todays_plan,
+            recalculate_all_levels,
+            get_settings,
+            update_setting,
+            get_wellness_settings,
+            reset_reminder_timer,
+            get_motivational_quote,
+            export_data,
+            import_data,
+            export_timeseries_csv,
+            import_logs_csv,
+            create_challenge,
+            delete_challenge,
+            get_active_challenges,
+            get_records,
+            get_next_achievement,
+            set_seconds_per_rep,
+            set_exercise_color,
+            set_exercise_reminder,
+            get_total_active_time,
+            get_available_icons,
+            set_exercise_icon,
+            create_profile,
+            list_profiles,
+            switch_profile,
+            reset_all_data,
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
+// ============ Tests ============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_io::{
+        build_export_json, import_json, import_keep_higher, verify_export_checksum, ExportData,
+    };
+    use crate::levels::{level_from_xp, xp_for_level};
+
+    #[test]
+    fn test_xp_for_level_1() {
+        assert_eq!(xp_for_level(1), 0);
+    }
+
+    #[test]
+    fn test_xp_for_level_2() {
+        // Level 2 should require some XP
+        let xp = xp_for_level(2);
+        assert!(xp > 0);
+        assert!(xp < 100); // Should be relatively small
+    }
+
+    #[test]
+    fn test_xp_increases_with_level() {
+        // XP requirements should increase with each level
+        for level in 2..99 {
+            assert!(
+                xp_for_level(level + 1) > xp_for_level(level),
+                "XP for level {} should be greater than level {}",
+                level + 1,
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn test_xp_for_level_99() {
+        // Level 99 should require significant XP (RuneScape style)
+        let xp = xp_for_level(99);
+        assert!(xp > 10_000_000, "Level 99 should require over 10M XP");
+    }
+
+    #[test]
+    fn test_level_from_xp_zero() {
+        assert_eq!(level_from_xp(0), 1);
+    }
+
+    #[test]
+    fn test_level_from_xp_basic() {
+        // With 0 XP, should be level 1
+        assert_eq!(level_from_xp(0), 1);
+
+        // With some XP, should level up
+        let xp_for_2 = xp_for_level(2);
+        assert_eq!(level_from_xp(xp_for_2), 2);
+        assert_eq!(level_from_xp(xp_for_2 - 1), 1);
+    }
+
+    #[test]
+    fn test_level_from_xp_max() {
+        // Even with huge XP, max level is 99
+        assert_eq!(level_from_xp(100_000_000), 99);
+        assert_eq!(level_from_xp(i64::MAX / 2), 99);
+    }
+
+    #[test]
+    fn test_level_xp_roundtrip() {
+        // For each level, getting XP for that level and converting back should give same level
+        for level in 1..=99 {
+            let xp = xp_for_level(level);
+            assert_eq!(
+                level_from_xp(xp),
+                level,
+                "XP {} should give level {}",
+                xp,
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn test_database_initialization() {
+        // Test that database initializes without error
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(init_database(&conn).is_ok());
+    }
+
+    #[test]
+    fn test_no_default_exercises_on_init() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let count: i32 = conn
+            .query_row("SELECT COUNT(*) FROM exercises", [], |row| row.get(0))
+            .unwrap();
+
+        // Exercises are added through onboarding, not on init
+        assert_eq!(count, 0, "Should have no exercises on init, got {}", count);
+    }
+
+    #[test]
+    fn test_default_achievements_created() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let count: i32 = conn
+            .query_row("SELECT COUNT(*) FROM achievements", [], |row| row.get(0))
+            .unwrap();
+
+        // Should have achievements
+        assert!(
+            count >= 9,
+            "Should have at least 9 achievements, got {}",
+            count
+        );
+    }
+
+    #[test]
+    fn test_user_stats_initialized() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let (streak, longest): (i32, i32) = conn
+            .query_row(
+                "SELECT current_streak, longest_streak FROM user_stats WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+
+        assert_eq!(streak, 0);
+        assert_eq!(longest, 0);
+    }
+
+    #[test]
+    fn test_settings_initialized() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        let reminder: String = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'reminder_enabled'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        assert_eq!(reminder, "true");
+    }
+
+    fn setup_exercise(conn: &Connection, name: &str, xp_per_rep: i32) -> i64 {
+        conn.execute(
+            "INSERT INTO exercises (name, xp_per_rep, total_xp, current_level) VALUES (?, ?, 0, 1)",
+            params![name, xp_per_rep],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn days_ago(days: i64) -> String {
+        (chrono::Local::now() - chrono::Duration::days(days))
+            .format("%Y-%m-%d 12:00:00")
+            .to_string()
+    }
+
+    fn insert_log(conn: &Connection, exercise_id: i64, reps: i32, xp: i32, logged_at: &str) {
+        conn.execute(
+            "INSERT INTO exercise_logs (exercise_id, reps, xp_earned, logged_at) VALUES (?, ?, ?, ?)",
+            params![exercise_id, reps, xp, logged_at],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_log_exercise_at_bridges_streak() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+
+        // Two segments: 4-3 days ago and 1-0 days ago, with a gap 2 days ago
+        for days in [4, 3, 1, 0] {
+            insert_log(&conn, id, 10, 100, &days_ago(days));
+        }
+        assert_eq!(recompute_streak(&conn).unwrap(), (2, 2));
+
+        let result = log_exercise_at_time(&conn, id, 5, &days_ago(2)).unwrap();
+        assert_eq!(result.xp_earned, 50);
+
+        let (current, longest): (i32, i32) = conn
+            .query_row(
+                "SELECT current_streak, longest_streak FROM user_stats WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(current, 5);
+        assert_eq!(longest, 5);
+
+        let total_xp: i64 = conn
+            .query_row(
+                "SELECT total_xp FROM exercises WHERE id = ?",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(total_xp, 50);
+    }
+
+    #[test]
+    fn test_log_exercise_at_rejects_invalid_timestamps() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+
+        assert!(log_exercise_at_time(&conn, id, 10, "yesterday").is_err());
+        assert!(log_exercise_at_time(&conn, id, 10, "2024-13-01 10:00:00").is_err());
+
+        let tomorrow = (chrono::Local::now() + chrono::Duration::days(1))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        assert!(log_exercise_at_time(&conn, id, 10, &tomorrow).is_err());
+    }
+
+    struct MockFullscreenDetector(bool);
+
+    impl FullscreenDetector for MockFullscreenDetector {
+        fn is_fullscreen_active(&self) -> bool {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_reminders_suppressed_during_fullscreen() {
+        let fullscreen = MockFullscreenDetector(true);
+        let windowed = MockFullscreenDetector(false);
+
+        assert!(should_suppress_reminders(true, &fullscreen));
+        assert!(!should_suppress_reminders(true, &windowed));
+        // The setting is opt-in
+        assert!(!should_suppress_reminders(false, &fullscreen));
+    }
+
+    #[test]
+    fn test_quote_is_stable_per_day() {
+        assert_eq!(quote_for_seed("2024-03-10"), quote_for_seed("2024-03-10"));
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        assert_eq!(
+            quote_of_the_day(&conn, Some("2024-03-10")),
+            quote_for_seed("2024-03-10")
+        );
+        assert_eq!(
+            quote_of_the_day(&conn, None),
+            quote_for_seed(&today_string(&conn))
+        );
+    }
+
+    #[test]
+    fn test_quote_rotates_across_days() {
+        let quotes: std::collections::HashSet<&str> = (1..=28)
+            .map(|day| quote_for_seed(&format!("2024-02-{:02}", day)))
+            .collect();
+        assert!(quotes.len() > 1, "Quotes should rotate between days");
+    }
+
+    #[test]
+    fn test_exercise_detail_includes_title() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Squats", 8);
+        conn.execute(
+            "UPDATE exercises SET current_level = 12 WHERE id = ?",
+            params![id],
+        )
+        .unwrap();
+
+        let exercise = get_exercise_by_id(&conn, id).unwrap();
+        assert_eq!(exercise.name, "Squats");
+        assert_eq!(
+            levels::get_title_for_level(exercise.current_level),
+            "Gym Initiate"
+        );
+        assert!(get_exercise_by_id(&conn, id + 1).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_exercise_copies_config_not_xp() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        conn.execute(
+            "UPDATE exercises SET icon = 'fitness_center', total_xp = 5000, current_level = 20 WHERE id = ?",
+            params![id],
+        )
+        .unwrap();
+
+        let copy_id = copy_exercise(&conn, id, "Diamond Pushups").unwrap();
+        assert_ne!(copy_id, id);
+
+        let original = get_exercise_by_id(&conn, id).unwrap();
+        let copy = get_exercise_by_id(&conn, copy_id).unwrap();
+        assert_eq!(copy.name, "Diamond Pushups");
+        assert_eq!(copy.xp_per_rep, original.xp_per_rep);
+        assert_eq!(copy.icon, original.icon);
+        assert_eq!(copy.total_xp, 0);
+        assert_eq!(copy.current_level, 1);
+
+        // XP is tracked independently
+        conn.execute(
+            "UPDATE exercises SET total_xp = 100 WHERE id = ?",
+            params![copy_id],
+        )
+        .unwrap();
+        assert_eq!(get_exercise_by_id(&conn, id).unwrap().total_xp, 5000);
+    }
+
+    #[test]
+    fn test_duplicate_exercise_rejects_existing_name() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+
+        let err = copy_exercise(&conn, id, "pushups").unwrap_err();
+        assert!(err.contains("already exists"));
+        assert!(copy_exercise(&conn, id + 100, "Wide Pushups").is_err());
+    }
+
+    #[test]
+    fn test_todays_plan_reflects_state() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 8);
+        let lunges = setup_exercise(&conn, "Lunges", 10);
+
+        insert_log(&conn, squats, 10, 80, &days_ago(3));
+        insert_log(&conn, lunges, 10, 100, &days_ago(1));
+        conn.execute("UPDATE user_stats SET current_streak = 4 WHERE id = 1", [])
+            .unwrap();
+
+        // Nothing logged today yet: the streak is at risk and Pushups was never done
+        let plan = build_todays_plan(&conn);
+        assert_eq!(plan.today_xp, 0);
+        assert_eq!(plan.daily_goal_xp, 500);
+        assert_eq!(plan.remaining_xp, 500);
+        assert!(plan.streak_at_risk);
+        assert_eq!(plan.suggested_exercise.unwrap().id, pushups);
+
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        insert_log(&conn, pushups, 20, 200, &now);
+        let plan = build_todays_plan(&conn);
+        assert_eq!(plan.today_xp, 200);
+        assert_eq!(plan.remaining_xp, 300);
+        assert!(!plan.streak_at_risk);
+        assert_eq!(plan.suggested_exercise.unwrap().id, squats);
+    }
+
+    #[test]
+    fn test_recalculate_levels_fixes_inconsistent_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let broken = setup_exercise(&conn, "Pushups", 10);
+        let fine = setup_exercise(&conn, "Squats", 8);
+
+        let xp = xp_for_level(15);
+        conn.execute(
+            "UPDATE exercises SET total_xp = ?, current_level = 3 WHERE id = ?",
+            params![xp, broken],
+        )
+        .unwrap();
+
+        assert_eq!(recalculate_levels(&conn).unwrap(), 1);
+        assert_eq!(get_exercise_by_id(&conn, broken).unwrap().current_level, 15);
+        assert_eq!(get_exercise_by_id(&conn, fine).unwrap().current_level, 1);
+
+        // Running again finds nothing to fix
+        assert_eq!(recalculate_levels(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_difficulty_applies_to_logged_xp() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Burpees", 15);
+        assert_eq!(get_exercise_by_id(&conn, id).unwrap().difficulty, 1.0);
+
+        set_exercise_difficulty(&conn, id, 1.3).unwrap();
+        let timestamp = days_ago(1);
+        let result = log_exercise_at_time(&conn, id, 7, &timestamp).unwrap();
+        // 15 * 7 * 1.3 = 136.5
+        assert_eq!(result.xp_earned, 137);
+
+        assert!(set_exercise_difficulty(&conn, id, 0.0).is_err());
+        assert!(set_exercise_difficulty(&conn, id + 1, 2.0).is_err());
+    }
+
+    #[test]
+    fn test_xp_timeseries_is_cumulative() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        insert_log(&conn, id, 10, 100, "2024-01-30 08:00:00");
+        insert_log(&conn, id, 5, 50, "2024-01-30 18:00:00");
+        insert_log(&conn, id, 20, 200, "2024-02-01 09:00:00");
+        insert_log(&conn, id, 3, 30, "2024-02-14 09:00:00");
+
+        for granularity in ["day", "week", "month"] {
+            let series = build_xp_timeseries(&conn, granularity).unwrap();
+            assert!(series.windows(2).all(|w| w[1].2 >= w[0].2));
+            assert_eq!(series.last().unwrap().2, 380);
+        }
+
+        let daily = build_xp_timeseries(&conn, "day").unwrap();
+        assert_eq!(daily.len(), 3);
+        assert_eq!(daily[0], ("2024-01-30".to_string(), 150, 150));
+
+        // Jan 30 and Feb 1 2024 fall in the same week starting Monday Jan 29
+        let weekly = build_xp_timeseries(&conn, "week").unwrap();
+        assert_eq!(weekly[0], ("2024-01-29".to_string(), 350, 350));
+
+        let monthly = build_xp_timeseries(&conn, "month").unwrap();
+        assert_eq!(
+            monthly,
+            vec![
+                ("2024-01".to_string(), 150, 150),
+                ("2024-02".to_string(), 230, 380)
+            ]
+        );
+
+        assert!(build_xp_timeseries(&conn, "hour").is_err());
+    }
+
+    #[test]
+    fn test_raising_max_level_recomputes_levels() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        conn.execute(
+            "UPDATE exercises SET total_xp = ?, current_level = 99 WHERE id = ?",
+            params![xp_for_level(110), id],
+        )
+        .unwrap();
+
+        assert_eq!(get_max_level(&conn), 99);
+        assert_eq!(recalculate_levels(&conn).unwrap(), 0);
+
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('max_level', '120')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(recalculate_levels(&conn).unwrap(), 1);
+        assert_eq!(get_exercise_by_id(&conn, id).unwrap().current_level, 110);
+    }
+
+    #[test]
+    fn test_exercise_frequency_by_hour() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        insert_log(&conn, id, 10, 100, "2024-03-01 06:15:00");
+        insert_log(&conn, id, 10, 100, "2024-03-02 06:45:00");
+        insert_log(&conn, id, 10, 100, "2024-03-02 13:00:00");
+        insert_log(&conn, id, 10, 100, "2024-03-03 23:59:59");
+
+        let buckets = exercise_frequency_by_hour(&conn).unwrap();
+        assert_eq!(buckets.len(), 24);
+        assert_eq!(buckets[6], 2);
+        assert_eq!(buckets[13], 1);
+        assert_eq!(buckets[23], 1);
+        assert_eq!(buckets[0], 0);
+        assert_eq!(buckets.iter().sum::<i32>(), 4);
+    }
+
+    #[test]
+    fn test_should_warn_streak() {
+        // Active streak, evening, nothing logged yet
+        assert!(should_warn_streak(5, 20, 20, false));
+        assert!(should_warn_streak(1, 23, 20, false));
+        // Too early in the day
+        assert!(!should_warn_streak(5, 19, 20, false));
+        // Already logged today
+        assert!(!should_warn_streak(5, 21, 20, true));
+        // No streak to protect
+        assert!(!should_warn_streak(0, 21, 20, false));
+    }
+
+    #[test]
+    fn test_active_streak_ignores_broken_streaks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let yesterday = days_ago(1)[..10].to_string();
+        conn.execute(
+            "UPDATE user_stats SET current_streak = 6, last_exercise_date = ? WHERE id = 1",
+            params![yesterday],
+        )
+        .unwrap();
+        assert_eq!(active_streak(&conn), 6);
+
+        let last_week = days_ago(7)[..10].to_string();
+        conn.execute(
+            "UPDATE user_stats SET last_exercise_date = ? WHERE id = 1",
+            params![last_week],
+        )
+        .unwrap();
+        assert_eq!(active_streak(&conn), 0);
+    }
+
+    #[test]
+    fn test_last_log_tracking() {
+        let state = LastLogState::default();
+        assert_eq!(state.get(), None);
+        assert_eq!(
+            log_again_label(state.get().as_ref()),
+            "Log last exercise again"
+        );
+
+        state.record(LastLog {
+            exercise_id: 1,
+            exercise_name: "Pushups".to_string(),
+            reps: 10,
+        });
+        state.record(LastLog {
+            exercise_id: 2,
+            exercise_name: "Squats".to_string(),
+            reps: 15,
+        });
+
+        let last = state.get().unwrap();
+        assert_eq!(last.exercise_id, 2);
+        assert_eq!(last.reps, 15);
+        assert_eq!(log_again_label(Some(&last)), "Log Squats x15 again");
+    }
+
+    #[test]
+    fn test_quick_log_exercise_payload_serialization() {
+        let payload = serde_json::to_value(quick_log_exercise_payload(14)).unwrap();
+        assert_eq!(payload, serde_json::json!({ "exercise_id": 14 }));
+    }
+
+    #[test]
+    fn test_parse_quick_log_presets() {
+        let presets = parse_quick_log_presets(
+            r#"{"1": [10, 25], "8": [0, 15, 20000], "x": [5], "3": []}"#,
+            1,
+        );
+        assert_eq!(presets.get(&1), Some(&vec![10, 25]));
+        // Out-of-range counts are dropped, and empty or non-numeric entries ignored
+        assert_eq!(presets.get(&8), Some(&vec![15]));
+        assert_eq!(presets.len(), 2);
+
+        // Counts under the min_reps setting are dropped too
+        let presets = parse_quick_log_presets(r#"{"1": [10, 25]}"#, 20);
+        assert_eq!(presets.get(&1), Some(&vec![25]));
+
+        assert!(parse_quick_log_presets("not json", 1).is_empty());
+    }
+
+    fn category_levels(levels: &[i64]) -> Vec<CategoryLevel> {
+        BALANCE_CATEGORIES
+            .iter()
+            .zip(levels)
+            .map(|(category, level)| CategoryLevel {
+                category: category.to_string(),
+                total_level: *level,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_balance_score_balanced_vs_lopsided() {
+        let balanced = compute_balance_score(category_levels(&[10, 10, 10, 10, 10]));
+        let lopsided = compute_balance_score(category_levels(&[40, 2, 3, 1, 4]));
+        let single = compute_balance_score(category_levels(&[50, 0, 0, 0, 0]));
+
+        assert_eq!(balanced.score, 100);
+        assert!(lopsided.score < balanced.score);
+        assert!(single.score < lopsided.score);
+        assert_eq!(single.score, 0);
+        assert_eq!(lopsided.weakest_category, "Cardio");
+        assert_eq!(single.weakest_category, "Core");
+    }
+
+    #[test]
+    fn test_balance_score_uses_categories() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        assert_eq!(balance_score(&conn).unwrap().score, 0);
+
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 8);
+        conn.execute(
+            "UPDATE exercises SET category = 'Upper Body', current_level = 10 WHERE id = ?",
+            params![pushups],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE exercises SET category = 'Lower Body', current_level = 10 WHERE id = ?",
+            params![squats],
+        )
+        .unwrap();
+
+        let result = balance_score(&conn).unwrap();
+        assert!(result.score > 0 && result.score < 100);
+        assert_eq!(result.weakest_category, "Core");
+    }
+
+    #[test]
+    fn test_database_health_reports_dangling_logs() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        insert_log(&conn, id, 10, 100, &days_ago(0));
+
+        let health = check_database_health(&conn);
+        assert!(health.ok, "unexpected issues: {:?}", health.issues);
+
+        insert_log(&conn, 999, 10, 100, &days_ago(0));
+        let health = check_database_health(&conn);
+        assert!(!health.ok);
+        assert_eq!(health.issues.len(), 1);
+        assert!(health.issues[0].contains("1 exercise log(s)"));
+    }
+
+    #[test]
+    fn test_level_timeline_records_each_level_up() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+
+        // 200 XP jumps from level 1 straight to level 3
+        let result = log_exercise_at_time(&conn, id, 20, &days_ago(1)).unwrap();
+        assert_eq!(result.new_exercise_level, 3);
+        // Not enough for level 4
+        log_exercise_at_time(&conn, id, 1, &local_timestamp(&conn)).unwrap();
+
+        let timeline = level_timeline(&conn, id).unwrap();
+        let levels: Vec<i32> = timeline.iter().map(|m| m.level).collect();
+        assert_eq!(levels, vec![2, 3]);
+        assert_eq!(timeline[0].reached_at, days_ago(1));
+    }
+
+    #[test]
+    fn test_clear_history_before_keeps_only_recent_totals() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+
+        log_exercise_at_time(&conn, id, 30, &days_ago(10)).unwrap();
+        log_exercise_at_time(&conn, id, 5, &days_ago(9)).unwrap();
+        log_exercise_at_time(&conn, id, 2, &days_ago(1)).unwrap();
+
+        let cutoff = (chrono::Local::now() - chrono::Duration::days(5))
+            .format("%Y-%m-%d")
+            .to_string();
+        assert_eq!(clear_logs_before(&conn, &cutoff).unwrap(), 2);
+
+        let exercise = get_exercise_by_id(&conn, id).unwrap();
+        assert_eq!(exercise.total_xp, 20);
+        assert_eq!(exercise.current_level, 1);
+        assert!(level_timeline(&conn, id).unwrap().is_empty());
+
+        assert!(clear_logs_before(&conn, "last week").is_err());
+    }
+
+    #[test]
+    fn test_import_logs_csv_well_formed() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+
+        let csv = format!(
+            "exercise_name,reps,logged_at\npushups,10,{}\nPushups,5,{}\n",
+            days_ago(2),
+            days_ago(1)
+        );
+        let result = import_logs_from_csv(&conn, &csv, "skip").unwrap();
+        assert_eq!(result.imported, 2);
+        assert!(result.unresolved.is_empty());
+
+        let exercise = get_exercise_by_id(&conn, id).unwrap();
+        assert_eq!(exercise.total_xp, 150);
+        assert_eq!(exercise.current_level, 2);
+    }
+
+    #[test]
+    fn test_import_logs_csv_unknown_exercise() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        setup_exercise(&conn, "Pushups", 10);
+
+        let csv = format!("Pushups,10,{}\nBurpees,3,{}\n", days_ago(1), days_ago(1));
+        let skipped = import_logs_from_csv(&conn, &csv, "skip").unwrap();
+        assert_eq!(skipped.imported, 1);
+        assert_eq!(skipped.unresolved.len(), 1);
+        assert!(skipped.unresolved[0].contains("Line 2"));
+        assert!(skipped.unresolved[0].contains("Burpees"));
+
+        let created = import_logs_from_csv(&conn, &csv, "create").unwrap();
+        assert_eq!(created.imported, 2);
+        assert_eq!(created.created_exercises, vec!["Burpees".to_string()]);
+        // Default-list exercises keep their configured XP per rep
+        let xp_per_rep: i32 = conn
+            .query_row(
+                "SELECT xp_per_rep FROM exercises WHERE name = 'Burpees'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(xp_per_rep, 15);
+
+        assert!(import_logs_from_csv(&conn, &csv, "merge").is_err());
+    }
+
+    #[test]
+    fn test_challenge_progress_within_range() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let squats = setup_exercise(&conn, "Squats", 8);
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+
+        let id = insert_challenge(
+            &conn,
+            "October squats",
+            Some(squats),
+            100,
+            "2026-10-01",
+            "2026-10-31",
+        )
+        .unwrap();
+        insert_log(&conn, squats, 50, 400, "2026-09-30 23:00:00"); // before the start
+        insert_log(&conn, squats, 30, 240, "2026-10-01 08:00:00");
+        insert_log(&conn, squats, 10, 80, "2026-10-31 21:00:00"); // last day counts
+        insert_log(&conn, pushups, 40, 400, "2026-10-05 12:00:00"); // other exercise
+
+        let challenges = refresh_challenges(&conn, "2026-10-10").unwrap();
+        assert_eq!(challenges.len(), 1);
+        assert_eq!(challenges[0].id, id);
+        assert_eq!(challenges[0].progress_reps, 40);
+        assert!((challenges[0].percent_complete - 40.0).abs() < f64::EPSILON);
+        assert_eq!(challenges[0].days_remaining, 22);
+        assert!(challenges[0].completed_at.is_none());
+
+        // Not active once it has ended
+        assert!(refresh_challenges(&conn, "2026-11-01").unwrap().is_empty());
+        assert!(
+            insert_challenge(&conn, "Backwards", None, 10, "2026-10-31", "2026-10-01").is_err()
+        );
+    }
+
+    #[test]
+    fn test_challenge_completes_exactly_at_target() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let squats = setup_exercise(&conn, "Squats", 8);
+        insert_challenge(&conn, "Any 100", None, 100, "2026-10-01", "2026-10-31").unwrap();
+
+        insert_log(&conn, squats, 99, 792, "2026-10-02 08:00:00");
+        let challenges = refresh_challenges(&conn, "2026-10-02").unwrap();
+        assert!(challenges[0].completed_at.is_none());
+
+        insert_log(&conn, squats, 1, 8, "2026-10-02 09:00:00");
+        let challenges = refresh_challenges(&conn, "2026-10-02").unwrap();
+        let completed_at = challenges[0].completed_at.clone();
+        assert!(completed_at.is_some());
+        assert!((challenges[0].percent_complete - 100.0).abs() < f64::EPSILON);
+
+        // The completion timestamp is kept on later refreshes
+        insert_log(&conn, squats, 10, 80, "2026-10-03 09:00:00");
+        let challenges = refresh_challenges(&conn, "2026-10-03").unwrap();
+        assert_eq!(challenges[0].completed_at, completed_at);
+        assert_eq!(challenges[0].days_remaining, 29);
+    }
+
+    #[test]
+    fn test_exercise_reminder_interval_weekday_overrides() {
+        use chrono::Weekday;
+
+        let overrides = r#"{"mon": 60, "Friday": 90, "wed": 0}"#;
+        assert_eq!(exercise_reminder_interval(120, overrides, Weekday::Mon), 60);
+        assert_eq!(exercise_reminder_interval(120, overrides, Weekday::Fri), 90);
+        // No override, or a zero override, falls back to the global interval
+        assert_eq!(
+            exercise_reminder_interval(120, overrides, Weekday::Tue),
+            120
+        );
+        assert_eq!(
+            exercise_reminder_interval(120, overrides, Weekday::Wed),
+            120
+        );
+        assert_eq!(
+            exercise_reminder_interval(120, "not json", Weekday::Mon),
+            120
+        );
+    }
+
+    #[test]
+    fn test_records() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        assert_eq!(first_log_date(&conn).unwrap(), None);
+        assert!(longest_streak_record(&conn).unwrap().is_none());
+        assert!(most_xp_in_a_day(&conn).unwrap().is_none());
+
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 8);
+        insert_log(&conn, pushups, 20, 200, "2026-03-01 08:00:00");
+        insert_log(&conn, squats, 15, 120, "2026-03-02 08:00:00");
+        insert_log(&conn, pushups, 35, 350, "2026-03-02 18:00:00");
+        insert_log(&conn, squats, 30, 240, "2026-03-03 08:00:00");
+        insert_log(&conn, pushups, 10, 100, "2026-03-10 08:00:00");
+
+        assert_eq!(
+            first_log_date(&conn).unwrap().as_deref(),
+            Some("2026-03-01")
+        );
+
+        let streak = longest_streak_record(&conn).unwrap().unwrap();
+        assert_eq!(streak.days, 3);
+        assert_eq!(streak.start_date, "2026-03-01");
+        assert_eq!(streak.end_date, "2026-03-03");
+
+        let sets = best_sets(&conn).unwrap();
+        assert_eq!(sets.len(), 2);
+        assert_eq!(sets[0].exercise_name, "Pushups");
+        assert_eq!(sets[0].reps, 35);
+        assert_eq!(sets[0].logged_at, "2026-03-02 18:00:00");
+        assert_eq!(sets[1].reps, 30);
+
+        let xp_day = most_xp_in_a_day(&conn).unwrap().unwrap();
+        assert_eq!(xp_day.date, "2026-03-02");
+        assert_eq!(xp_day.value, 470);
+
+        let variety_day = most_exercises_in_a_day(&conn).unwrap().unwrap();
+        assert_eq!(variety_day.date, "2026-03-02");
+        assert_eq!(variety_day.value, 2);
+    }
+
+    #[test]
+    fn test_export_checksum_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        log_exercise_at_time(&conn, id, 10, &days_ago(1)).unwrap();
+
+        let json = build_export_json(&conn).unwrap();
+        let data: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(data["checksum"].as_str().map(|c| c.len()), Some(64));
+        assert!(verify_export_checksum(&data).is_ok());
+
+        // Older exports without a checksum still import
+        let mut legacy = data.clone();
+        legacy.as_object_mut().unwrap().remove("checksum");
+        assert!(verify_export_checksum(&legacy).is_ok());
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 8);
+        log_exercise_at_time(&conn, pushups, 20, &days_ago(2)).unwrap();
+        log_exercise_at_time(&conn, squats, 15, &days_ago(1)).unwrap();
+        let json = build_export_json(&conn).unwrap();
+
+        let restored = Connection::open_in_memory().unwrap();
+        init_database(&restored).unwrap();
+        import_json(&restored, &json, None).unwrap();
+        let original: ExportData = serde_json::from_str(&json).unwrap();
+        let round_trip: ExportData =
+            serde_json::from_str(&build_export_json(&restored).unwrap()).unwrap();
+        let summary = |data: &ExportData| -> Vec<(String, i64, i32)> {
+            data.exercises
+                .iter()
+                .map(|e| (e.name.clone(), e.total_xp, e.current_level))
+                .collect()
+        };
+        assert_eq!(summary(&round_trip), summary(&original));
+        assert_eq!(round_trip.exercise_logs.len(), 2);
+        assert_eq!(round_trip.user_stats.total_xp, original.user_stats.total_xp);
+
+        // Merging the same backup again keeps one copy of everything
+        import_json(&restored, &json, Some("keep_higher")).unwrap();
+        let merged: ExportData =
+            serde_json::from_str(&build_export_json(&restored).unwrap()).unwrap();
+        assert_eq!(summary(&merged), summary(&original));
+        assert_eq!(merged.exercise_logs.len(), 2);
+
+        assert!(import_json(&restored, &json, Some("append")).is_err());
+        assert!(import_json(&restored, "{}", None).is_err());
+    }
+
+    #[test]
+    fn test_export_checksum_rejects_tampering() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        log_exercise_at_time(&conn, id, 10, &days_ago(1)).unwrap();
+
+        let mut tampered: serde_json::Value =
+            serde_json::from_str(&build_export_json(&conn).unwrap()).unwrap();
+        tampered["exercises"][0]["total_xp"] = serde_json::json!(999_999);
+
+        let err = verify_export_checksum(&tampered).unwrap_err();
+        assert!(err.contains("corrupted or was edited"));
+    }
+
+    #[test]
+    fn test_next_achievement_picks_closest() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+
+        // Nothing logged yet: the first log is 0% like everything else, and listed first
+        let next = next_achievement(&conn).unwrap().unwrap();
+        assert_eq!(next.key, "first_exercise");
+        assert_eq!(next.progress, 0.0);
+
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 8);
+        let lunges = setup_exercise(&conn, "Lunges", 10);
+        let today = format!("{} 12:00:00", today_string(&conn));
+        for id in [pushups, squats, lunges] {
+            insert_log(&conn, id, 10, 100, &today);
         }
-        _ => return Err(format!("Unknown reminder type: {}", reminder_type)),
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'first_exercise'",
+            params![today],
+        )
+        .unwrap();
+
+        // 3 of 5 distinct exercises beats 30/1000 reps and 10/100 pushups today
+        let next = next_achievement(&conn).unwrap().unwrap();
+        assert_eq!(next.key, "variety");
+        assert_eq!((next.current, next.target), (3, 5));
+        assert!((next.progress - 0.6).abs() < 1e-9);
     }
-    Ok(())
-}
 
-// ============ Background Reminder System ============
+    #[test]
+    fn test_default_seconds_per_rep() {
+        assert_eq!(
+            default_seconds_per_rep("Plank (10 sec)", Some("Core")),
+            10.0
+        );
+        assert_eq!(default_seconds_per_rep("Pushups", Some("Upper Body")), 2.0);
+        assert_eq!(default_seconds_per_rep("Burpees", Some("Cardio")), 1.0);
+        assert_eq!(default_seconds_per_rep("Handstand", None), 3.0);
+    }
 
-fn start_reminder_loop(app_handle: AppHandle) {
-    let handle = app_handle.clone();
+    #[test]
+    fn test_active_minutes_mixed_exercises() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let plank = setup_exercise(&conn, "Plank (10 sec)", 5);
+        let custom = setup_exercise(&conn, "Kettlebell Swings", 8);
+        conn.execute(
+            "UPDATE exercises SET category = 'Upper Body' WHERE id = ?",
+            params![pushups],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE exercises SET seconds_per_rep = 4.0 WHERE id = ?",
+            params![custom],
+        )
+        .unwrap();
 
-    std::thread::spawn(move || {
-        // Check every 30 seconds
-        let check_interval = Duration::from_secs(30);
+        let today = format!("{} 12:00:00", today_string(&conn));
+        insert_log(&conn, pushups, 30, 300, &today); // 30 x 2s = 60s
+        insert_log(&conn, plank, 6, 30, &days_ago(3)); // 6 x 10s = 60s
+        insert_log(&conn, custom, 15, 120, &today); // 15 x 4s = 60s
 
-        loop {
-            std::thread::sleep(check_interval);
+        let lifetime = active_minutes(&conn, None).unwrap();
+        assert!((lifetime - 3.0).abs() < 1e-9);
+        let today_only = active_minutes(&conn, Some(&today_string(&conn))).unwrap();
+        assert!((today_only - 2.0).abs() < 1e-9);
+    }
 
-            // Get reminder state
-            let reminder_state = match handle.try_state::<ReminderState>() {
-                Some(state) => state,
-                None => continue,
-            };
+    #[test]
+    fn test_update_exercise_icon_validation() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
 
-            if !reminder_state.running.load(Ordering::Relaxed) {
-                continue;
-            }
+        update_exercise_icon(&conn, id, "directions_run").unwrap();
+        assert_eq!(
+            get_exercise_by_id(&conn, id).unwrap().icon.as_deref(),
+            Some("directions_run")
+        );
 
-            // Get database connection
-            let db_state = match handle.try_state::<DbState>() {
-                Some(state) => state,
-                None => continue,
-            };
+        let err = update_exercise_icon(&conn, id, "fitnes_center").unwrap_err();
+        assert!(err.contains("Unknown icon 'fitnes_center'"));
+        assert!(err.contains("fitness_center"));
+        assert!(update_exercise_icon(&conn, 999, "pool").is_err());
 
-            let conn = match db_state.0.lock() {
-                Ok(conn) => conn,
-                Err(_) => continue,
-            };
+        // Seeded defaults only use supported icons
+        for (_, _, icon, _) in get_default_exercises_list() {
+            assert!(AVAILABLE_ICONS.contains(&icon), "{} is not allowed", icon);
+        }
+    }
 
-            // Helper to get setting value
-            let get_setting = |key: &str, default: &str| -> String {
-                conn.query_row(
-                    "SELECT value FROM settings WHERE key = ?",
-                    params![key],
-                    |row| row.get(0),
-                )
-                .unwrap_or_else(|_| default.to_string())
-            };
+    #[test]
+    fn test_profiles_registry() {
+        let registry = Connection::open_in_memory().unwrap();
+        init_database(&registry).unwrap();
+
+        let work = insert_profile(&registry, "Work").unwrap();
+        assert!(insert_profile(&registry, "work").is_err());
+        assert!(insert_profile(&registry, "  ").is_err());
+
+        let profiles = list_profiles_in(&registry).unwrap();
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["default", "Work"]);
+        assert!(profiles[0].active);
+        assert_eq!(profiles[1].id, work);
+    }
 
-            let now = Instant::now();
+    #[test]
+    fn test_profiles_keep_logs_separate() {
+        let app_dir = std::env::temp_dir().join(format!(
+            "geekfit-profiles-test-{}-{}",
+            std::process::id(),
+            chrono::Local::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_default()
+        ));
+
+        let default_conn = open_profile_registry(&app_dir).unwrap();
+        let home = insert_profile(&default_conn, "Home").unwrap();
+        let home_conn = open_profile_database(&app_dir, home).unwrap();
+
+        let pushups = setup_exercise(&default_conn, "Pushups", 10);
+        log_exercise_at_time(&default_conn, pushups, 20, &local_timestamp(&default_conn)).unwrap();
+
+        let home_pushups = setup_exercise(&home_conn, "Pushups", 10);
+        let log_count = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM exercise_logs", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(log_count(&home_conn), 0);
+        assert_eq!(
+            get_exercise_by_id(&home_conn, home_pushups)
+                .unwrap()
+                .total_xp,
+            0
+        );
+        assert_eq!(log_count(&default_conn), 1);
 
-            // Check eye care reminder
-            let eye_care_enabled = get_setting("wellness_eye_care_enabled", "true") == "true";
-            let eye_care_interval: u64 = get_setting("wellness_eye_care_interval", "20")
-                .parse()
-                .unwrap_or(20);
+        drop(default_conn);
+        drop(home_conn);
+        let _ = std::fs::remove_dir_all(&app_dir);
+    }
 
-            if eye_care_enabled {
-                let last = *reminder_state.last_eye_care.lock().unwrap();
-                if now.duration_since(last) >= Duration::from_secs(eye_care_interval * 60) {
-                    send_reminder_notification(
-                        &handle,
-                        "Eye Break Time! 👀",
-                        "Look at something 20 feet away for 20 seconds. Your eyes will thank you!",
-                    );
-                    *reminder_state.last_eye_care.lock().unwrap() = now;
-                }
-            }
+    #[test]
+    fn test_month_summary_boundaries() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        insert_log(&conn, id, 10, 100, "2024-01-31 23:59:00");
+        insert_log(&conn, id, 5, 50, "2024-02-01 00:00:00");
+        insert_log(&conn, id, 5, 50, "2024-02-01 08:00:00");
+        insert_log(&conn, id, 20, 200, "2024-02-29 12:00:00");
+        insert_log(&conn, id, 10, 100, "2024-03-01 07:00:00");
+
+        // Leap-year February, excluding the neighbouring months' logs
+        let february = month_summary(&conn, 2024, 2).unwrap();
+        assert_eq!(february.len(), 29);
+        assert_eq!(february[0].date, "2024-02-01");
+        assert_eq!((february[0].count, february[0].xp), (2, 100));
+        assert_eq!((february[1].count, february[1].xp), (0, 0));
+        assert_eq!(february[28].xp, 200);
+
+        let december = month_summary(&conn, 2023, 12).unwrap();
+        assert_eq!(december.len(), 31);
+        assert!(december.iter().all(|day| day.count == 0 && day.xp == 0));
+
+        assert!(month_summary(&conn, 2024, 13).is_err());
+    }
 
-            // Check hydration reminder
-            let hydration_enabled = get_setting("wellness_hydration_enabled", "true") == "true";
-            let hydration_interval: u64 = get_setting("wellness_hydration_interval", "60")
-                .parse()
-                .unwrap_or(60);
+    #[test]
+    fn test_category_breakdown_shares() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        assert!(category_breakdown(&conn, "2024-05-01").unwrap().is_empty());
 
-            if hydration_enabled {
-                let last = *reminder_state.last_hydration.lock().unwrap();
-                if now.duration_since(last) >= Duration::from_secs(hydration_interval * 60) {
-                    send_reminder_notification(
-                        &handle,
-                        "Hydration Reminder 💧",
-                        "Time to drink some water! Stay hydrated for better focus.",
-                    );
-                    *reminder_state.last_hydration.lock().unwrap() = now;
-                }
-            }
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 8);
+        let custom = setup_exercise(&conn, "Kettlebell Swings", 8);
+        conn.execute(
+            "UPDATE exercises SET category = 'Upper Body' WHERE id = ?",
+            params![pushups],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE exercises SET category = 'Lower Body' WHERE id = ?",
+            params![squats],
+        )
+        .unwrap();
+        insert_log(&conn, pushups, 20, 200, "2024-05-01 09:00:00");
+        insert_log(&conn, squats, 10, 80, "2024-05-01 12:00:00");
+        insert_log(&conn, custom, 15, 120, "2024-05-01 18:00:00");
+        insert_log(&conn, pushups, 50, 500, "2024-05-02 09:00:00"); // another day
+
+        let breakdown = category_breakdown(&conn, "2024-05-01").unwrap();
+        let categories: Vec<&str> = breakdown.iter().map(|s| s.category.as_str()).collect();
+        assert_eq!(
+            categories,
+            vec!["Upper Body", "Uncategorized", "Lower Body"]
+        );
+        assert!((breakdown[0].percentage - 50.0).abs() < 1e-9);
 
-            // Check posture reminder
-            let posture_enabled = get_setting("wellness_posture_enabled", "true") == "true";
-            let posture_interval: u64 = get_setting("wellness_posture_interval", "45")
-                .parse()
-                .unwrap_or(45);
+        let total: f64 = breakdown.iter().map(|s| s.percentage).sum();
+        assert!((total - 100.0).abs() < 1e-9);
 
-            if posture_enabled {
-                let last = *reminder_state.last_posture.lock().unwrap();
-                if now.duration_since(last) >= Duration::from_secs(posture_interval * 60) {
-                    send_reminder_notification(
-                        &handle,
-                        "Posture Check! 🧘",
-                        "Roll your shoulders back, unclench your jaw, and sit up straight.",
-                    );
-                    *reminder_state.last_posture.lock().unwrap() = now;
-                }
-            }
+        assert!(category_breakdown(&conn, "2024-05-03").unwrap().is_empty());
+    }
 
-            // Check exercise reminder
-            let exercise_enabled = get_setting("reminder_enabled", "true") == "true";
-            let exercise_interval: u64 = get_setting("reminder_interval_minutes", "120")
-                .parse()
-                .unwrap_or(120);
+    #[test]
+    fn test_day_rollover_counts_early_logs_toward_previous_day() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        insert_log(&conn, id, 10, 100, "2024-03-01 22:00:00");
+        insert_log(&conn, id, 10, 100, "2024-03-03 02:00:00");
+
+        // Midnight rollover: the 2 AM log starts a new day after a gap
+        assert_eq!(
+            longest_run(&active_dates(&conn).unwrap()).map(|(s, e)| (e - s).num_days()),
+            Some(0)
+        );
 
-            if exercise_enabled {
-                let last = *reminder_state.last_exercise.lock().unwrap();
-                if now.duration_since(last) >= Duration::from_secs(exercise_interval * 60) {
-                    send_reminder_notification(
-                        &handle,
-                        "Exercise Break! 💪",
-                        "Time for a quick exercise break! Move your body, refresh your mind.",
-                    );
-                    *reminder_state.last_exercise.lock().unwrap() = now;
-                }
-            }
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('day_rollover_hour', '4')",
+            [],
+        )
+        .unwrap();
+        let dates = active_dates(&conn).unwrap();
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        assert_eq!(dates, vec![day(1), day(2)]);
+        assert_eq!(streaks::compute_streaks(&dates, day(3)), (2, 2));
+
+        let two_am =
+            chrono::NaiveDateTime::parse_from_str("2024-03-03 02:00:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap();
+        assert_eq!(streaks::effective_date(two_am, 4), day(2));
+        assert_eq!(streaks::effective_date(two_am, 0), day(3));
+
+        // Day buckets agree with the streak: the 2 AM log belongs to the 2nd
+        assert_eq!(xp_sources(&conn, "2024-03-02").unwrap().len(), 1);
+        assert!(xp_sources(&conn, "2024-03-03").unwrap().is_empty());
+        let march = month_summary(&conn, 2024, 3).unwrap();
+        assert_eq!(march[1].count, 1);
+        assert_eq!(march[2].count, 0);
+        assert_eq!(
+            first_log_date(&conn).unwrap().as_deref(),
+            Some("2024-03-01")
+        );
+        let days: Vec<String> = heaviest_days(&conn, 5)
+            .unwrap()
+            .into_iter()
+            .map(|d| d.date)
+            .collect();
+        assert_eq!(days, ["2024-03-01", "2024-03-02"]);
+    }
+
+    #[test]
+    fn test_merge_exercises_combines_history() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let push_ups = setup_exercise(&conn, "Push-ups", 10);
+        log_exercise_at_time(&conn, pushups, 10, &days_ago(2)).unwrap();
+        log_exercise_at_time(&conn, push_ups, 5, &days_ago(1)).unwrap();
+        log_exercise_at_time(&conn, push_ups, 5, &local_timestamp(&conn)).unwrap();
+
+        assert!(merge_exercise_into(&conn, pushups, pushups).is_err());
+
+        let merged = merge_exercise_into(&conn, push_ups, pushups).unwrap();
+        assert_eq!(merged.total_xp, 200);
+        assert_eq!(merged.current_level, level_from_xp(200));
+
+        let log_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM exercise_logs WHERE exercise_id = ?",
+                params![pushups],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(log_count, 3);
+        assert!(get_exercise_by_id(&conn, push_ups).is_err());
+    }
+
+    #[test]
+    fn test_run_routine_logs_every_step() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 5);
+
+        let steps = vec![
+            RoutineStep {
+                exercise_id: pushups,
+                reps: 20,
+            },
+            RoutineStep {
+                exercise_id: squats,
+                reps: 30,
+            },
+        ];
+        let id = insert_routine(&conn, "Morning Routine", &steps).unwrap();
+        assert!(insert_routine(&conn, "Empty", &[]).is_err());
+
+        let routines = list_routines(&conn).unwrap();
+        assert_eq!(routines.len(), 1);
+        assert_eq!(routines[0].steps.len(), 2);
+        assert_eq!(routines[0].steps[1].exercise_id, squats);
+
+        let result = run_routine_now(&conn, id).unwrap();
+        assert_eq!(result.routine_name, "Morning Routine");
+        assert_eq!(result.sets_logged, 2);
+        assert_eq!(result.xp_earned, 350);
+        assert_eq!(result.level_ups.len(), 2);
+
+        let log_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM exercise_logs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(log_count, 2);
+        assert_eq!(get_exercise_by_id(&conn, pushups).unwrap().total_xp, 200);
+        assert_eq!(get_exercise_by_id(&conn, squats).unwrap().total_xp, 150);
+    }
+
+    #[test]
+    fn test_streak_history_splits_on_gaps() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        for n in [9, 8, 5, 4, 3, 0] {
+            insert_log(&conn, id, 10, 100, &days_ago(n));
+        }
+        // Two logs on one day still count once
+        insert_log(&conn, id, 10, 100, &days_ago(0));
+
+        let runs = streak_history(&conn).unwrap();
+        let days: Vec<i32> = runs.iter().map(|run| run.days).collect();
+        assert_eq!(days, vec![2, 3, 1]);
+        assert_eq!(runs[0].start_date, days_ago(9)[..10]);
+        assert_eq!(runs[1].end_date, days_ago(3)[..10]);
+        assert_eq!(runs[2].start_date, days_ago(0)[..10]);
+    }
+
+    #[test]
+    fn test_render_template_placeholders() {
+        let ctx: std::collections::HashMap<&str, String> = [
+            ("exercise", "Pushups".to_string()),
+            ("reps", "15".to_string()),
+            ("streak", "4".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            render_template("{reps} {exercise} keeps your {streak}-day streak!", &ctx),
+            "15 Pushups keeps your 4-day streak!"
+        );
+        // Unknown placeholders, missing values and stray braces stay literal
+        assert_eq!(
+            render_template("{mood} {exercise} at Lv{level} {", &ctx),
+            "{mood} Pushups at Lv{level} {"
+        );
+        assert_eq!(render_template("{{exercise}}", &ctx), "{Pushups}");
+        assert_eq!(
+            render_template(DEFAULT_REMINDER_TEMPLATE, &ctx),
+            DEFAULT_REMINDER_TEMPLATE
+        );
+    }
+
+    #[test]
+    fn test_reminder_template_context() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        assert!(!reminder_template_context(&conn).contains_key("exercise"));
+
+        let id = setup_exercise(&conn, "Squats", 5);
+        insert_log(&conn, id, 25, 125, &days_ago(0));
+        let ctx = reminder_template_context(&conn);
+        assert_eq!(ctx["exercise"], "Squats");
+        assert_eq!(ctx["reps"], "25");
+        assert_eq!(ctx["level"], "1");
+    }
 
-            // Drop the connection lock before sleeping
-            drop(conn);
+    #[test]
+    fn test_reminders_paused_until_boundary() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let at = |time: &str| {
+            chrono::NaiveDateTime::parse_from_str(
+                &format!("2026-10-16 {}", time),
+                "%Y-%m-%d %H:%M:%S",
+            )
+            .unwrap()
+        };
+
+        pause_reminders(&conn, 60, at("09:00:00")).unwrap();
+        let until = parse_paused_until(&get_setting_value(&conn, "reminders_paused_until", ""));
+        assert_eq!(until, Some(at("10:00:00")));
+
+        assert!(reminders_paused(until, at("09:59:59")));
+        assert!(!reminders_paused(until, at("10:00:00")));
+        assert!(!reminders_paused(None, at("09:30:00")));
+
+        let status = reminder_status(&conn, at("09:15:00"));
+        assert_eq!(status.paused_until.as_deref(), Some("2026-10-16 10:00:00"));
+        assert_eq!(status.minutes_remaining, 45);
+        assert!(reminder_status(&conn, at("10:00:00"))
+            .paused_until
+            .is_none());
+
+        // Zero minutes resumes right away
+        pause_reminders(&conn, 0, at("09:15:00")).unwrap();
+        assert!(reminder_status(&conn, at("09:15:00"))
+            .paused_until
+            .is_none());
+
+        // Huge pauses are refused instead of overflowing the timestamp
+        pause_reminders(&conn, MAX_PAUSE_MINUTES, at("09:15:00")).unwrap();
+        assert!(pause_reminders(&conn, MAX_PAUSE_MINUTES + 1, at("09:15:00")).is_err());
+        assert!(pause_reminders(&conn, i64::MAX, at("09:15:00")).is_err());
+        assert_eq!(
+            parse_paused_until(&get_setting_value(&conn, "reminders_paused_until", "")),
+            Some(at("09:15:00") + chrono::Duration::minutes(MAX_PAUSE_MINUTES))
+        );
+    }
+
+    #[test]
+    fn test_xp_decay_applied_on_read() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        log_exercise_at_time(&conn, id, 100, &days_ago(10)).unwrap();
+        assert!(levels::xp_decay_config(&conn).is_none());
+
+        for (key, value) in [
+            ("xp_decay_enabled", "true"),
+            ("xp_decay_grace_days", "7"),
+            ("xp_decay_percent_per_day", "10"),
+            ("xp_decay_allow_delevel", "true"),
+        ] {
+            conn.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+                params![key, value],
+            )
+            .unwrap();
         }
-    });
-}
+        assert!(levels::xp_decay_config(&conn).is_some());
+
+        // Three idle days past the grace period: 1000 * 0.9^3, wherever it's read from
+        let exercise = get_exercise_by_id(&conn, id).unwrap();
+        assert_eq!(exercise.total_xp, 729);
+        assert_eq!(exercise.current_level, level_from_xp(729));
+        assert_eq!(list_exercises(&conn).unwrap()[0].total_xp, 729);
+        let stats = user_stats(&conn).unwrap();
+        assert_eq!(stats.total_xp, 729);
+        assert_eq!(stats.total_level, level_from_xp(729));
+        assert_eq!(dashboard_snapshot(&conn).unwrap().stats.total_xp, 729);
+        // Exports keep the stored total, so a round trip doesn't decay twice
+        let export = data_io::build_export(&conn).unwrap();
+        assert_eq!(export.exercises[0].total_xp, 1000);
+        data_io::apply_import(&conn, &export, None).unwrap();
+        assert_eq!(get_exercise_by_id(&conn, id).unwrap().total_xp, 729);
+
+        // The stored total is left alone until the next log
+        let stored: i64 = conn
+            .query_row(
+                "SELECT total_xp FROM exercises WHERE id = ?",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(stored, 1000);
 
-fn send_reminder_notification(app_handle: &AppHandle, title: &str, body: &str) {
-    use tauri_plugin_notification::NotificationExt;
-    let _ = app_handle
-        .notification()
-        .builder()
-        .title(title)
-        .body(body)
-        .show();
-}
+        // ...which builds on the decayed value rather than the full one
+        let result = log_exercise_at_time(&conn, id, 1, &local_timestamp(&conn)).unwrap();
+        assert_eq!(get_exercise_by_id(&conn, id).unwrap().total_xp, 739);
+        assert_eq!(result.new_exercise_level, level_from_xp(739));
+    }
 
-// ============ Export/Import Data ============
+    #[test]
+    fn test_dashboard_snapshot_matches_individual_queries() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        setup_exercise(&conn, "Squats", 5);
+        let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        log_exercise_at_time(&conn, pushups, 20, &now).unwrap();
+
+        let snapshot = dashboard_snapshot(&conn).unwrap();
+        let stats = user_stats(&conn).unwrap();
+        assert_eq!(snapshot.stats.total_xp, stats.total_xp);
+        assert_eq!(snapshot.stats.total_level, stats.total_level);
+        assert_eq!(snapshot.stats.current_streak, stats.current_streak);
+        assert_eq!(snapshot.stats.exercise_count, 2);
+
+        let exercises = list_exercises(&conn).unwrap();
+        let ids: Vec<i64> = snapshot.exercises.iter().map(|e| e.id).collect();
+        assert_eq!(ids, exercises.iter().map(|e| e.id).collect::<Vec<_>>());
+
+        let unlocked: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM achievements WHERE unlocked_at IS NOT NULL",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(snapshot.unlocked_achievements, unlocked);
+        assert_eq!(snapshot.today_xp, 200);
+        assert_eq!(snapshot.today_xp, get_today_xp(&conn));
+        assert_eq!(snapshot.daily_goal_xp, get_daily_goal_xp(&conn));
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ExportData {
-    pub version: String,
-    pub exported_at: String,
-    pub exercises: Vec<Exercise>,
-    pub exercise_logs: Vec<ExerciseLog>,
-    pub user_stats: UserStats,
-    pub achievements: Vec<Achievement>,
-    pub settings: Settings,
-}
+    #[test]
+    fn test_hex_color_validation() {
+        assert!(is_valid_hex_color("#1a2B3c"));
+        assert!(is_valid_hex_color("#000000"));
+        assert!(!is_valid_hex_color("1a2b3c"));
+        assert!(!is_valid_hex_color("#1a2b3"));
+        assert!(!is_valid_hex_color("#1a2b3g"));
+        assert!(!is_valid_hex_color("#1a2b3c4"));
+        assert!(!is_valid_hex_color("#ääää"));
+    }
 
-#[tauri::command]
-fn export_data(state: State<DbState>) -> Result<String, String> {
-    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    #[test]
+    fn test_exercise_color_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        assert!(get_exercise_by_id(&conn, id).unwrap().color.is_none());
 
-    // Get all exercises
-    let mut stmt = conn
-        .prepare("SELECT id, name, xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1), icon, created_at FROM exercises")
-        .map_err(|e| e.to_string())?;
-    let exercises: Vec<Exercise> = stmt
-        .query_map([], |row| {
-            Ok(Exercise {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                xp_per_rep: row.get(2)?,
-                total_xp: row.get(3)?,
-                current_level: row.get(4)?,
-                icon: row.get(5)?,
-                created_at: row.get(6)?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        update_exercise_color(&conn, id, Some("#ff8800")).unwrap();
+        assert_eq!(
+            get_exercise_by_id(&conn, id).unwrap().color.as_deref(),
+            Some("#FF8800")
+        );
+        assert!(update_exercise_color(&conn, id, Some("orange")).is_err());
+        assert!(update_exercise_color(&conn, 999, Some("#FF8800")).is_err());
 
-    // Get all logs
-    let mut stmt = conn
-        .prepare("SELECT id, exercise_id, reps, xp_earned, logged_at FROM exercise_logs")
-        .map_err(|e| e.to_string())?;
-    let exercise_logs: Vec<ExerciseLog> = stmt
-        .query_map([], |row| {
-            Ok(ExerciseLog {
-                id: row.get(0)?,
-                exercise_id: row.get(1)?,
-                reps: row.get(2)?,
-                xp_earned: row.get(3)?,
-                logged_at: row.get(4)?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+        update_exercise_color(&conn, id, None).unwrap();
+        assert!(get_exercise_by_id(&conn, id).unwrap().color.is_none());
+    }
 
-    // Get stats
-    let (total_xp, total_level, exercise_count): (i64, i32, i32) = conn
-        .query_row(
-            "SELECT COALESCE(SUM(total_xp), 0), COALESCE(SUM(current_level), 0), COUNT(*) FROM exercises",
+    #[test]
+    fn test_goal_streak_resets_on_missed_goal() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+
+        // Goal is 500: days 5 and 4 meet it, day 3 falls short, days 2..0 meet it
+        insert_log(&conn, id, 50, 500, &days_ago(5));
+        insert_log(&conn, id, 60, 600, &days_ago(4));
+        insert_log(&conn, id, 20, 200, &days_ago(3));
+        insert_log(&conn, id, 50, 500, &days_ago(2));
+        insert_log(&conn, id, 30, 300, &days_ago(1));
+        insert_log(&conn, id, 30, 300, &days_ago(1));
+        insert_log(&conn, id, 70, 700, &days_ago(0));
+        assert_eq!(goal_streak(&conn).unwrap(), 3);
+        assert_eq!(user_stats(&conn).unwrap().goal_streak, 3);
+
+        // Today's shortfall doesn't break the streak until the day is over
+        conn.execute(
+            "UPDATE exercise_logs SET xp_earned = 100 WHERE logged_at = ?",
+            params![days_ago(0)],
+        )
+        .unwrap();
+        assert_eq!(goal_streak(&conn).unwrap(), 2);
+
+        // Raising the goal today leaves past days judged by the old goal
+        let today = days_ago(0)[..10].to_string();
+        record_daily_goal_change(&conn, 500, 1000, &today).unwrap();
+        conn.execute(
+            "UPDATE settings SET value = '1000' WHERE key = 'daily_goal_xp'",
             [],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
         )
-        .unwrap_or((0, 0, 0));
+        .unwrap();
+        assert_eq!(goal_streak(&conn).unwrap(), 2);
 
-    let (current_streak, longest_streak, last_exercise_date): (i32, i32, Option<String>) = conn
-        .query_row(
-            "SELECT current_streak, longest_streak, last_exercise_date FROM user_stats WHERE id = 1",
+        // A raised goal applied to past days would have broken it
+        conn.execute("DELETE FROM daily_goal_history", []).unwrap();
+        assert_eq!(goal_streak(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_import_keep_higher_retains_higher_local_xp() {
+        let backup = Connection::open_in_memory().unwrap();
+        init_database(&backup).unwrap();
+        let pushups = setup_exercise(&backup, "Pushups", 10);
+        let squats = setup_exercise(&backup, "Squats", 5);
+        let lunges = setup_exercise(&backup, "Lunges", 5);
+        log_exercise_at_time(&backup, pushups, 10, &days_ago(3)).unwrap();
+        log_exercise_at_time(&backup, squats, 100, &days_ago(3)).unwrap();
+        log_exercise_at_time(&backup, lunges, 20, &days_ago(3)).unwrap();
+        let data: ExportData = serde_json::from_str(&build_export_json(&backup).unwrap()).unwrap();
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let local_pushups = setup_exercise(&conn, "Pushups", 10);
+        let local_squats = setup_exercise(&conn, "squats", 5);
+        log_exercise_at_time(&conn, local_pushups, 50, &days_ago(1)).unwrap();
+        log_exercise_at_time(&conn, local_squats, 10, &days_ago(1)).unwrap();
+
+        import_keep_higher(&conn, &data).unwrap();
+
+        // Local pushups (500 XP) beat the backup's 100 XP
+        let kept = get_exercise_by_id(&conn, local_pushups).unwrap();
+        assert_eq!(kept.total_xp, 500);
+        // The backup's squats (500 XP) beat the local 50 XP, history included
+        let replaced = get_exercise_by_id(&conn, local_squats).unwrap();
+        assert_eq!(replaced.total_xp, 500);
+        assert_eq!(replaced.current_level, level_from_xp(500));
+        let squat_logs: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM exercise_logs WHERE exercise_id = ? AND reps = 100",
+                params![local_squats],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(squat_logs, 1);
+        // Exercises only in the backup are added
+        let lunges_xp: i64 = conn
+            .query_row(
+                "SELECT total_xp FROM exercises WHERE name = 'Lunges'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(lunges_xp, 100);
+        let log_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM exercise_logs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(log_count, 3);
+    }
+
+    #[test]
+    fn test_replay_achievements_restores_cleared_unlocks() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 5);
+        // 120 pushups on one day two weeks ago, then a 7-day streak of squats
+        insert_log(
+            &conn,
+            pushups,
+            60,
+            600,
+            &format!("{} 06:30:00", &days_ago(14)[..10]),
+        );
+        insert_log(&conn, pushups, 60, 600, &days_ago(14));
+        for n in 0..7 {
+            insert_log(&conn, squats, 20, 100, &days_ago(n));
+        }
+        conn.execute("UPDATE achievements SET unlocked_at = NULL", [])
+            .unwrap();
+        // A stale unlock the data doesn't support is dropped
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = '2020-01-01 00:00:00' WHERE key = 'month_streak'",
             [],
-            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
         )
-        .unwrap_or((0, 0, None));
+        .unwrap();
+        // A still-valid unlock keeps its original date
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = '2021-05-05 10:00:00' WHERE key = 'first_exercise'",
+            [],
+        )
+        .unwrap();
 
-    let user_stats = UserStats {
-        total_xp,
-        total_level,
-        current_streak,
-        longest_streak,
-        last_exercise_date,
-        exercise_count,
-    };
+        let keys = replay_achievements(&conn).unwrap();
+        for expected in [
+            "first_exercise",
+            "week_streak",
+            "hundred_pushups",
+            "early_bird",
+        ] {
+            assert!(keys.contains(&expected.to_string()), "missing {}", expected);
+        }
+        for unexpected in [
+            "month_streak",
+            "variety",
+            "night_owl",
+            "thousand_reps",
+            "skill_10",
+        ] {
+            assert!(
+                !keys.contains(&unexpected.to_string()),
+                "unexpected {}",
+                unexpected
+            );
+        }
+        let unlocked_at: String = conn
+            .query_row(
+                "SELECT unlocked_at FROM achievements WHERE key = 'first_exercise'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(unlocked_at, "2021-05-05 10:00:00");
+    }
 
-    // Get achievements
-    let mut stmt = conn
-        .prepare("SELECT id, key, name, description, icon, unlocked_at FROM achievements")
-        .map_err(|e| e.to_string())?;
-    let achievements: Vec<Achievement> = stmt
-        .query_map([], |row| {
-            Ok(Achievement {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                name: row.get(2)?,
-                description: row.get(3)?,
-                icon: row.get(4)?,
-                unlocked_at: row.get(5)?,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+    #[test]
+    fn test_weighted_xp_scaling() {
+        assert_eq!(weighted_xp(100, None, true), 100);
+        assert_eq!(weighted_xp(100, Some(20.0), true), 120);
+        assert_eq!(weighted_xp(100, Some(20.0), false), 100);
+        assert_eq!(weighted_xp(100, Some(150.0), true), 250);
 
-    // Get settings
-    let get_setting = |key: &str, default: &str| -> String {
-        conn.query_row(
-            "SELECT value FROM settings WHERE key = ?",
-            params![key],
-            |row| row.get(0),
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Squats", 5);
+        let result = log_weighted_set(&conn, id, 10, Some(40.0), &days_ago(1)).unwrap();
+        assert_eq!(result.result.xp_earned, 70);
+        assert!(log_weighted_set(&conn, id, 10, Some(-5.0), &days_ago(1)).is_err());
+
+        let weight: Option<f64> = conn
+            .query_row(
+                "SELECT weight FROM exercise_logs WHERE exercise_id = ?",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(weight, Some(40.0));
+    }
+
+    #[test]
+    fn test_weight_pr_detection() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Deadlift", 5);
+
+        let first = log_weighted_set(&conn, id, 5, Some(60.0), &days_ago(3)).unwrap();
+        assert!(first.new_weight_pr);
+        let lighter = log_weighted_set(&conn, id, 12, Some(50.0), &days_ago(2)).unwrap();
+        assert!(!lighter.new_weight_pr);
+        let bodyweight = log_weighted_set(&conn, id, 20, None, &days_ago(2)).unwrap();
+        assert!(!bodyweight.new_weight_pr);
+        let heavier = log_weighted_set(&conn, id, 3, Some(70.0), &days_ago(1)).unwrap();
+        assert!(heavier.new_weight_pr);
+
+        let sets = heaviest_sets(&conn).unwrap();
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].weight, 70.0);
+        assert_eq!(sets[0].reps, 3);
+    }
+
+    #[test]
+    fn test_compare_periods() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        insert_log(&conn, id, 10, 100, "2026-09-02 08:00:00");
+        insert_log(&conn, id, 20, 200, "2026-09-02 18:00:00");
+        insert_log(&conn, id, 30, 300, "2026-09-20 08:00:00");
+        insert_log(&conn, id, 15, 150, "2026-10-05 08:00:00");
+
+        let comparison = compare_periods(
+            &conn,
+            ("2026-09-01", "2026-09-30"),
+            ("2026-10-01", "2026-10-31"),
         )
-        .unwrap_or_else(|_| default.to_string())
-    };
+        .unwrap();
+        assert_eq!(comparison.first.xp, 600);
+        assert_eq!(comparison.first.reps, 60);
+        assert_eq!(comparison.first.sessions, 3);
+        assert_eq!(comparison.first.active_days, 2);
+        assert_eq!(comparison.second.xp, 150);
+        assert_eq!(comparison.xp_delta, -450);
+        assert_eq!(comparison.active_days_delta, -1);
+        assert_eq!(comparison.overlap_days, 0);
+
+        // An empty second range is all zeros
+        let empty = compare_periods(
+            &conn,
+            ("2026-09-01", "2026-09-30"),
+            ("2026-08-01", "2026-08-31"),
+        )
+        .unwrap();
+        assert_eq!(empty.second.xp, 0);
+        assert_eq!(empty.second.sessions, 0);
+        assert_eq!(empty.sessions_delta, -3);
+
+        let overlapping = compare_periods(
+            &conn,
+            ("2026-09-01", "2026-09-20"),
+            ("2026-09-15", "2026-10-05"),
+        )
+        .unwrap();
+        assert_eq!(overlapping.overlap_days, 6);
+        assert_eq!(overlapping.second.xp, 450);
+
+        assert!(compare_periods(
+            &conn,
+            ("2026-09-30", "2026-09-01"),
+            ("2026-10-01", "2026-10-31")
+        )
+        .is_err());
+        assert!(compare_periods(
+            &conn,
+            ("2026-09-01", "2026-09-30"),
+            ("october", "2026-10-31")
+        )
+        .is_err());
+    }
 
-    let settings = Settings {
-        reminder_enabled: get_setting("reminder_enabled", "true") == "true",
-        reminder_interval_minutes: get_setting("reminder_interval_minutes", "120")
-            .parse()
-            .unwrap_or(120),
-        sound_enabled: get_setting("sound_enabled", "true") == "true",
-        daily_goal_xp: get_setting("daily_goal_xp", "500").parse().unwrap_or(500),
-        theme_mode: Some(get_setting("theme_mode", "dark")),
-    };
+    #[test]
+    fn test_select_data_dir_falls_back_when_unwritable() {
+        let primary = PathBuf::from("/locked/AppData/GeekFit");
+        let documents = PathBuf::from("/home/me/Documents/GeekFit");
+        let temp = PathBuf::from("/tmp/GeekFit");
+        let fallbacks = vec![documents.clone(), temp.clone()];
+
+        assert_eq!(
+            select_data_dir(&primary, &fallbacks, |_| true),
+            Some(primary.clone())
+        );
+        assert_eq!(
+            select_data_dir(&primary, &fallbacks, |dir| dir != primary.as_path()),
+            Some(documents.clone())
+        );
+        assert_eq!(
+            select_data_dir(&primary, &fallbacks, |dir| dir == temp.as_path()),
+            Some(temp.clone())
+        );
+        assert_eq!(select_data_dir(&primary, &fallbacks, |_| false), None);
+    }
 
-    let export_data = ExportData {
-        version: "1.0.0".to_string(),
-        exported_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-        exercises,
-        exercise_logs,
-        user_stats,
-        achievements,
-        settings,
-    };
+    #[test]
+    fn test_moving_average() {
+        let values = [7.0, 0.0, 14.0, 7.0, 7.0, 7.0, 7.0, 14.0];
+        let averages = moving_average(&values, 7);
+        assert_eq!(averages[0], 7.0);
+        assert_eq!(averages[1], 3.5);
+        assert_eq!(averages[2], 7.0);
+        assert_eq!(averages[6], 7.0);
+        // Day 8 drops the first 7 and adds 14
+        assert_eq!(averages[7], 8.0);
+        assert!(moving_average(&[], 7).is_empty());
+    }
 
-    serde_json::to_string_pretty(&export_data).map_err(|e| e.to_string())
-}
+    #[test]
+    fn test_trend_slope_labels() {
+        assert_eq!(linear_slope(&[1.0, 2.0, 3.0, 4.0]), 1.0);
+        assert_eq!(linear_slope(&[5.0, 5.0, 5.0]), 0.0);
+        assert_eq!(linear_slope(&[3.0]), 0.0);
+
+        assert_eq!(trend_label(0.5, 10.0), "improving");
+        assert_eq!(trend_label(-0.5, 10.0), "declining");
+        assert_eq!(trend_label(0.2, 10.0), "steady");
+        assert_eq!(trend_label(-0.2, 10.0), "steady");
+        // The same slope is noise at a higher volume
+        assert_eq!(trend_label(0.5, 100.0), "steady");
+        assert_eq!(trend_label(1.0, 0.0), "steady");
+    }
 
-#[tauri::command]
-fn import_data(state: State<DbState>, json_data: String) -> Result<(), String> {
-    let data: ExportData =
-        serde_json::from_str(&json_data).map_err(|e| format!("Invalid data format: {}", e))?;
-    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    #[test]
+    fn test_exercise_trend_handles_sparse_data() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        let today = chrono::Local::now().date_naive();
 
-    // Clear existing data
-    conn.execute_batch(
-        "
-        DELETE FROM exercise_logs;
-        DELETE FROM exercises;
-        UPDATE user_stats SET current_streak = 0, longest_streak = 0, last_exercise_date = NULL WHERE id = 1;
-        UPDATE achievements SET unlocked_at = NULL;
-        ",
-    )
-    .map_err(|e| e.to_string())?;
+        let empty = exercise_trend(&conn, id, 14, today).unwrap();
+        assert_eq!(empty.points.len(), 14);
+        assert_eq!(empty.trend, "steady");
+
+        for (n, reps) in [(13, 5), (9, 10), (5, 20), (2, 30), (0, 40)] {
+            insert_log(&conn, id, reps, reps * 10, &days_ago(n));
+        }
+        let trend = exercise_trend(&conn, id, 14, today).unwrap();
+        assert_eq!(trend.points[0].reps, 5);
+        assert_eq!(trend.points[1].reps, 0);
+        assert_eq!(trend.points[13].reps, 40);
+        assert_eq!(trend.points[13].date, today.format("%Y-%m-%d").to_string());
+        assert!(trend.slope > 0.0);
+        assert_eq!(trend.trend, "improving");
+
+        assert!(exercise_trend(&conn, id, 1, today).is_err());
+        assert!(exercise_trend(&conn, 999, 14, today).is_err());
+    }
 
-    // Import exercises
-    for exercise in &data.exercises {
+    #[test]
+    fn test_default_quick_log_reps_by_category() {
+        assert_eq!(default_quick_log_reps("Cardio"), &[10, 25, 50]);
+        assert_eq!(default_quick_log_reps("Upper Body"), &[5, 10, 20]);
+        assert_eq!(default_quick_log_reps("Lower Body"), &[5, 10, 20]);
+        assert_eq!(default_quick_log_reps("Stretches"), &[5, 10]);
+        assert_eq!(default_quick_log_reps("Unknown"), &[5, 10, 20]);
+        // Cardio defaults run higher than strength ones
+        assert!(default_quick_log_reps("Cardio")[2] > default_quick_log_reps("Upper Body")[2]);
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Burpees", 15);
         conn.execute(
-            "INSERT INTO exercises (id, name, xp_per_rep, total_xp, current_level, icon, created_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
-            params![
-                exercise.id,
-                exercise.name,
-                exercise.xp_per_rep,
-                exercise.total_xp,
-                exercise.current_level,
-                exercise.icon,
-                exercise.created_at
-            ],
+            "UPDATE exercises SET category = 'Cardio' WHERE id = ?",
+            params![id],
         )
-        .map_err(|e| e.to_string())?;
+        .unwrap();
+        let categories = exercise_categories(&conn);
+        assert_eq!(categories.get(&id).map(|c| c.as_str()), Some("Cardio"));
+    }
+
+    #[test]
+    fn test_consistency_score_in_stats() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        assert_eq!(user_stats(&conn).unwrap().consistency_score, 0);
+
+        for n in 0..28 {
+            insert_log(&conn, id, 10, 100, &days_ago(n));
+        }
+        // Logs outside the window don't count
+        insert_log(&conn, id, 10, 100, &days_ago(40));
+        let today = chrono::Local::now().date_naive();
+        assert_eq!(consistency_score_for(&conn, today).unwrap(), 100);
+        assert_eq!(user_stats(&conn).unwrap().consistency_score, 100);
     }
 
-    // Import exercise logs
-    for log in &data.exercise_logs {
+    #[test]
+    fn test_user_profile_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let profile = user_profile(&conn);
+        assert_eq!(profile.user_name, "");
+        assert_eq!(profile.avatar, "");
+
+        save_user_profile(&conn, "  Ada  ", "fitness_center").unwrap();
+        let profile = user_profile(&conn);
+        assert_eq!(profile.user_name, "Ada");
+        assert_eq!(profile.avatar, "fitness_center");
+        assert_eq!(dashboard_snapshot(&conn).unwrap().profile.user_name, "Ada");
+
+        assert!(save_user_profile(&conn, &"x".repeat(41), "").is_err());
+        assert_eq!(user_profile(&conn).user_name, "Ada");
+    }
+
+    #[test]
+    fn test_xp_sources_running_total() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 5);
+        insert_log(&conn, squats, 20, 100, "2026-03-02 18:00:00");
+        insert_log(&conn, pushups, 10, 100, "2026-03-02 08:00:00");
+        insert_log(&conn, pushups, 5, 50, "2026-03-02 12:30:00");
+        insert_log(&conn, pushups, 30, 300, "2026-03-03 09:00:00");
+
+        let sources = xp_sources(&conn, "2026-03-02").unwrap();
+        let names: Vec<&str> = sources.iter().map(|s| s.exercise_name.as_str()).collect();
+        assert_eq!(names, vec!["Pushups", "Pushups", "Squats"]);
+
+        let mut cumulative = 0;
+        for source in &sources {
+            cumulative += source.xp_earned as i64;
+            assert_eq!(source.running_xp, cumulative);
+        }
+        assert_eq!(cumulative, 250);
+
+        assert!(xp_sources(&conn, "2026-03-04").unwrap().is_empty());
+        assert!(xp_sources(&conn, "03/02/2026").is_err());
+    }
+
+    #[test]
+    fn test_log_rejects_out_of_range_reps() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        let ts = days_ago(1);
+
+        for reps in [0, -3, 10_001] {
+            assert!(log_exercise_at_time(&conn, id, reps, &ts).is_err());
+        }
+        let logs: i64 = conn
+            .query_row("SELECT COUNT(*) FROM exercise_logs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(logs, 0);
+
         conn.execute(
-            "INSERT INTO exercise_logs (id, exercise_id, reps, xp_earned, logged_at) VALUES (?, ?, ?, ?, ?)",
-            params![log.id, log.exercise_id, log.reps, log.xp_earned, log.logged_at],
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('min_reps', '5')",
+            [],
         )
-        .map_err(|e| e.to_string())?;
+        .unwrap();
+        assert!(log_exercise_at_time(&conn, id, 4, &ts).is_err());
+        assert!(log_exercise_at_time(&conn, id, 5, &ts).is_ok());
     }
 
-    // Update user stats
-    conn.execute(
-        "UPDATE user_stats SET current_streak = ?, longest_streak = ?, last_exercise_date = ? WHERE id = 1",
-        params![
-            data.user_stats.current_streak,
-            data.user_stats.longest_streak,
-            data.user_stats.last_exercise_date
-        ],
-    )
-    .map_err(|e| e.to_string())?;
+    #[test]
+    fn test_comeback_after_long_gap() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+        assert!(is_comeback(Some("2026-03-05"), today));
+        assert!(!is_comeback(Some("2026-03-14"), today));
+        assert!(!is_comeback(Some("2026-03-08"), today));
+        assert!(!is_comeback(None, today));
+
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        assert!(
+            !log_exercise_at_time(&conn, id, 10, &days_ago(21))
+                .unwrap()
+                .comeback
+        );
+        assert!(
+            log_exercise_at_time(&conn, id, 10, &days_ago(11))
+                .unwrap()
+                .comeback
+        );
+        assert!(
+            !log_exercise_at_time(&conn, id, 10, &days_ago(10))
+                .unwrap()
+                .comeback
+        );
+    }
+
+    #[test]
+    fn test_share_card_stats() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        insert_log(&conn, pushups, 10, 100, &days_ago(0));
+        insert_log(&conn, pushups, 5, 50, &days_ago(0));
+        insert_log(&conn, pushups, 10, 100, &days_ago(3));
+        insert_log(&conn, pushups, 10, 100, &days_ago(40));
+
+        let today = chrono::Local::now().date_naive();
+        let stats = share_card_stats(&conn, today).unwrap();
+        assert_eq!(stats.daily_xp.len(), share_card::HEATMAP_DAYS);
+        assert_eq!(stats.daily_xp.last(), Some(&150));
+        assert_eq!(stats.daily_xp[share_card::HEATMAP_DAYS - 4], 100);
+        assert_eq!(stats.daily_xp.iter().sum::<i64>(), 250);
+        assert_eq!(stats.top_exercises.len(), 1);
+        assert!(!share_card::render(&stats).unwrap().is_empty());
+    }
 
-    // Update achievements
-    for achievement in &data.achievements {
-        if achievement.unlocked_at.is_some() {
+    #[test]
+    fn test_week_plan_favors_neglected_categories() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        for (name, category, level) in [
+            ("Pushups", "Upper Body", 60),
+            ("Pullups", "Upper Body", 40),
+            ("Planks", "Core", 1),
+            ("Squats", "Lower Body", 5),
+            ("Burpees", "Cardio", 2),
+        ] {
+            let id = setup_exercise(&conn, name, 5);
             conn.execute(
-                "UPDATE achievements SET unlocked_at = ? WHERE key = ?",
-                params![achievement.unlocked_at, achievement.key],
+                "UPDATE exercises SET category = ?, current_level = ? WHERE id = ?",
+                params![category, level, id],
             )
-            .map_err(|e| e.to_string())?;
+            .unwrap();
         }
-    }
-
-    // Update settings
-    conn.execute(
-        "INSERT OR REPLACE INTO settings (key, value) VALUES ('reminder_enabled', ?)",
-        params![data.settings.reminder_enabled.to_string()],
-    )
-    .map_err(|e| e.to_string())?;
-    conn.execute(
-        "INSERT OR REPLACE INTO settings (key, value) VALUES ('reminder_interval_minutes', ?)",
-        params![data.settings.reminder_interval_minutes.to_string()],
-    )
-    .map_err(|e| e.to_string())?;
-    conn.execute(
-        "INSERT OR REPLACE INTO settings (key, value) VALUES ('sound_enabled', ?)",
-        params![data.settings.sound_enabled.to_string()],
-    )
-    .map_err(|e| e.to_string())?;
-    conn.execute(
-        "INSERT OR REPLACE INTO settings (key, value) VALUES ('daily_goal_xp', ?)",
-        params![data.settings.daily_goal_xp.to_string()],
-    )
-    .map_err(|e| e.to_string())?;
-    if let Some(theme_mode) = &data.settings.theme_mode {
         conn.execute(
-            "INSERT OR REPLACE INTO settings (key, value) VALUES ('theme_mode', ?)",
-            params![theme_mode],
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('rest_days', 'Sun')",
+            [],
         )
-        .map_err(|e| e.to_string())?;
+        .unwrap();
+
+        // 2026-03-02 is a Monday
+        let start = chrono::NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let mut counts: std::collections::HashMap<String, usize> = Default::default();
+        for seed in 0..20 {
+            let plan = recommendations(&conn, start, seed).unwrap();
+            assert_eq!(plan.len(), 7);
+            for day in &plan {
+                assert_eq!(day.rest_day, day.date == "2026-03-08");
+                assert_eq!(day.sets.len(), if day.rest_day { 0 } else { 3 });
+                for set in &day.sets {
+                    *counts.entry(set.category.clone()).or_default() += 1;
+                }
+            }
+        }
+        assert!(counts["Core"] > counts["Upper Body"]);
+        assert!(counts["Cardio"] > counts["Upper Body"]);
+
+        let first = recommendations(&conn, start, 7).unwrap();
+        let again = recommendations(&conn, start, 7).unwrap();
+        let ids = |plan: &[PlannedDay]| -> Vec<i64> {
+            plan.iter()
+                .flat_map(|d| d.sets.iter().map(|s| s.exercise_id))
+                .collect()
+        };
+        assert_eq!(ids(&first), ids(&again));
     }
 
-    Ok(())
-}
-
-#[tauri::command]
-fn reset_all_data(state: State<DbState>) -> Result<(), String> {
-    let conn = state.0.lock().map_err(|e| e.to_string())?;
+    #[test]
+    fn test_add_exercise_pack_skips_duplicates() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        setup_exercise(&conn, "pushups", 10);
 
-    // Clear all data - user must go through onboarding to add exercises again
-    conn.execute_batch(
-        "
-        DELETE FROM exercise_logs;
-        DELETE FROM exercises;
-        UPDATE user_stats SET current_streak = 0, longest_streak = 0, last_exercise_date = NULL WHERE id = 1;
-        UPDATE achievements SET unlocked_at = NULL;
-        ",
-    )
-    .map_err(|e| e.to_string())?;
+        let first = add_exercise_pack(&conn, "strength pack").unwrap();
+        assert_eq!(first.added, 6);
+        assert_eq!(first.skipped, 1);
 
-    Ok(())
-}
+        let second = add_exercise_pack(&conn, "Strength Pack").unwrap();
+        assert_eq!(second.added, 0);
+        assert_eq!(second.skipped, 7);
 
-// ============ System Tray Setup ============
+        let categorized: i32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM exercises WHERE category = 'Lower Body'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(categorized, 3);
+        assert!(add_exercise_pack(&conn, "Powerlifting Pack").is_err());
+    }
 
-fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let open = MenuItem::with_id(app, "open", "Open Dashboard", true, None::<&str>)?;
-    let quick_log_window = MenuItem::with_id(
-        app,
-        "quick_log_window",
-        "Quick Log... (Ctrl+Shift+Alt+G)",
-        true,
-        None::<&str>,
-    )?;
-    let quit = MenuItem::with_id(app, "quit", "Quit GeekFit", true, None::<&str>)?;
+    #[test]
+    fn test_exercises_report_last_logged() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 8);
+        let lunges = setup_exercise(&conn, "Lunges", 10);
+        insert_log(&conn, pushups, 10, 100, "2026-03-01 08:00:00");
+        insert_log(&conn, pushups, 10, 100, "2026-03-04 19:30:00");
+        insert_log(&conn, pushups, 10, 100, "2026-03-02 12:00:00");
+        insert_log(&conn, squats, 10, 80, "2026-02-20 07:15:00");
+
+        let exercises = list_exercises(&conn).unwrap();
+        let last_logged = |id: i64| {
+            exercises
+                .iter()
+                .find(|e| e.id == id)
+                .unwrap()
+                .last_logged
+                .clone()
+        };
+        assert_eq!(last_logged(pushups).as_deref(), Some("2026-03-04 19:30:00"));
+        assert_eq!(last_logged(squats).as_deref(), Some("2026-02-20 07:15:00"));
+        assert_eq!(last_logged(lunges), None);
+        assert_eq!(
+            get_exercise_by_id(&conn, squats)
+                .unwrap()
+                .last_logged
+                .as_deref(),
+            Some("2026-02-20 07:15:00")
+        );
+    }
 
-    // Quick Log submenu with popular exercises
-    // Format: "log_{exercise_id}_{reps}" - we'll parse this in the event handler
+    #[test]
+    fn test_reminder_escalation_state_machine() {
+        let cap = 2;
+        let mut escalation = ReminderEscalation::default();
+
+        // First reminder of the day is normal; each ignored one raises the level to the cap
+        assert_eq!(escalation.fire("2026-03-02", 0, cap), 0);
+        assert_eq!(escalation.fire("2026-03-02", 0, cap), 1);
+        assert_eq!(escalation.fire("2026-03-02", 0, cap), 2);
+        assert_eq!(escalation.fire("2026-03-02", 0, cap), 2);
+        assert_eq!(escalation.level(cap), 2);
+
+        // A log resets it before the next reminder
+        escalation.observe("2026-03-02", 1);
+        assert_eq!(escalation.level(cap), 0);
+        assert_eq!(escalation.fire("2026-03-02", 1, cap), 0);
+        assert_eq!(escalation.fire("2026-03-02", 1, cap), 1);
+
+        // So does a new day, and a zero cap disables escalation
+        assert_eq!(escalation.fire("2026-03-03", 0, cap), 0);
+        assert_eq!(escalation.fire("2026-03-03", 0, 0), 0);
+    }
 
-    // Pushups submenu
-    let pushups_5 = MenuItem::with_id(app, "log_1_5", "5 reps", true, None::<&str>)?;
-    let pushups_10 = MenuItem::with_id(app, "log_1_10", "10 reps", true, None::<&str>)?;
-    let pushups_20 = MenuItem::with_id(app, "log_1_20", "20 reps", true, None::<&str>)?;
-    let pushups_menu = Submenu::with_items(
-        app,
-        "Pushups",
-        true,
-        &[&pushups_5, &pushups_10, &pushups_20],
-    )?;
+    #[test]
+    fn test_escalated_interval() {
+        assert_eq!(escalated_interval(120, 0), 120);
+        assert_eq!(escalated_interval(120, 1), 60);
+        assert_eq!(escalated_interval(120, 2), 30);
+        assert_eq!(escalated_interval(40, 2), 15);
+        assert_eq!(escalated_interval(10, 2), 10);
+    }
 
-    // Squats submenu
-    let squats_5 = MenuItem::with_id(app, "log_8_5", "5 reps", true, None::<&str>)?;
-    let squats_10 = MenuItem::with_id(app, "log_8_10", "10 reps", true, None::<&str>)?;
-    let squats_20 = MenuItem::with_id(app, "log_8_20", "20 reps", true, None::<&str>)?;
-    let squats_menu =
-        Submenu::with_items(app, "Squats", true, &[&squats_5, &squats_10, &squats_20])?;
-
-    // Sit-ups submenu
-    let situps_5 = MenuItem::with_id(app, "log_3_5", "5 reps", true, None::<&str>)?;
-    let situps_10 = MenuItem::with_id(app, "log_3_10", "10 reps", true, None::<&str>)?;
-    let situps_20 = MenuItem::with_id(app, "log_3_20", "20 reps", true, None::<&str>)?;
-    let situps_menu =
-        Submenu::with_items(app, "Sit-ups", true, &[&situps_5, &situps_10, &situps_20])?;
-
-    // Jumping Jacks submenu
-    let jj_10 = MenuItem::with_id(app, "log_14_10", "10 reps", true, None::<&str>)?;
-    let jj_20 = MenuItem::with_id(app, "log_14_20", "20 reps", true, None::<&str>)?;
-    let jj_50 = MenuItem::with_id(app, "log_14_50", "50 reps", true, None::<&str>)?;
-    let jj_menu = Submenu::with_items(app, "Jumping Jacks", true, &[&jj_10, &jj_20, &jj_50])?;
-
-    // Stretches submenu (quick desk stretches)
-    let neck_5 = MenuItem::with_id(app, "log_19_5", "5 reps", true, None::<&str>)?;
-    let neck_10 = MenuItem::with_id(app, "log_19_10", "10 reps", true, None::<&str>)?;
-    let neck_menu = Submenu::with_items(app, "Neck Stretches", true, &[&neck_5, &neck_10])?;
-
-    let wrist_5 = MenuItem::with_id(app, "log_21_5", "5 reps", true, None::<&str>)?;
-    let wrist_10 = MenuItem::with_id(app, "log_21_10", "10 reps", true, None::<&str>)?;
-    let wrist_menu = Submenu::with_items(app, "Wrist Circles", true, &[&wrist_5, &wrist_10])?;
-
-    let shoulder_5 = MenuItem::with_id(app, "log_20_5", "5 reps", true, None::<&str>)?;
-    let shoulder_10 = MenuItem::with_id(app, "log_20_10", "10 reps", true, None::<&str>)?;
-    let shoulder_menu =
-        Submenu::with_items(app, "Shoulder Shrugs", true, &[&shoulder_5, &shoulder_10])?;
+    #[test]
+    fn test_should_send_summary() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        assert!(!should_send_summary(7, 8, "2026-03-01", today));
+        assert!(should_send_summary(8, 8, "2026-03-01", today));
+        assert!(should_send_summary(15, 8, "", today));
+        // At most once per day
+        assert!(!should_send_summary(9, 8, "2026-03-02", today));
+        // Midnight summaries go out on the first wake of the day
+        assert!(should_send_summary(0, 0, "2026-03-01", today));
+    }
 
-    // Stretches parent submenu
-    let stretches_menu = Submenu::with_items(
-        app,
-        "Stretches",
-        true,
-        &[&neck_menu, &wrist_menu, &shoulder_menu],
-    )?;
+    #[test]
+    fn test_daily_summary_text() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 8);
+        insert_log(&conn, pushups, 20, 200, "2026-03-01 08:00:00");
+        insert_log(&conn, pushups, 10, 100, "2026-03-01 12:00:00");
+        insert_log(&conn, squats, 5, 40, "2026-03-01 18:00:00");
+
+        let day = chrono::NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let text = daily_summary_text(&conn, day);
+        assert!(
+            text.starts_with("Yesterday: 2 exercises, +340 XP"),
+            "{}",
+            text
+        );
+        assert!(daily_summary_text(&conn, day.succ_opt().unwrap()).contains("rest day"));
+    }
 
-    let separator1 = PredefinedMenuItem::separator(app)?;
-    let separator2 = PredefinedMenuItem::separator(app)?;
-    let separator3 = PredefinedMenuItem::separator(app)?;
+    #[test]
+    fn test_reminder_picker_skips_opted_out_exercises() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let burpees = setup_exercise(&conn, "Burpees", 15);
+        let stretches = setup_exercise(&conn, "Neck Stretches", 2);
+        insert_log(&conn, stretches, 10, 20, &days_ago(1));
+        assert!(get_exercise_by_id(&conn, burpees).unwrap().remind);
+
+        // Burpees were never logged, so they'd be suggested first
+        update_exercise_reminder(&conn, burpees, false).unwrap();
+        assert!(!get_exercise_by_id(&conn, burpees).unwrap().remind);
+        assert_eq!(
+            suggest_neglected_exercise(&conn, true).unwrap().id,
+            stretches
+        );
+        assert_eq!(
+            reminder_template_context(&conn)
+                .get("exercise")
+                .map(String::as_str),
+            Some("Neck Stretches")
+        );
 
-    // Main Quick Log submenu
-    let quick_log_menu = Submenu::with_items(
-        app,
-        "Quick Log",
-        true,
-        &[
-            &pushups_menu,
-            &squats_menu,
-            &situps_menu,
-            &jj_menu,
-            &separator1,
-            &stretches_menu,
-        ],
-    )?;
+        // Still suggested outside reminders, and still loggable
+        assert_eq!(
+            suggest_neglected_exercise(&conn, false).unwrap().id,
+            burpees
+        );
+        assert!(log_exercise_at_time(&conn, burpees, 5, &local_timestamp(&conn)).is_ok());
 
-    let menu = Menu::with_items(
-        app,
-        &[
-            &open,
-            &quick_log_window,
-            &separator2,
-            &quick_log_menu,
-            &separator3,
-            &quit,
-        ],
-    )?;
+        update_exercise_reminder(&conn, stretches, false).unwrap();
+        assert!(suggest_neglected_exercise(&conn, true).is_none());
+        assert!(update_exercise_reminder(&conn, 999, true).is_err());
+    }
 
-    let _tray = TrayIconBuilder::new()
-        .icon(app.default_window_icon().unwrap().clone())
-        .menu(&menu)
-        .tooltip("GeekFit - Stay fit while coding!")
-        .show_menu_on_left_click(false)
-        .on_menu_event(|app, event| {
-            let event_id = event.id.as_ref();
+    #[test]
+    fn test_estimate_time_to_level() {
+        let target = xp_for_level(99);
+        assert_eq!(estimate_time_to_level(target, 99, 0.0), Some(0));
+        assert_eq!(estimate_time_to_level(target - 100, 99, 30.0), Some(4));
+        assert_eq!(estimate_time_to_level(0, 99, 0.0), None);
+    }
 
-            // Handle quick log events (format: log_{exercise_id}_{reps})
-            if event_id.starts_with("log_") {
-                let parts: Vec<&str> = event_id.split('_').collect();
-                if parts.len() == 3 {
-                    if let (Ok(exercise_id), Ok(reps)) = (parts[1].parse::<i64>(), parts[2].parse::<i32>()) {
-                        // Log the exercise using the database
-                        if let Some(db_state) = app.try_state::<DbState>() {
-                            if let Ok(conn) = db_state.0.lock() {
-                                // Get exercise name for notification
-                                let exercise_name: String = conn
-                                    .query_row(
-                                        "SELECT name FROM exercises WHERE id = ?",
-                                        params![exercise_id],
-                                        |row| row.get(0),
-                                    )
-                                    .unwrap_or_else(|_| "Exercise".to_string());
-
-                                // Get exercise XP info
-                                if let Ok((xp_per_rep, old_xp, old_level)) = conn.query_row::<(i32, i64, i32), _, _>(
-                                    "SELECT xp_per_rep, COALESCE(total_xp, 0), COALESCE(current_level, 1) FROM exercises WHERE id = ?",
-                                    params![exercise_id],
-                                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-                                ) {
-                                    let xp_earned = xp_per_rep * reps;
-                                    let new_xp = old_xp + xp_earned as i64;
-                                    let new_level = level_from_xp(new_xp);
-                                    let leveled_up = new_level > old_level;
-
-                                    // Log the exercise
-                                    let _ = conn.execute(
-                                        "INSERT INTO exercise_logs (exercise_id, reps, xp_earned, logged_at) VALUES (?, ?, ?, datetime('now', 'localtime'))",
-                                        params![exercise_id, reps, xp_earned],
-                                    );
-
-                                    // Update exercise XP and level
-                                    let _ = conn.execute(
-                                        "UPDATE exercises SET total_xp = ?, current_level = ? WHERE id = ?",
-                                        params![new_xp, new_level, exercise_id],
-                                    );
-
-                                    // Update streak
-                                    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-                                    let last_date: Option<String> = conn
-                                        .query_row(
-                                            "SELECT last_exercise_date FROM user_stats WHERE id = 1",
-                                            [],
-                                            |row| row.get(0),
-                                        )
-                                        .unwrap_or(None);
-
-                                    let (current_streak, longest_streak): (i32, i32) = conn
-                                        .query_row(
-                                            "SELECT current_streak, longest_streak FROM user_stats WHERE id = 1",
-                                            [],
-                                            |row| Ok((row.get(0)?, row.get(1)?)),
-                                        )
-                                        .unwrap_or((0, 0));
-
-                                    let new_streak = match &last_date {
-                                        Some(date) => {
-                                            if date == &today {
-                                                current_streak
-                                            } else {
-                                                let yesterday = (chrono::Local::now() - chrono::Duration::days(1))
-                                                    .format("%Y-%m-%d")
-                                                    .to_string();
-                                                if date == &yesterday {
-                                                    current_streak + 1
-                                                } else {
-                                                    1
-                                                }
-                                            }
-                                        }
-                                        None => 1,
-                                    };
-                                    let new_longest = std::cmp::max(new_streak, longest_streak);
-
-                                    let _ = conn.execute(
-                                        "UPDATE user_stats SET current_streak = ?, longest_streak = ?, last_exercise_date = ? WHERE id = 1",
-                                        params![new_streak, new_longest, today],
-                                    );
-
-                                    // Send notification
-                                    let title = if leveled_up {
-                                        format!("Level Up! {} is now Lv{}", exercise_name, new_level)
-                                    } else {
-                                        format!("Logged {} x {}", exercise_name, reps)
-                                    };
-                                    let body = format!("+{} XP | Streak: {} days", xp_earned, new_streak);
-
-                                    // Emit event to frontend to refresh stats
-                                    let _ = app.emit("exercise-logged", ());
-
-                                    // Show system notification
-                                    use tauri_plugin_notification::NotificationExt;
-                                    let _ = app.notification()
-                                        .builder()
-                                        .title(&title)
-                                        .body(&body)
-                                        .show();
-                                }
-                            }
-                        }
-                    }
-                }
-                return;
-            }
+    #[test]
+    fn test_level_99_projection_for_steady_and_inactive_exercises() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let steady = setup_exercise(&conn, "Pushups", 10);
+        let idle = setup_exercise(&conn, "Squats", 10);
+        let today = effective_today(&conn);
 
-            // Handle other menu events
-            match event_id {
-                "open" => {
-                    if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
-                }
-                "quick_log_window" => {
-                    // Emit event to frontend to open quick log dialog
-                    let _ = app.emit("global-quick-log", ());
-                    if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.show();
-                        let _ = window.set_focus();
-                    }
-                }
-                "quit" => {
-                    app.exit(0);
-                }
-                _ => {}
-            }
-        })
-        .on_tray_icon_event(|tray, event| {
-            if let tauri::tray::TrayIconEvent::DoubleClick { .. } = event {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
-        })
-        .build(app)?;
+        // 1,000 XP a day, every day of the window
+        for n in 0..PROJECTION_WINDOW_DAYS {
+            insert_log(&conn, steady, 100, 1000, &days_ago(n));
+        }
+        let remaining = xp_for_level(99) - 30_000;
+        conn.execute(
+            "UPDATE exercises SET total_xp = 30000 WHERE id = ?",
+            params![steady],
+        )
+        .unwrap();
+        // Only logged before the window
+        insert_log(
+            &conn,
+            idle,
+            100,
+            1000,
+            &days_ago(PROJECTION_WINDOW_DAYS + 5),
+        );
 
-    Ok(())
-}
+        let projections = level_99_projections(&conn, today).unwrap();
+        let find = |id: i64| projections.iter().find(|p| p.exercise_id == id).unwrap();
 
-// ============ Global Shortcut Setup ============
+        let expected = today + chrono::Duration::days((remaining as f64 / 1000.0).ceil() as i64);
+        assert_eq!(find(steady).average_daily_xp, 1000.0);
+        assert_eq!(
+            find(steady).projected_date.as_deref(),
+            Some(expected.format("%Y-%m-%d").to_string().as_str())
+        );
+        assert!(expected > today + chrono::Duration::days(365));
 
-#[cfg(not(any(target_os = "android", target_os = "ios")))]
-fn setup_global_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    use tauri_plugin_global_shortcut::{
-        Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState,
-    };
+        assert_eq!(find(idle).average_daily_xp, 0.0);
+        assert_eq!(find(idle).projected_date, None);
+    }
 
-    // Register Ctrl+Shift+Alt+G for quick log
-    let shortcut = Shortcut::new(
-        Some(Modifiers::CONTROL | Modifiers::SHIFT | Modifiers::ALT),
-        Code::KeyG,
-    );
+    #[test]
+    fn test_validate_setting_booleans() {
+        for key in BOOLEAN_SETTINGS {
+            assert!(validate_setting(key, "true").is_ok(), "{}", key);
+            assert!(validate_setting(key, "false").is_ok(), "{}", key);
+            assert!(validate_setting(key, "yes").is_err(), "{}", key);
+            assert!(validate_setting(key, "").is_err(), "{}", key);
+        }
+    }
 
-    // First try to unregister in case it was previously registered
-    let _ = app.global_shortcut().unregister(shortcut);
+    #[test]
+    fn test_validate_setting_integers_in_range() {
+        for (key, min, max) in INTEGER_SETTINGS {
+            assert!(validate_setting(key, &min.to_string()).is_ok(), "{}", key);
+            assert!(validate_setting(key, &max.to_string()).is_ok(), "{}", key);
+            assert!(
+                validate_setting(key, &(min - 1).to_string()).is_err(),
+                "{}",
+                key
+            );
+            assert!(
+                validate_setting(key, &(max + 1).to_string()).is_err(),
+                "{}",
+                key
+            );
+            assert!(validate_setting(key, "abc").is_err(), "{}", key);
+            assert!(validate_setting(key, "1.5").is_err(), "{}", key);
+        }
+        let err = validate_setting("reminder_interval_minutes", "abc").unwrap_err();
+        assert!(err.contains("reminder_interval_minutes"));
+        assert!(validate_setting("daily_summary_hour", "").is_ok());
+        assert!(validate_setting("reminder_interval_minutes", "").is_err());
+    }
 
-    // Register the shortcut with explicit state handling
-    match app
-        .global_shortcut()
-        .on_shortcut(shortcut, |app, _shortcut, event| {
-            // Only trigger on key press, not release
-            if event.state == ShortcutState::Pressed {
-                log::info!("Global shortcut Ctrl+Shift+Alt+G triggered");
+    #[test]
+    fn test_validate_setting_enums_and_free_text() {
+        assert!(validate_setting("xp_decay_percent_per_day", "2.5").is_ok());
+        assert!(validate_setting("xp_decay_percent_per_day", "-1").is_err());
+        assert!(validate_setting("xp_decay_percent_per_day", "lots").is_err());
+
+        assert!(validate_setting("timezone", "Europe/Berlin").is_ok());
+        assert!(validate_setting("timezone", "").is_ok());
+        assert!(validate_setting("timezone", "Mars/Olympus").is_err());
+
+        assert!(validate_setting("rest_days", "Sat, Sun").is_ok());
+        assert!(validate_setting("rest_days", "").is_ok());
+        assert!(validate_setting("rest_days", "Sat,Caturday").is_err());
+
+        assert!(validate_setting("theme_mode", "dracula").is_ok());
+        assert!(validate_setting("theme_mode", "neon").is_err());
+
+        assert!(validate_setting("quick_log_presets", "{}").is_ok());
+        assert!(validate_setting("quick_log_presets", r#"{"1": [10, 25]}"#).is_ok());
+        assert!(validate_setting("quick_log_presets", r#"{"1": [0]}"#).is_err());
+        assert!(validate_setting("quick_log_presets", r#"{"x": [10]}"#).is_err());
+        assert!(validate_setting("quick_log_presets", "[10]").is_err());
+
+        assert!(validate_setting("reminder_weekday_intervals", r#"{"Sat": 240}"#).is_ok());
+        assert!(validate_setting("reminder_weekday_intervals", r#"{"Caturday": 60}"#).is_err());
+        assert!(validate_setting("reminder_weekday_intervals", r#"{"Sat": -5}"#).is_err());
+        assert!(validate_setting("reminder_weekday_intervals", "60").is_err());
+
+        // Untyped keys are stored as given
+        assert!(validate_setting("reminder_template", "Time for {exercise}!").is_ok());
+    }
 
-                // Show and focus the window (unminimize if needed)
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.unminimize();
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
+    #[test]
+    fn test_achievement_progress_for_locked_thresholds() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 8);
+        let today = format!("{} 12:00:00", today_string(&conn));
+        insert_log(&conn, pushups, 40, 400, &today);
+        insert_log(&conn, squats, 20, 160, &today);
+        insert_log(&conn, pushups, 10, 100, &days_ago(1));
+        conn.execute(
+            "UPDATE achievements SET unlocked_at = ? WHERE key = 'first_exercise'",
+            params![today],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE user_stats SET current_streak = 2, last_exercise_date = ? WHERE id = 1",
+            params![today_string(&conn)],
+        )
+        .unwrap();
+
+        let achievements = achievements_with_progress(&conn).unwrap();
+        let progress = |key: &str| achievements.iter().find(|a| a.key == key).unwrap().progress;
+
+        assert_eq!(progress("first_exercise"), None);
+        assert_eq!(progress("variety"), Some((2, 5)));
+        assert_eq!(progress("hundred_pushups"), Some((40, 100)));
+        assert_eq!(progress("week_streak"), Some((2, 7)));
+        assert_eq!(progress("month_streak"), Some((2, 30)));
+        assert_eq!(progress("thousand_reps"), Some((70, 1000)));
+        // No measurable progress toward time-of-day achievements
+        assert_eq!(progress("night_owl"), None);
+    }
 
-                // Emit event to frontend to open quick log
-                if let Err(e) = app.emit("global-quick-log", ()) {
-                    log::error!("Failed to emit global-quick-log event: {}", e);
-                }
-            }
-        }) {
-        Ok(_) => {
-            log::info!("Successfully registered global shortcut Ctrl+Shift+Alt+G");
-        }
-        Err(e) => {
-            log::error!("Failed to register global shortcut Ctrl+Shift+Alt+G: {}", e);
+    #[test]
+    fn test_undo_several_logs_then_redo() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+
+        // Longest streak is a record and survives undo, so it's left out
+        let snapshot = |conn: &Connection| {
+            let exercise = get_exercise_by_id(conn, pushups).unwrap();
+            let logs: Vec<(i64, i32, String)> = conn
+                .prepare("SELECT id, reps, logged_at FROM exercise_logs ORDER BY id")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            let history: Vec<(i64, i32, String)> = conn
+                .prepare("SELECT id, level, reached_at FROM level_history ORDER BY id")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+            let streak: (i32, Option<String>) = conn
+                .query_row(
+                    "SELECT current_streak, last_exercise_date FROM user_stats WHERE id = 1",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap();
+            (
+                exercise.total_xp,
+                exercise.current_level,
+                logs,
+                history,
+                streak,
+            )
+        };
+
+        let mut states = vec![snapshot(&conn)];
+        for n in [3, 2, 1] {
+            log_exercise_at_time(&conn, pushups, 20, &days_ago(n)).unwrap();
+            states.push(snapshot(&conn));
         }
+        assert!(states[3].1 > 1);
+
+        let mut undone = Vec::new();
+        let first = undo_log(&conn, &mut undone).unwrap();
+        assert_eq!(first.reps, 20);
+        assert_eq!(snapshot(&conn), states[2]);
+        undo_log(&conn, &mut undone).unwrap();
+        assert_eq!(snapshot(&conn), states[1]);
+        undo_log(&conn, &mut undone).unwrap();
+        assert_eq!(snapshot(&conn), states[0]);
+        assert!(undo_log(&conn, &mut undone).is_err());
+
+        let redone = redo_log(&conn, &mut undone).unwrap();
+        assert_eq!(redone.new_exercise_level, states[1].1);
+        assert_eq!(snapshot(&conn), states[1]);
+        redo_log(&conn, &mut undone).unwrap();
+        redo_log(&conn, &mut undone).unwrap();
+        assert_eq!(snapshot(&conn), states[3]);
+        assert!(redo_log(&conn, &mut undone).is_err());
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_redo_refused_after_a_newer_log() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        log_exercise_at_time(&conn, pushups, 10, &days_ago(2)).unwrap();
+        log_exercise_at_time(&conn, pushups, 10, &days_ago(1)).unwrap();
+
+        let mut undone = Vec::new();
+        undo_log(&conn, &mut undone).unwrap();
+        undo_log(&conn, &mut undone).unwrap();
+        log_exercise_at_time(&conn, pushups, 5, &days_ago(1)).unwrap();
+
+        assert!(redo_log(&conn, &mut undone).is_err());
+        assert!(undone.is_empty());
+        assert_eq!(get_exercise_by_id(&conn, pushups).unwrap().total_xp, 50);
+    }
 
-// ============ App Entry Point ============
+    #[test]
+    fn test_redo_refused_after_exercise_deleted() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        log_exercise_at_time(&conn, pushups, 10, &days_ago(1)).unwrap();
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    let mut builder = tauri::Builder::default()
-        .plugin(tauri_plugin_notification::init())
-        .plugin(tauri_plugin_store::Builder::new().build())
-        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_process::init());
+        let mut undone = Vec::new();
+        undo_log(&conn, &mut undone).unwrap();
+        conn.execute("DELETE FROM exercises WHERE id = ?", params![pushups])
+            .unwrap();
 
-    // Add logging in debug mode
-    if cfg!(debug_assertions) {
-        builder = builder.plugin(
-            tauri_plugin_log::Builder::default()
-                .level(log::LevelFilter::Debug)
-                .build(),
+        assert!(redo_log(&conn, &mut undone).is_err());
+        assert!(undone.is_empty());
+        let logs: i64 = conn
+            .query_row("SELECT COUNT(*) FROM exercise_logs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(logs, 0);
+    }
+
+    #[test]
+    fn test_weekday_averages() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        // 2024-03-04 is a Monday: two Mondays averaging (150 + 50) / 2
+        insert_log(&conn, id, 10, 100, "2024-03-04 07:00:00");
+        insert_log(&conn, id, 5, 50, "2024-03-04 18:00:00");
+        insert_log(&conn, id, 5, 50, "2024-03-11 07:00:00");
+        insert_log(&conn, id, 30, 300, "2024-03-06 12:00:00");
+        insert_log(&conn, id, 2, 20, "2024-03-10 12:00:00");
+
+        let averages = weekday_averages(&conn).unwrap();
+        let days: Vec<&str> = averages.iter().map(|a| a.weekday.as_str()).collect();
+        assert_eq!(days, ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]);
+        assert_eq!(
+            (averages[0].average_xp, averages[0].active_days),
+            (100.0, 2)
         );
+        assert_eq!((averages[1].average_xp, averages[1].active_days), (0.0, 0));
+        assert_eq!(
+            (averages[2].average_xp, averages[2].active_days),
+            (300.0, 1)
+        );
+        assert_eq!((averages[6].average_xp, averages[6].active_days), (20.0, 1));
     }
 
-    builder
-        .setup(|app| {
-            // Initialize database
-            let app_dir = app.path().app_data_dir()?;
-            std::fs::create_dir_all(&app_dir)?;
-            let db_path = app_dir.join("geekfit.db");
+    #[test]
+    fn test_combo_bonus_fires_once_per_day() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 10);
+        let lunges = setup_exercise(&conn, "Lunges", 10);
+        let planks = setup_exercise(&conn, "Planks", 10);
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('combo_bonus', '100')",
+            [],
+        )
+        .unwrap();
+
+        let log = |id: i64, n: i64| log_exercise_at_time(&conn, id, 5, &days_ago(n)).unwrap();
+        assert!(!log(pushups, 2).combo_awarded);
+        assert!(!log(pushups, 2).combo_awarded);
+        assert!(!log(squats, 2).combo_awarded);
+        let third = log(lunges, 2);
+        assert!(third.combo_awarded);
+        assert_eq!(third.xp_earned, 150);
+        // More variety or repeats the same day don't pay out again
+        assert!(!log(planks, 2).combo_awarded);
+        assert!(!log(lunges, 2).combo_awarded);
+
+        // A new day can earn it again
+        log(planks, 1);
+        log(squats, 1);
+        assert!(log(pushups, 1).combo_awarded);
+
+        let total: i64 = conn
+            .query_row("SELECT SUM(total_xp) FROM exercises", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(total, 9 * 50 + 2 * 100);
 
-            let conn = Connection::open(db_path).expect("Failed to open database");
-            init_database(&conn).expect("Failed to initialize database");
+        conn.execute(
+            "UPDATE settings SET value = '0' WHERE key = 'combo_bonus'",
+            [],
+        )
+        .unwrap();
+        log(planks, 3);
+        log(squats, 3);
+        assert!(!log(lunges, 3).combo_awarded);
+    }
 
-            app.manage(DbState(Mutex::new(conn)));
+    #[test]
+    fn test_time_until_next_reminder() {
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap();
+        let next = next_reminder_at(now, Duration::from_secs(78 * 60), 120);
+        assert_eq!(next, now + chrono::Duration::minutes(42));
+        assert_eq!(minutes_until(next, now), 42);
+        assert_eq!(minutes_until(now + chrono::Duration::seconds(61), now), 2);
+        // Overdue reminders fire on the next tick
+        assert_eq!(
+            next_reminder_at(now, Duration::from_secs(3 * 3600), 120),
+            now
+        );
+        assert_eq!(minutes_until(now - chrono::Duration::minutes(5), now), 0);
 
-            // Initialize reminder state
-            let now = Instant::now();
-            app.manage(ReminderState {
-                last_eye_care: Mutex::new(now),
-                last_hydration: Mutex::new(now),
-                last_posture: Mutex::new(now),
-                last_exercise: Mutex::new(now),
-                running: AtomicBool::new(true),
-            });
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        setup_exercise(&conn, "Pushups", 10);
 
-            // Start background reminder loop
-            start_reminder_loop(app.handle().clone());
+        let due = due_reminders(&conn, Some(next), now);
+        assert_eq!(due.minutes_until_next, Some(42));
+        assert_eq!(due.next_reminder_at.as_deref(), Some("2024-03-01 09:42:00"));
+        assert_eq!(due.exercise.unwrap().name, "Pushups");
+        assert!(!due.paused);
 
-            // Setup system tray
-            setup_tray(app.handle())?;
+        // Paused for an hour: the reminder waits for the pause to end
+        pause_reminders(&conn, 60, now).unwrap();
+        let due = due_reminders(&conn, Some(next), now);
+        assert!(due.paused);
+        assert_eq!(due.minutes_until_next, Some(60));
 
-            // Setup global shortcuts (desktop only)
-            #[cfg(not(any(target_os = "android", target_os = "ios")))]
-            setup_global_shortcuts(app.handle())?;
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('reminder_enabled', 'false')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(due_reminders(&conn, Some(next), now).next_reminder_at, None);
+    }
 
-            Ok(())
-        })
-        .on_window_event(|window, event| {
-            // Minimize to tray instead of closing
-            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                // Hide the window instead of closing
-                let _ = window.hide();
-                // Prevent the window from actually closing
-                api.prevent_close();
-            }
-        })
-        .invoke_handler(tauri::generate_handler![
-            get_exercises,
-            add_exercise,
-            delete_exercise,
-            get_default_exercises,
-            complete_initial_setup,
-            log_exercise,
-            get_stats,
-            get_achievements,
-            get_exercise_history,
-            get_activity_data,
-            get_settings,
-            update_setting,
-            get_wellness_settings,
-            reset_reminder_timer,
-            export_data,
-            import_data,
-            reset_all_data,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
+    #[test]
+    fn test_heaviest_days_ordering_and_breakdown() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 5);
+        insert_log(&conn, pushups, 10, 100, "2024-03-01 08:00:00");
+        insert_log(&conn, pushups, 20, 200, "2024-03-02 08:00:00");
+        insert_log(&conn, squats, 60, 300, "2024-03-02 19:00:00");
+        insert_log(&conn, squats, 30, 150, "2024-03-03 08:00:00");
+        insert_log(&conn, pushups, 10, 100, "2024-03-04 08:00:00");
+
+        let top = heaviest_days(&conn, 2).unwrap();
+        assert_eq!(top.len(), 2);
+        assert_eq!((top[0].date.as_str(), top[0].total_xp), ("2024-03-02", 500));
+        assert_eq!((top[1].date.as_str(), top[1].total_xp), ("2024-03-03", 150));
+        let names: Vec<&str> = top[0]
+            .exercises
+            .iter()
+            .map(|e| e.exercise_name.as_str())
+            .collect();
+        assert_eq!(names, ["Squats", "Pushups"]);
+        assert_eq!(
+            (top[0].exercises[0].reps, top[0].exercises[0].xp),
+            (60, 300)
+        );
 
-// ============ Tests ============
+        // More days asked for than exist; ties go to the earlier day
+        let all = heaviest_days(&conn, 10).unwrap();
+        let dates: Vec<&str> = all.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(
+            dates,
+            ["2024-03-02", "2024-03-03", "2024-03-01", "2024-03-04"]
+        );
+        assert!(heaviest_days(&conn, 0).unwrap().is_empty());
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    struct MockAutostartLauncher {
+        enabled: std::cell::Cell<bool>,
+        available: bool,
+    }
 
-    #[test]
-    fn test_xp_for_level_1() {
-        assert_eq!(xp_for_level(1), 0);
+    impl AutostartLauncher for MockAutostartLauncher {
+        fn is_enabled(&self) -> Result<bool, String> {
+            if self.available {
+                Ok(self.enabled.get())
+            } else {
+                Err("launcher unavailable".to_string())
+            }
+        }
+
+        fn set_enabled(&self, enabled: bool) -> Result<(), String> {
+            self.enabled.set(enabled);
+            Ok(())
+        }
     }
 
     #[test]
-    fn test_xp_for_level_2() {
-        // Level 2 should require some XP
-        let xp = xp_for_level(2);
-        assert!(xp > 0);
-        assert!(xp < 100); // Should be relatively small
+    fn test_launch_on_startup_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let launcher = MockAutostartLauncher {
+            enabled: std::cell::Cell::new(false),
+            available: true,
+        };
+        assert!(!launch_on_startup(&conn, &launcher));
+
+        assert!(set_launch_on_startup(&conn, &launcher, true).unwrap());
+        assert!(launcher.enabled.get());
+        assert_eq!(get_setting_value(&conn, "launch_on_startup", ""), "true");
+
+        // Turned off outside the app: the OS state wins over the stored setting
+        launcher.enabled.set(false);
+        assert!(!launch_on_startup(&conn, &launcher));
+
+        assert!(!set_launch_on_startup(&conn, &launcher, false).unwrap());
+        assert_eq!(get_setting_value(&conn, "launch_on_startup", ""), "false");
+
+        // Without an OS answer, the stored setting is reported
+        let unavailable = MockAutostartLauncher {
+            enabled: std::cell::Cell::new(false),
+            available: false,
+        };
+        assert!(set_launch_on_startup(&conn, &unavailable, true).unwrap());
+        assert!(launch_on_startup(&conn, &unavailable));
     }
 
     #[test]
-    fn test_xp_increases_with_level() {
-        // XP requirements should increase with each level
-        for level in 2..99 {
-            assert!(
-                xp_for_level(level + 1) > xp_for_level(level),
-                "XP for level {} should be greater than level {}",
-                level + 1,
-                level
-            );
+    fn test_exercise_distribution_matches_logs() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let names = [
+            "Pushups", "Squats", "Lunges", "Planks", "Burpees", "Crunches", "Dips",
+        ];
+        let ids: Vec<i64> = names
+            .iter()
+            .map(|name| setup_exercise(&conn, name, 10))
+            .collect();
+        // Pushups get the most XP, Dips the least; nothing on 03-09
+        for (i, id) in ids.iter().enumerate() {
+            let xp = 100 * (names.len() - i) as i32;
+            insert_log(&conn, *id, 10, xp, "2024-03-08 08:00:00");
+            insert_log(&conn, *id, 10, xp, "2024-03-10 18:00:00");
+        }
+        insert_log(&conn, ids[0], 5, 50, "2024-03-10 19:00:00");
+        // Outside the window
+        insert_log(&conn, ids[1], 10, 999, "2024-03-01 08:00:00");
+
+        let dist = exercise_distribution(&conn, 3, false, today).unwrap();
+        assert_eq!(dist.series.len(), MAX_DISTRIBUTION_SERIES);
+        assert_eq!(dist.series[0], "Pushups");
+        assert_eq!(dist.series.last().unwrap(), OTHER_SERIES);
+
+        let dates: Vec<&str> = dist.days.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, ["2024-03-08", "2024-03-09", "2024-03-10"]);
+        assert!(dist.days[1].xp.values().all(|xp| *xp == 0));
+        assert_eq!(dist.days[1].xp.len(), MAX_DISTRIBUTION_SERIES);
+
+        assert_eq!(dist.days[2].xp["Pushups"], 750);
+        assert_eq!(dist.days[2].xp["Squats"], 600);
+        // Crunches (200) and Dips (100) share the last series
+        assert_eq!(dist.days[2].xp[OTHER_SERIES], 300);
+
+        // Every day's series add up to the raw logs for that day
+        for day in &dist.days {
+            let raw: i64 = conn
+                .query_row(
+                    &format!(
+                        "SELECT COALESCE(SUM(xp_earned), 0) FROM exercise_logs WHERE {} = ?",
+                        log_day_sql(&conn, "logged_at")
+                    ),
+                    params![day.date],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(day.xp.values().sum::<i64>(), raw);
         }
+
+        let by_category = exercise_distribution(&conn, 3, true, today).unwrap();
+        assert_eq!(by_category.series, ["Uncategorized"]);
+        assert_eq!(by_category.days[0].xp["Uncategorized"], 2800);
+        assert!(exercise_distribution(&conn, 0, false, today).is_err());
     }
 
     #[test]
-    fn test_xp_for_level_99() {
-        // Level 99 should require significant XP (RuneScape style)
-        let xp = xp_for_level(99);
-        assert!(xp > 10_000_000, "Level 99 should require over 10M XP");
+    fn test_maintenance_reclaims_deleted_pages() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        for n in 0..2000 {
+            insert_log(&conn, id, 10, 100, &days_ago(n % 365));
+        }
+        conn.execute("DELETE FROM exercise_logs", []).unwrap();
+
+        let result = run_maintenance(&conn).unwrap();
+        assert!(result.size_before > 0);
+        assert!(result.size_after > 0);
+        assert!(result.size_after < result.size_before);
+        assert_eq!(get_exercise_by_id(&conn, id).unwrap().name, "Pushups");
+
+        let tx = conn.unchecked_transaction().unwrap();
+        assert!(run_maintenance(&tx).is_err());
     }
 
     #[test]
-    fn test_level_from_xp_zero() {
-        assert_eq!(level_from_xp(0), 1);
+    fn test_xp_event_doubles_logs_inside_its_window() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let squats = setup_exercise(&conn, "Squats", 10);
+        let at = |n: i64, time: &str| {
+            (chrono::Local::now() - chrono::Duration::days(n))
+                .format(&format!("%Y-%m-%d {}", time))
+                .to_string()
+        };
+        let event = |multiplier: f64, exercise_id: Option<i64>| XpEvent {
+            id: 0,
+            name: "Double XP Weekend".to_string(),
+            starts_at: at(3, "00:00:00"),
+            ends_at: at(1, "00:00:00"),
+            multiplier,
+            exercise_id,
+            category: None,
+        };
+        insert_xp_event(&conn, &event(2.0, None)).unwrap();
+        insert_xp_event(&conn, &event(3.0, Some(squats))).unwrap();
+
+        let inside = log_exercise_at_time(&conn, pushups, 5, &at(2, "12:00:00")).unwrap();
+        assert_eq!(inside.xp_multiplier, 2.0);
+        assert_eq!(inside.xp_earned, 100);
+        // The exercise-specific event is larger, and events don't stack
+        let squats_inside = log_exercise_at_time(&conn, squats, 5, &at(2, "12:00:00")).unwrap();
+        assert_eq!(squats_inside.xp_earned, 150);
+
+        let before = log_exercise_at_time(&conn, pushups, 5, &at(4, "23:59:59")).unwrap();
+        assert_eq!((before.xp_multiplier, before.xp_earned), (1.0, 50));
+        // The end is exclusive
+        let after = log_exercise_at_time(&conn, pushups, 5, &at(1, "00:00:00")).unwrap();
+        assert_eq!((after.xp_multiplier, after.xp_earned), (1.0, 50));
+        assert_eq!(get_exercise_by_id(&conn, pushups).unwrap().total_xp, 200);
+
+        assert!(insert_xp_event(&conn, &event(0.0, None)).is_err());
+        let backwards = XpEvent {
+            starts_at: at(1, "00:00:00"),
+            ends_at: at(3, "00:00:00"),
+            ..event(2.0, None)
+        };
+        assert!(insert_xp_event(&conn, &backwards).is_err());
     }
 
     #[test]
-    fn test_level_from_xp_basic() {
-        // With 0 XP, should be level 1
-        assert_eq!(level_from_xp(0), 1);
-
-        // With some XP, should level up
-        let xp_for_2 = xp_for_level(2);
-        assert_eq!(level_from_xp(xp_for_2), 2);
-        assert_eq!(level_from_xp(xp_for_2 - 1), 1);
+    fn test_all_settings_includes_custom_keys() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        save_setting(&conn, "dashboard_layout", "compact").unwrap();
+        save_setting(&conn, "rest_days", "Sat,Sun").unwrap();
+        assert!(save_setting(&conn, "reminder_interval_minutes", "abc").is_err());
+
+        let settings = all_settings(&conn).unwrap();
+        assert_eq!(settings["dashboard_layout"], "compact");
+        assert_eq!(settings["rest_days"], "Sat,Sun");
+        assert_eq!(settings["reminder_enabled"], "true");
+        assert!(!settings.contains_key("timezone"));
+
+        save_setting(&conn, "dashboard_layout", "wide").unwrap();
+        assert_eq!(all_settings(&conn).unwrap()["dashboard_layout"], "wide");
     }
 
     #[test]
-    fn test_level_from_xp_max() {
-        // Even with huge XP, max level is 99
-        assert_eq!(level_from_xp(100_000_000), 99);
-        assert_eq!(level_from_xp(i64::MAX / 2), 99);
+    fn test_override_streak_validation() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        let yesterday = (effective_today(&conn) - chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string();
+        let tomorrow = (effective_today(&conn) + chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string();
+
+        assert!(override_streak(&conn, 10, 5, Some(&yesterday)).is_err());
+        assert!(override_streak(&conn, 3, 5, Some("2024-02-30")).is_err());
+        assert!(override_streak(&conn, 3, 5, Some("yesterday")).is_err());
+        assert!(override_streak(&conn, 3, 5, Some(&tomorrow)).is_err());
+        assert!(override_streak(&conn, 3, 5, None).is_err());
+        assert!(override_streak(&conn, -1, 5, Some(&yesterday)).is_err());
+        // Nothing was written by the rejected calls
+        assert_eq!(active_streak(&conn), 0);
+
+        override_streak(&conn, 12, 40, Some(&yesterday)).unwrap();
+        let stored: (i32, i32, Option<String>) = conn
+            .query_row(
+                "SELECT current_streak, longest_streak, last_exercise_date FROM user_stats WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(stored, (12, 40, Some(yesterday)));
+        assert_eq!(active_streak(&conn), 12);
+
+        override_streak(&conn, 0, 40, None).unwrap();
+        assert_eq!(active_streak(&conn), 0);
     }
 
-    #[test]
-    fn test_level_xp_roundtrip() {
-        // For each level, getting XP for that level and converting back should give same level
-        for level in 1..=99 {
-            let xp = xp_for_level(level);
-            assert_eq!(
-                level_from_xp(xp),
-                level,
-                "XP {} should give level {}",
-                xp,
-                level
-            );
-        }
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "geekfit-{}-test-{}-{}",
+            name,
+            std::process::id(),
+            chrono::Local::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_default()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
     }
 
     #[test]
-    fn test_database_initialization() {
-        // Test that database initializes without error
-        let conn = Connection::open_in_memory().unwrap();
-        assert!(init_database(&conn).is_ok());
+    fn test_open_checked_database_repairs_dangling_logs_in_place() {
+        let app_dir = scratch_dir("health");
+        let (conn, _) = open_checked_profile(&app_dir, DEFAULT_PROFILE_ID).unwrap();
+        let id = setup_exercise(&conn, "Pushups", 10);
+        insert_log(&conn, id, 10, 100, &days_ago(1));
+        insert_log(&conn, 999, 10, 100, &days_ago(1));
+        drop(conn);
+
+        let (conn, health) = open_checked_profile(&app_dir, DEFAULT_PROFILE_ID).unwrap();
+        assert!(health.ok, "unexpected issues: {:?}", health.issues);
+        assert!(health.restored_from_backup.is_none());
+        assert_eq!(health.repaired.len(), 1);
+        // The valid log survives; only the dangling one is gone
+        let logs: Vec<i64> = conn
+            .prepare("SELECT exercise_id FROM exercise_logs")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(logs, vec![id]);
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&app_dir);
     }
 
     #[test]
-    fn test_no_default_exercises_on_init() {
-        let conn = Connection::open_in_memory().unwrap();
-        init_database(&conn).unwrap();
+    fn test_open_checked_database_keeps_corrupt_file_when_restoring() {
+        let app_dir = scratch_dir("restore");
+        let (conn, _) = open_checked_profile(&app_dir, DEFAULT_PROFILE_ID).unwrap();
+        setup_exercise(&conn, "Pushups", 10);
+        let backup = app_dir.join("backups").join("geekfit-2024-01-01.db");
+        std::fs::create_dir_all(backup.parent().unwrap()).unwrap();
+        conn.execute(
+            "VACUUM INTO ?",
+            params![backup.to_string_lossy().to_string()],
+        )
+        .unwrap();
+        drop(conn);
 
-        let count: i32 = conn
+        std::fs::write(app_dir.join("geekfit.db"), b"definitely not sqlite").unwrap();
+        std::fs::write(app_dir.join("geekfit.db-wal"), b"stale wal").unwrap();
+
+        let (conn, health) = open_checked_profile(&app_dir, DEFAULT_PROFILE_ID).unwrap();
+        assert!(!health.issues.is_empty());
+        assert!(health.restored_from_backup.is_some());
+        let exercises: i64 = conn
             .query_row("SELECT COUNT(*) FROM exercises", [], |row| row.get(0))
             .unwrap();
+        assert_eq!(exercises, 1);
+
+        let kept: Vec<String> = std::fs::read_dir(&app_dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("geekfit.corrupt-"))
+            .collect();
+        assert!(kept.iter().any(|name| name.ends_with(".db")), "{:?}", kept);
+        assert!(
+            kept.iter().any(|name| name.ends_with(".db-wal")),
+            "{:?}",
+            kept
+        );
 
-        // Exercises are added through onboarding, not on init
-        assert_eq!(count, 0, "Should have no exercises on init, got {}", count);
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&app_dir);
     }
 
     #[test]
-    fn test_default_achievements_created() {
-        let conn = Connection::open_in_memory().unwrap();
-        init_database(&conn).unwrap();
+    fn test_profile_databases_get_their_own_snapshots() {
+        let app_dir = scratch_dir("profile-backups");
+        let registry = open_profile_registry(&app_dir).unwrap();
+        let home = insert_profile(&registry, "Home").unwrap();
+        drop(registry);
+
+        let (conn, health) = open_checked_profile(&app_dir, home).unwrap();
+        assert!(health.ok, "unexpected issues: {:?}", health.issues);
+        let snapshot =
+            profile_backup_dir(&app_dir, home).join(format!("geekfit-{}.db", today_string(&conn)));
+        assert!(snapshot.exists());
+        assert_ne!(
+            profile_backup_dir(&app_dir, home),
+            profile_backup_dir(&app_dir, DEFAULT_PROFILE_ID)
+        );
 
-        let count: i32 = conn
-            .query_row("SELECT COUNT(*) FROM achievements", [], |row| row.get(0))
-            .unwrap();
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&app_dir);
+    }
 
-        // Should have achievements
-        assert!(
-            count >= 9,
-            "Should have at least 9 achievements, got {}",
-            count
+    #[test]
+    fn test_adopted_fallback_keeps_existing_data() {
+        let primary = scratch_dir("fallback-primary");
+        let documents = scratch_dir("fallback-documents");
+        let temp = scratch_dir("fallback-temp");
+        {
+            let conn = open_and_init(&primary.join("geekfit.db")).unwrap();
+            setup_exercise(&conn, "Pushups", 10);
+        }
+        std::fs::create_dir_all(primary.join("profiles")).unwrap();
+        std::fs::write(primary.join("profiles").join("profile-2.db"), b"").unwrap();
+
+        assert_eq!(adopt_fallback_dir(&primary, &temp).unwrap(), 2);
+        let conn = Connection::open(temp.join("geekfit.db")).unwrap();
+        let name: String = conn
+            .query_row("SELECT name FROM exercises", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(name, "Pushups");
+        assert!(temp.join("profiles").join("profile-2.db").exists());
+
+        // The adopted fallback wins over both the primary and earlier fallbacks
+        let fallbacks = vec![documents.clone(), temp.clone()];
+        assert_eq!(
+            select_data_dir(&primary, &fallbacks, |_| true),
+            Some(temp.clone())
         );
+        // Re-adopting doesn't overwrite data written since
+        assert_eq!(adopt_fallback_dir(&primary, &temp).unwrap(), 0);
     }
 
     #[test]
-    fn test_user_stats_initialized() {
+    fn test_backdated_logs_judged_by_their_own_time() {
         let conn = Connection::open_in_memory().unwrap();
         init_database(&conn).unwrap();
-
-        let (streak, longest): (i32, i32) = conn
-            .query_row(
-                "SELECT current_streak, longest_streak FROM user_stats WHERE id = 1",
-                [],
-                |row| Ok((row.get(0)?, row.get(1)?)),
+        let pushups = setup_exercise(&conn, "Pushups", 10);
+        let unlocked_at = |key: &str| -> Option<String> {
+            conn.query_row(
+                "SELECT unlocked_at FROM achievements WHERE key = ?",
+                params![key],
+                |row| row.get(0),
             )
-            .unwrap();
+            .unwrap()
+        };
+
+        // A noon set never earns the time-of-day achievements, whatever the clock says now
+        log_exercise_at_time(&conn, pushups, 60, "2024-03-01 12:00:00").unwrap();
+        assert_eq!(unlocked_at("early_bird"), None);
+        assert_eq!(unlocked_at("night_owl"), None);
+        assert_eq!(
+            unlocked_at("first_exercise").as_deref(),
+            Some("2024-03-01 12:00:00")
+        );
 
-        assert_eq!(streak, 0);
-        assert_eq!(longest, 0);
+        // 100 pushups on that past day count for the century, stamped when it happened
+        log_exercise_at_time(&conn, pushups, 40, "2024-03-01 23:00:00").unwrap();
+        assert_eq!(
+            unlocked_at("hundred_pushups").as_deref(),
+            Some("2024-03-01 23:00:00")
+        );
+        assert_eq!(
+            unlocked_at("night_owl").as_deref(),
+            Some("2024-03-01 23:00:00")
+        );
     }
 
     #[test]
-    fn test_settings_initialized() {
+    fn test_clearing_logs_keeps_decay_between_logs() {
         let conn = Connection::open_in_memory().unwrap();
         init_database(&conn).unwrap();
-
-        let reminder: String = conn
-            .query_row(
-                "SELECT value FROM settings WHERE key = 'reminder_enabled'",
-                [],
-                |row| row.get(0),
+        let id = setup_exercise(&conn, "Pushups", 10);
+        for (key, value) in [
+            ("xp_decay_enabled", "true"),
+            ("xp_decay_grace_days", "7"),
+            ("xp_decay_percent_per_day", "10"),
+            ("xp_decay_allow_delevel", "true"),
+        ] {
+            conn.execute(
+                "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+                params![key, value],
             )
             .unwrap();
-
-        assert_eq!(reminder, "true");
+        }
+        let decay = levels::xp_decay_config(&conn).unwrap();
+        log_exercise_at_time(&conn, id, 100, &days_ago(40)).unwrap();
+        log_exercise_at_time(&conn, id, 100, &days_ago(30)).unwrap();
+        log_exercise_at_time(&conn, id, 1, &days_ago(3)).unwrap();
+        let stored = |conn: &Connection| -> i64 {
+            conn.query_row(
+                "SELECT total_xp FROM exercises WHERE id = ?",
+                params![id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        let expected = levels::decayed_xp(
+            levels::decayed_xp(1000, 10, &decay, levels::DEFAULT_MAX_LEVEL) + 1000,
+            27,
+            &decay,
+            levels::DEFAULT_MAX_LEVEL,
+        ) + 10;
+        assert_eq!(stored(&conn), expected);
+
+        // Rebuilding from the remaining logs loses the same idle gaps again
+        assert_eq!(clear_logs_before(&conn, &days_ago(35)[..10]).unwrap(), 1);
+        assert_eq!(
+            stored(&conn),
+            levels::decayed_xp(1000, 27, &decay, levels::DEFAULT_MAX_LEVEL) + 10
+        );
+        assert_eq!(replayed_xp(&conn, id).unwrap(), stored(&conn));
     }
 }
@@ -0,0 +1,242 @@
+// Local HTTP API so scripts can log exercises while the GUI holds the database.
+// Built only with the `local-api` feature and bound to 127.0.0.1. Requests from web
+// pages are refused: anything carrying an Origin header is rejected, and POST bodies must
+// be sent as application/json, which browsers can't do cross-origin without a preflight.
+//
+//   POST /log    {"exercise": "Pushups" | 1, "reps": 20}
+//   GET  /stats
+
+use crate::streaks::local_timestamp;
+use crate::{get_exercise_by_id, get_setting_value, log_exercise_at_time, user_stats, DbState};
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::Read;
+use tauri::{AppHandle, Emitter, Manager};
+
+const DEFAULT_PORT: u16 = 4587;
+
+#[derive(Debug, Deserialize)]
+struct LogRequest {
+    exercise: Value, // Exercise name or id
+    reps: i32,
+}
+
+fn resolve_exercise(conn: &Connection, exercise: &Value) -> Result<i64, String> {
+    match exercise {
+        Value::Number(id) => {
+            let id = id.as_i64().ok_or("Invalid exercise id")?;
+            get_exercise_by_id(conn, id).map(|exercise| exercise.id)
+        }
+        Value::String(name) => conn
+            .query_row(
+                "SELECT id FROM exercises WHERE LOWER(name) = LOWER(?)",
+                params![name.trim()],
+                |row| row.get(0),
+            )
+            .map_err(|_| format!("Unknown exercise '{}'", name)),
+        _ => Err("'exercise' must be a name or id".to_string()),
+    }
+}
+
+fn handle_log(conn: &Connection, body: &str) -> Result<Value, String> {
+    let request: LogRequest =
+        serde_json::from_str(body).map_err(|e| format!("Invalid request body: {}", e))?;
+    if request.reps <= 0 {
+        return Err("Reps must be positive".to_string());
+    }
+
+    let exercise_id = resolve_exercise(conn, &request.exercise)?;
+    let result = log_exercise_at_time(conn, exercise_id, request.reps, &local_timestamp(conn))?;
+    serde_json::to_value(result).map_err(|e| e.to_string())
+}
+
+/// Status and error body for requests that could come from a web page rather than a
+/// local script, or `None` if the request may proceed.
+pub(crate) fn reject_browser_request(
+    method: &str,
+    origin: Option<&str>,
+    content_type: Option<&str>,
+) -> Option<(u16, Value)> {
+    if origin.is_some() {
+        return Some((
+            403,
+            json!({ "error": "Requests from web pages are not allowed" }),
+        ));
+    }
+    let is_json = content_type.is_some_and(|value| {
+        value
+            .split(';')
+            .next()
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+    });
+    if method == "POST" && !is_json {
+        return Some((
+            415,
+            json!({ "error": "Content-Type must be application/json" }),
+        ));
+    }
+    None
+}
+
+/// Routes a request to its handler, returning the status code and JSON body.
+pub(crate) fn handle_request(
+    conn: &Connection,
+    method: &str,
+    path: &str,
+    body: &str,
+) -> (u16, Value) {
+    let result = match (method, path) {
+        ("POST", "/log") => handle_log(conn, body),
+        ("GET", "/stats") => user_stats(conn)
+            .and_then(|stats| serde_json::to_value(stats).map_err(|e| e.to_string())),
+        _ => return (404, json!({ "error": "Not found" })),
+    };
+
+    match result {
+        Ok(value) => (200, value),
+        Err(e) => (400, json!({ "error": e })),
+    }
+}
+
+/// Starts the API on a background thread. The port comes from the `local_api_port` setting.
+pub(crate) fn start(app: AppHandle) {
+    let port = {
+        let db_state = app.state::<DbState>();
+        let value = match db_state.0.lock() {
+            Ok(conn) => get_setting_value(&conn, "local_api_port", &DEFAULT_PORT.to_string()),
+            Err(_) => DEFAULT_PORT.to_string(),
+        };
+        value.parse::<u16>().unwrap_or(DEFAULT_PORT)
+    };
+
+    std::thread::spawn(move || {
+        let server = match tiny_http::Server::http(("127.0.0.1", port)) {
+            Ok(server) => server,
+            Err(e) => {
+                log::error!("Failed to start local API on port {}: {}", port, e);
+                return;
+            }
+        };
+        log::info!("Local API listening on 127.0.0.1:{}", port);
+
+        for mut request in server.incoming_requests() {
+            let mut body = String::new();
+            let _ = request.as_reader().read_to_string(&mut body);
+            let method = request.method().as_str().to_string();
+            let path = request.url().to_string();
+            let header = |name: &'static str| {
+                request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv(name))
+                    .map(|h| h.value.as_str().to_string())
+            };
+            let origin = header("Origin");
+            let content_type = header("Content-Type");
+
+            let (status, payload) =
+                match reject_browser_request(&method, origin.as_deref(), content_type.as_deref()) {
+                    Some(rejection) => rejection,
+                    None => match app.state::<DbState>().0.lock() {
+                        Ok(conn) => handle_request(&conn, &method, &path, &body),
+                        Err(e) => (500, json!({ "error": e.to_string() })),
+                    },
+                };
+
+            // Same event as the tray quick log, so the open dashboard refreshes
+            if status == 200 && method == "POST" {
+                let _ = app.emit("exercise-logged", ());
+            }
+
+            let response = tiny_http::Response::from_string(payload.to_string())
+                .with_status_code(status)
+                .with_header(
+                    tiny_http::Header::from_bytes("Content-Type", "application/json").unwrap(),
+                );
+            if let Err(e) = request.respond(response) {
+                log::error!("Failed to respond to local API request: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_database;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        init_database(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO exercises (name, xp_per_rep, total_xp, current_level) VALUES ('Pushups', 10, 0, 1)",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_log_by_name_and_id() {
+        let conn = setup();
+
+        let (status, body) = handle_request(
+            &conn,
+            "POST",
+            "/log",
+            r#"{"exercise": "pushups", "reps": 10}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(body["xp_earned"], 100);
+
+        let (status, _) = handle_request(&conn, "POST", "/log", r#"{"exercise": 1, "reps": 5}"#);
+        assert_eq!(status, 200);
+
+        let (status, body) = handle_request(&conn, "GET", "/stats", "");
+        assert_eq!(status, 200);
+        assert_eq!(body["total_xp"], 150);
+        assert_eq!(body["current_streak"], 1);
+    }
+
+    #[test]
+    fn test_rejects_bad_requests() {
+        let conn = setup();
+
+        let (status, body) = handle_request(
+            &conn,
+            "POST",
+            "/log",
+            r#"{"exercise": "Burpees", "reps": 10}"#,
+        );
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("Burpees"));
+
+        let (status, _) = handle_request(&conn, "POST", "/log", r#"{"exercise": 1, "reps": 0}"#);
+        assert_eq!(status, 400);
+        let (status, _) = handle_request(&conn, "POST", "/log", "not json");
+        assert_eq!(status, 400);
+        let (status, _) = handle_request(&conn, "DELETE", "/log", "");
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn test_rejects_browser_requests() {
+        // A cross-origin page can POST text/plain without a preflight, but always sends Origin
+        let rejected = reject_browser_request("POST", Some("https://example.com"), None);
+        assert_eq!(rejected.map(|(status, _)| status), Some(403));
+        let rejected = reject_browser_request("GET", Some("null"), None);
+        assert_eq!(rejected.map(|(status, _)| status), Some(403));
+
+        let rejected = reject_browser_request("POST", None, Some("text/plain"));
+        assert_eq!(rejected.map(|(status, _)| status), Some(415));
+        let rejected = reject_browser_request("POST", None, None);
+        assert_eq!(rejected.map(|(status, _)| status), Some(415));
+
+        assert!(reject_browser_request("POST", None, Some("application/json")).is_none());
+        assert!(
+            reject_browser_request("POST", None, Some("Application/JSON; charset=utf-8")).is_none()
+        );
+        assert!(reject_browser_request("GET", None, None).is_none());
+    }
+}
@@ -0,0 +1,275 @@
+// Shareable PNG summary card: total level, streak, top exercises and a mini heatmap.
+// Layout is a pure function from `CardStats` to rectangles, so it can be tested without
+// looking at pixels; `render` only paints those rectangles and encodes the PNG.
+
+use image::{ImageFormat, Rgba, RgbaImage};
+use std::io::Cursor;
+
+pub(crate) const CARD_WIDTH: u32 = 600;
+pub(crate) const CARD_HEIGHT: u32 = 315;
+/// Days shown in the heatmap, oldest first, one column per week
+pub(crate) const HEATMAP_DAYS: usize = 28;
+
+const BACKGROUND: [u8; 4] = [0x1E, 0x1E, 0x2E, 0xFF];
+const ACCENT: [u8; 4] = [0x89, 0xB4, 0xFA, 0xFF];
+const TEXT: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+const STREAK: [u8; 4] = [0xFA, 0xB3, 0x87, 0xFF];
+const EMPTY_DAY: [u8; 4] = [0x31, 0x32, 0x44, 0xFF];
+const ACTIVE_DAY: [u8; 4] = [0xA6, 0xE3, 0xA1, 0xFF];
+const BAR_PALETTE: [[u8; 4]; 3] = [
+    [0xF3, 0x8B, 0xA8, 0xFF],
+    [0xCB, 0xA6, 0xF7, 0xFF],
+    [0x94, 0xE2, 0xD5, 0xFF],
+];
+
+// 3x5 bitmap digits, one row per entry, high bit on the left
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+#[derive(Debug, Clone)]
+pub(crate) struct CardExercise {
+    pub level: i32,
+    pub color: Option<String>, // "#RRGGBB", falls back to the palette
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CardStats {
+    pub total_level: i32,
+    pub current_streak: i32,
+    pub max_level: i32,
+    pub top_exercises: Vec<CardExercise>, // Up to three, strongest first
+    pub daily_xp: Vec<i64>,               // HEATMAP_DAYS entries, oldest first
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+    pub color: [u8; 4],
+}
+
+fn parse_hex(color: &str) -> Option<[u8; 4]> {
+    let hex = color.strip_prefix('#')?;
+    // Byte slicing below would panic inside a multi-byte character
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?, 0xFF])
+}
+
+fn blend(from: [u8; 4], to: [u8; 4], t: f64) -> [u8; 4] {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    [
+        mix(from[0], to[0]),
+        mix(from[1], to[1]),
+        mix(from[2], to[2]),
+        0xFF,
+    ]
+}
+
+/// Rectangles for `value` drawn in the bitmap font at `scale` pixels per dot.
+fn number(value: i64, x: u32, y: u32, scale: u32, color: [u8; 4]) -> Vec<Rect> {
+    let mut rects = Vec::new();
+    for (i, ch) in value.max(0).to_string().chars().enumerate() {
+        let glyph = DIGITS[ch.to_digit(10).unwrap_or(0) as usize];
+        let left = x + i as u32 * 4 * scale;
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    rects.push(Rect {
+                        x: left + col * scale,
+                        y: y + row as u32 * scale,
+                        w: scale,
+                        h: scale,
+                        color,
+                    });
+                }
+            }
+        }
+    }
+    rects
+}
+
+/// Everything on the card, in paint order.
+pub(crate) fn layout(stats: &CardStats) -> Vec<Rect> {
+    let mut rects = vec![
+        Rect {
+            x: 0,
+            y: 0,
+            w: CARD_WIDTH,
+            h: CARD_HEIGHT,
+            color: BACKGROUND,
+        },
+        Rect {
+            x: 0,
+            y: 0,
+            w: CARD_WIDTH,
+            h: 8,
+            color: ACCENT,
+        },
+    ];
+
+    rects.extend(number(stats.total_level as i64, 32, 32, 10, TEXT));
+
+    // Streak: a flame-colored marker followed by the day count
+    rects.push(Rect {
+        x: 32,
+        y: 110,
+        w: 20,
+        h: 25,
+        color: STREAK,
+    });
+    rects.extend(number(stats.current_streak as i64, 64, 110, 5, STREAK));
+
+    let max_bar = 280;
+    let max_level = stats.max_level.max(1);
+    for (i, exercise) in stats.top_exercises.iter().take(3).enumerate() {
+        let y = 170 + i as u32 * 40;
+        let width =
+            ((exercise.level.clamp(0, max_level) as u32 * max_bar) / max_level as u32).max(8);
+        let color = exercise
+            .color
+            .as_deref()
+            .and_then(parse_hex)
+            .unwrap_or(BAR_PALETTE[i]);
+        rects.push(Rect {
+            x: 32,
+            y,
+            w: width,
+            h: 24,
+            color,
+        });
+        rects.extend(number(
+            exercise.level as i64,
+            32 + width + 10,
+            y + 2,
+            4,
+            TEXT,
+        ));
+    }
+
+    let max_xp = stats.daily_xp.iter().copied().max().unwrap_or(0).max(1);
+    for (i, xp) in stats.daily_xp.iter().take(HEATMAP_DAYS).enumerate() {
+        let (week, weekday) = (i as u32 / 7, i as u32 % 7);
+        let color = if *xp > 0 {
+            blend(
+                EMPTY_DAY,
+                ACTIVE_DAY,
+                0.3 + 0.7 * (*xp as f64 / max_xp as f64),
+            )
+        } else {
+            EMPTY_DAY
+        };
+        rects.push(Rect {
+            x: 420 + week * 34,
+            y: 40 + weekday * 34,
+            w: 28,
+            h: 28,
+            color,
+        });
+    }
+
+    rects
+}
+
+/// Paints the card and returns it as PNG bytes.
+pub(crate) fn render(stats: &CardStats) -> Result<Vec<u8>, String> {
+    let mut image = RgbaImage::from_pixel(CARD_WIDTH, CARD_HEIGHT, Rgba(BACKGROUND));
+    for rect in layout(stats) {
+        for y in rect.y..(rect.y + rect.h).min(CARD_HEIGHT) {
+            for x in rect.x..(rect.x + rect.w).min(CARD_WIDTH) {
+                image.put_pixel(x, y, Rgba(rect.color));
+            }
+        }
+    }
+
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> CardStats {
+        CardStats {
+            total_level: 1234,
+            current_streak: 42,
+            max_level: 99,
+            top_exercises: vec![
+                CardExercise {
+                    level: 99,
+                    color: Some("#FF0000".to_string()),
+                },
+                CardExercise {
+                    level: 50,
+                    color: None,
+                },
+                CardExercise {
+                    level: 1,
+                    color: Some("not a color".to_string()),
+                },
+            ],
+            daily_xp: (0..HEATMAP_DAYS as i64).map(|d| d * 10).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_hex_rejects_non_ascii() {
+        assert_eq!(parse_hex("#ff8000"), Some([0xFF, 0x80, 0x00, 0xFF]));
+        assert_eq!(parse_hex("#ff€0"), None);
+        assert_eq!(parse_hex("#ff80zz"), None);
+    }
+
+    #[test]
+    fn test_layout_stays_on_the_card() {
+        let rects = layout(&sample());
+        assert_eq!(rects[0].w, CARD_WIDTH);
+        assert_eq!(rects[0].h, CARD_HEIGHT);
+        for rect in &rects {
+            assert!(rect.x + rect.w <= CARD_WIDTH, "{:?}", rect);
+            assert!(rect.y + rect.h <= CARD_HEIGHT, "{:?}", rect);
+        }
+        assert!(rects.iter().any(|r| r.color == [0xFF, 0x00, 0x00, 0xFF]));
+    }
+
+    #[test]
+    fn test_render_produces_png() {
+        let bytes = render(&sample()).unwrap();
+        assert!(!bytes.is_empty());
+        assert_eq!(&bytes[1..4], b"PNG");
+
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!(decoded.width(), CARD_WIDTH);
+        assert_eq!(decoded.height(), CARD_HEIGHT);
+    }
+
+    #[test]
+    fn test_render_handles_a_fresh_profile() {
+        let stats = CardStats {
+            total_level: 0,
+            current_streak: 0,
+            max_level: 99,
+            top_exercises: Vec::new(),
+            daily_xp: vec![0; HEATMAP_DAYS],
+        };
+        assert!(!render(&stats).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+// Short chimes for level-ups and achievement unlocks, gated by the `sound_enabled` setting.
+// Built only with the `sound` feature. The clips are compiled into the binary, so there is
+// nothing to load at play time.
+
+use std::io::Cursor;
+
+const LEVEL_UP_CHIME: &[u8] = include_bytes!("../sounds/level_up.wav");
+const ACHIEVEMENT_CHIME: &[u8] = include_bytes!("../sounds/achievement.wav");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SoundEvent {
+    LevelUp,
+    Achievement,
+}
+
+pub(crate) fn asset_for(event: SoundEvent) -> &'static [u8] {
+    match event {
+        SoundEvent::LevelUp => LEVEL_UP_CHIME,
+        SoundEvent::Achievement => ACHIEVEMENT_CHIME,
+    }
+}
+
+/// The chime for a log, if it earned one. An unlock is rarer, so it wins over a level-up.
+pub(crate) fn event_for_log(leveled_up: bool, unlocked_achievement: bool) -> Option<SoundEvent> {
+    if unlocked_achievement {
+        Some(SoundEvent::Achievement)
+    } else if leveled_up {
+        Some(SoundEvent::LevelUp)
+    } else {
+        None
+    }
+}
+
+/// Plays `event` on a detached thread so audio never holds up the database lock.
+pub(crate) fn play(event: SoundEvent) {
+    std::thread::spawn(move || {
+        let (_stream, handle) = match rodio::OutputStream::try_default() {
+            Ok(output) => output,
+            Err(e) => {
+                log::warn!("No audio output for {:?} chime: {}", event, e);
+                return;
+            }
+        };
+        let result = rodio::Sink::try_new(&handle)
+            .map_err(|e| e.to_string())
+            .and_then(|sink| {
+                let source = rodio::Decoder::new(Cursor::new(asset_for(event)))
+                    .map_err(|e| e.to_string())?;
+                sink.append(source);
+                sink.sleep_until_end();
+                Ok(())
+            });
+        if let Err(e) = result {
+            log::warn!("Failed to play {:?} chime: {}", event, e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_for_log() {
+        assert_eq!(event_for_log(false, false), None);
+        assert_eq!(event_for_log(true, false), Some(SoundEvent::LevelUp));
+        assert_eq!(event_for_log(false, true), Some(SoundEvent::Achievement));
+        assert_eq!(event_for_log(true, true), Some(SoundEvent::Achievement));
+    }
+
+    #[test]
+    fn test_each_event_has_its_own_wav() {
+        let level_up = asset_for(SoundEvent::LevelUp);
+        let achievement = asset_for(SoundEvent::Achievement);
+        assert_ne!(level_up, achievement);
+        for asset in [level_up, achievement] {
+            assert_eq!(&asset[..4], b"RIFF");
+            assert_eq!(&asset[8..12], b"WAVE");
+        }
+    }
+}
@@ -0,0 +1,360 @@
+// Streak derivation shared by the Tauri app and the CLI
+
+use rusqlite::{params, Connection};
+
+/// Computes (current, longest) streaks from sorted, de-duplicated active dates.
+/// The current streak only counts if its last day is today or yesterday.
+pub fn compute_streaks(dates: &[chrono::NaiveDate], today: chrono::NaiveDate) -> (i32, i32) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut prev: Option<chrono::NaiveDate> = None;
+
+    for &date in dates {
+        run = match prev {
+            Some(p) if date - p == chrono::Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = std::cmp::max(longest, run);
+        prev = Some(date);
+    }
+
+    let current = match prev {
+        Some(last) if today - last <= chrono::Duration::days(1) => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+/// First and last day of the longest run of consecutive dates (the earliest on ties).
+pub fn longest_run(dates: &[chrono::NaiveDate]) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+    let mut best: Option<(chrono::NaiveDate, chrono::NaiveDate)> = None;
+    let mut start = *dates.first()?;
+    let mut prev = start;
+
+    for &date in dates {
+        if date - prev > chrono::Duration::days(1) {
+            start = date;
+        }
+        prev = date;
+        if best.map_or(true, |(s, e)| date - start > e - s) {
+            best = Some((start, date));
+        }
+    }
+    best
+}
+
+/// Every run of consecutive dates as (first day, last day), oldest first.
+pub fn streak_runs(dates: &[chrono::NaiveDate]) -> Vec<(chrono::NaiveDate, chrono::NaiveDate)> {
+    let mut runs: Vec<(chrono::NaiveDate, chrono::NaiveDate)> = Vec::new();
+    for &date in dates {
+        match runs.last_mut() {
+            Some((_, end)) if date - *end == chrono::Duration::days(1) => *end = date,
+            Some((_, end)) if date == *end => {}
+            _ => runs.push((date, date)),
+        }
+    }
+    runs
+}
+
+/// 0-100 score for how regularly the `dates` (sorted, de-duplicated, all within the
+/// trailing `window_days`) are spread. Mostly driven by how even the gaps between active
+/// days are (coefficient of variation), with a smaller share for how many days were active.
+pub fn consistency_score(dates: &[chrono::NaiveDate], window_days: i64) -> i32 {
+    if dates.is_empty() || window_days <= 0 {
+        return 0;
+    }
+    let frequency = (dates.len() as f64 / window_days as f64).min(1.0);
+
+    let gaps: Vec<f64> = dates
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_days() as f64)
+        .collect();
+    let regularity = if gaps.is_empty() {
+        0.0
+    } else {
+        let mean = gaps.iter().sum::<f64>() / gaps.len() as f64;
+        let variance = gaps.iter().map(|g| (g - mean).powi(2)).sum::<f64>() / gaps.len() as f64;
+        1.0 / (1.0 + variance.sqrt() / mean)
+    };
+
+    (100.0 * (0.7 * regularity + 0.3 * frequency)).round() as i32
+}
+
+/// Hour (0-23) from the `day_rollover_hour` setting; logs before it count toward the
+/// previous day, so a 1 AM set after a late coding session keeps the streak going.
+pub fn day_rollover_hour(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'day_rollover_hour'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| value.parse::<i64>().ok())
+    .unwrap_or(0)
+    .clamp(0, 23)
+}
+
+/// The day a moment counts toward when days roll over at `rollover_hour`.
+pub fn effective_date(at: chrono::NaiveDateTime, rollover_hour: i64) -> chrono::NaiveDate {
+    (at - chrono::Duration::hours(rollover_hour)).date()
+}
+
+/// Home timezone from the `timezone` setting (an IANA name such as "Europe/Berlin").
+/// With none set, or an unknown name, the system zone is used.
+pub fn home_timezone(conn: &Connection) -> Option<chrono_tz::Tz> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'timezone'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|name| name.trim().parse().ok())
+}
+
+/// Wall-clock time of `instant` in `timezone`, or in the system zone when there is none.
+pub fn wall_clock_at(
+    instant: chrono::DateTime<chrono::Utc>,
+    timezone: Option<chrono_tz::Tz>,
+) -> chrono::NaiveDateTime {
+    match timezone {
+        Some(tz) => instant.with_timezone(&tz).naive_local(),
+        None => instant.with_timezone(&chrono::Local).naive_local(),
+    }
+}
+
+/// Current wall-clock time in the home timezone. Logs and day buckets use this rather
+/// than the system clock, so travelling doesn't skip or double-count streak days.
+pub fn local_now(conn: &Connection) -> chrono::NaiveDateTime {
+    wall_clock_at(chrono::Utc::now(), home_timezone(conn))
+}
+
+/// `local_now` in the `logged_at` format.
+pub fn local_timestamp(conn: &Connection) -> String {
+    local_now(conn).format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+pub fn effective_today(conn: &Connection) -> chrono::NaiveDate {
+    effective_date(local_now(conn), day_rollover_hour(conn))
+}
+
+/// SQL for the day a `logged_at` column counts toward, the query-side twin of
+/// `effective_date`. Every per-day bucket of logs goes through this.
+pub fn log_day_sql(conn: &Connection, column: &str) -> String {
+    format!("DATE({}, '-{} hours')", column, day_rollover_hour(conn))
+}
+
+/// Distinct days with at least one log (after the day rollover), oldest first.
+pub fn active_dates(conn: &Connection) -> Result<Vec<chrono::NaiveDate>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT DISTINCT {} FROM exercise_logs ORDER BY 1",
+            log_day_sql(conn, "logged_at")
+        ))
+        .map_err(|e| e.to_string())?;
+    let dates = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .filter_map(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok())
+        .collect();
+    Ok(dates)
+}
+
+/// Rebuilds `user_stats` streak fields from the log table and returns (current, longest).
+/// The stored longest streak is never lowered, so imported records survive.
+pub fn recompute_streak(conn: &Connection) -> Result<(i32, i32), String> {
+    let dates = active_dates(conn)?;
+
+    let (current, longest) = compute_streaks(&dates, effective_today(conn));
+
+    let stored_longest: i32 = conn
+        .query_row(
+            "SELECT longest_streak FROM user_stats WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+    let longest = std::cmp::max(longest, stored_longest);
+    let last_date = dates.last().map(|d| d.format("%Y-%m-%d").to_string());
+
+    conn.execute(
+        "UPDATE user_stats SET current_streak = ?, longest_streak = ?, last_exercise_date = ? WHERE id = 1",
+        params![current, longest, last_date],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok((current, longest))
+}
+
+/// Stores the goal taking effect on `date`. The first change also records the previous
+/// goal as a baseline, so days before it are judged by the goal they actually had.
+pub fn record_daily_goal_change(
+    conn: &Connection,
+    previous_goal: i64,
+    new_goal: i64,
+    date: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO daily_goal_history (effective_date, goal_xp)
+         SELECT '0001-01-01', ? WHERE NOT EXISTS (SELECT 1 FROM daily_goal_history)",
+        params![previous_goal],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO daily_goal_history (effective_date, goal_xp) VALUES (?, ?)",
+        params![date, new_goal],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_streaks_with_gap() {
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let dates: Vec<chrono::NaiveDate> = [1, 2, 3, 7, 8, 9, 10]
+            .iter()
+            .map(|d| chrono::NaiveDate::from_ymd_opt(2024, 3, *d).unwrap())
+            .collect();
+        assert_eq!(compute_streaks(&dates, today), (4, 4));
+
+        // A stale run does not count as current
+        let later = chrono::NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        assert_eq!(compute_streaks(&dates, later), (0, 4));
+        assert_eq!(compute_streaks(&[], today), (0, 0));
+    }
+
+    #[test]
+    fn test_longest_run() {
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let dates: Vec<chrono::NaiveDate> = [1, 2, 5, 6, 7, 9, 10, 11].map(day).to_vec();
+        // Ties keep the earlier run
+        assert_eq!(longest_run(&dates), Some((day(5), day(7))));
+        assert_eq!(longest_run(&[day(4)]), Some((day(4), day(4))));
+        assert_eq!(longest_run(&[]), None);
+    }
+
+    #[test]
+    fn test_consistency_daily_beats_weekend_cramming() {
+        let start = chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(); // A Monday
+        let daily: Vec<chrono::NaiveDate> =
+            (0..28).map(|d| start + chrono::Duration::days(d)).collect();
+        let weekends: Vec<chrono::NaiveDate> = daily
+            .iter()
+            .copied()
+            .filter(|d| d.format("%a").to_string().starts_with('S'))
+            .collect();
+        let weekly: Vec<chrono::NaiveDate> = daily.iter().copied().step_by(7).collect();
+
+        let daily_score = consistency_score(&daily, 28);
+        let weekend_score = consistency_score(&weekends, 28);
+        assert_eq!(daily_score, 100);
+        assert!(weekend_score < daily_score);
+        // Evenly spaced sessions are regular even when rare
+        assert!(consistency_score(&weekly, 28) > weekend_score);
+
+        assert_eq!(consistency_score(&[], 28), 0);
+        assert!(consistency_score(&daily[..1], 28) < 10);
+    }
+
+    #[test]
+    fn test_streak_runs() {
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let dates: Vec<chrono::NaiveDate> = [1, 2, 3, 5, 8, 9].map(day).to_vec();
+        assert_eq!(
+            streak_runs(&dates),
+            vec![(day(1), day(3)), (day(5), day(5)), (day(8), day(9))]
+        );
+        assert!(streak_runs(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_today_in_home_timezone() {
+        use chrono::TimeZone;
+
+        // 23:30 UTC is still the 10th in New York but already the 11th in Tokyo
+        let instant = chrono::Utc
+            .with_ymd_and_hms(2024, 3, 10, 23, 30, 0)
+            .unwrap();
+        let new_york = wall_clock_at(instant, Some(chrono_tz::America::New_York));
+        let tokyo = wall_clock_at(instant, Some(chrono_tz::Asia::Tokyo));
+        assert_eq!(
+            new_york,
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 10)
+                .unwrap()
+                .and_hms_opt(19, 30, 0)
+                .unwrap()
+        );
+        assert_eq!(
+            tokyo.date(),
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 11).unwrap()
+        );
+
+        // The rollover hour applies on top of the home timezone
+        assert_eq!(
+            effective_date(tokyo, 9),
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_home_timezone_setting() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT)",
+            [],
+        )
+        .unwrap();
+        assert_eq!(home_timezone(&conn), None);
+
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES ('timezone', 'Europe/Berlin')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(home_timezone(&conn), Some(chrono_tz::Europe::Berlin));
+
+        conn.execute(
+            "UPDATE settings SET value = 'Mars/Olympus' WHERE key = 'timezone'",
+            [],
+        )
+        .unwrap();
+        assert_eq!(home_timezone(&conn), None);
+    }
+
+    #[test]
+    fn test_log_day_sql_matches_effective_date() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT)",
+            [],
+        )
+        .unwrap();
+        let day_of = |at: &str| -> String {
+            conn.query_row(
+                &format!("SELECT {}", log_day_sql(&conn, "?")),
+                params![at],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(day_of("2024-03-03 02:00:00"), "2024-03-03");
+
+        conn.execute(
+            "INSERT INTO settings (key, value) VALUES ('day_rollover_hour', '4')",
+            [],
+        )
+        .unwrap();
+        for at in ["2024-03-03 02:00:00", "2024-03-03 04:00:00"] {
+            let parsed = chrono::NaiveDateTime::parse_from_str(at, "%Y-%m-%d %H:%M:%S").unwrap();
+            assert_eq!(
+                day_of(at),
+                effective_date(parsed, 4).format("%Y-%m-%d").to_string()
+            );
+        }
+        assert_eq!(day_of("2024-03-03 02:00:00"), "2024-03-02");
+    }
+}
@@ -21,7 +21,7 @@ import { useKeyboardShortcuts } from "./hooks/useKeyboardShortcuts";
 const SIDEBAR_WIDTH = 220;
 
 function AppContent() {
-  const { quickLogOpen, closeQuickLog } = useGlobalHotkey();
+  const { quickLogOpen, quickLogExerciseId, closeQuickLog } = useGlobalHotkey();
   const { showOnboarding, completeOnboarding } = useOnboarding();
   const { refreshExercises } = useExercises();
 
@@ -71,7 +71,12 @@ function AppContent() {
       </Box>
 
       {/* Global Quick Log Dialog (Ctrl+Shift+Alt+G) */}
-      <QuickLogDialog open={quickLogOpen} onClose={closeQuickLog} soundEnabled={true} />
+      <QuickLogDialog
+        open={quickLogOpen}
+        onClose={closeQuickLog}
+        soundEnabled={true}
+        initialExerciseId={quickLogExerciseId}
+      />
 
       {/* Keyboard Shortcuts Dialog (Ctrl+/) */}
       <KeyboardShortcutsDialog
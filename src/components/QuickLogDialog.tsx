@@ -26,12 +26,14 @@ interface QuickLogDialogProps {
   open: boolean;
   onClose: () => void;
   soundEnabled?: boolean;
+  initialExerciseId?: number | null;
 }
 
 export default function QuickLogDialog({
   open,
   onClose,
   soundEnabled = true,
+  initialExerciseId = null,
 }: QuickLogDialogProps) {
   const { exercises, logExercise, refreshData } = useExercises();
   const { refreshStats } = useUser();
@@ -44,11 +46,11 @@ export default function QuickLogDialog({
   // Reset state when dialog opens
   useEffect(() => {
     if (open) {
-      setSelectedExercise(null);
+      setSelectedExercise(exercises.find((e) => e.id === initialExerciseId) ?? null);
       setReps(10);
       setLastResult(null);
     }
-  }, [open]);
+  }, [open, initialExerciseId]);
 
   // Focus on reps input when exercise is selected
   useEffect(() => {
@@ -3,15 +3,24 @@ import { listen } from "@tauri-apps/api/event";
 
 export function useGlobalHotkey() {
   const [quickLogOpen, setQuickLogOpen] = useState(false);
+  const [quickLogExerciseId, setQuickLogExerciseId] = useState<number | null>(null);
 
   useEffect(() => {
     // Listen for the global-quick-log event from Tauri
     const unlisten = listen("global-quick-log", () => {
+      setQuickLogExerciseId(null);
+      setQuickLogOpen(true);
+    });
+
+    // Tray "Custom..." items open the dialog with an exercise pre-selected
+    const unlistenExercise = listen<{ exercise_id: number }>("quick-log-exercise", (event) => {
+      setQuickLogExerciseId(event.payload.exercise_id);
       setQuickLogOpen(true);
     });
 
     return () => {
       unlisten.then((fn) => fn());
+      unlistenExercise.then((fn) => fn());
     };
   }, []);
 
@@ -21,6 +30,7 @@ export function useGlobalHotkey() {
 
   return {
     quickLogOpen,
+    quickLogExerciseId,
     setQuickLogOpen,
     closeQuickLog,
   };
@@ -7,6 +7,9 @@ export interface Exercise {
   current_level: number;  // Level for this exercise (1-99)
   icon: string | null;
   created_at: string;
+  color?: string | null;  // "#RRGGBB" accent, null for the theme default
+  remind?: boolean;  // false when opted out of exercise reminders
+  last_logged?: string | null;  // Most recent log time, null if never logged
 }
 
 // Default exercise for onboarding selection
@@ -31,6 +34,9 @@ export interface LogExerciseResult {
   xp_earned: number;
   new_exercise_level: number;
   leveled_up: boolean;
+  comeback?: boolean;
+  combo_awarded?: boolean;  // This log completed the day's combo; xp_earned includes the bonus
+  xp_multiplier?: number;  // From an active XP event, 1 otherwise
 }
 
 // User stats - totals calculated from all exercises
@@ -51,6 +57,7 @@ export interface Achievement {
   description: string | null;
   icon: string | null;
   unlocked_at: string | null;
+  progress?: [number, number] | null;  // [current, target] while locked
 }
 
 // Settings
@@ -60,6 +67,7 @@ export interface Settings {
   sound_enabled: boolean;
   daily_goal_xp: number;
   theme_mode?: string;
+  launch_on_startup?: boolean;  // Whether the OS starts GeekFit at login
 }
 
 // Title tiers based on total level (sum of all exercise levels)